@@ -1893,10 +1893,267 @@ pub struct IosConfig {
   ///
   /// Note that you need to recreate the iOS project for the changes to be applied.
   pub frameworks: Option<Vec<String>>,
+  /// Frameworks of `frameworks` that are not linked in a build configuration, keyed by the configuration name
+  /// (`debug` or `release`), e.g. `{ "release": ["DebugOverlay.framework"] }` for a debug-only framework.
+  /// Frameworks are linked in every configuration by default.
+  ///
+  /// The frameworks linked in some configurations only are passed to the linker
+  /// and are not embedded, so XCFrameworks are not supported.
+  /// Note that you need to recreate the iOS project for the changes to be applied.
+  #[serde(alias = "excluded-frameworks")]
+  pub excluded_frameworks: Option<HashMap<String, Vec<String>>>,
   /// The development team. This value is required for iOS development because code signing is enforced.
   /// The `APPLE_DEVELOPMENT_TEAM` environment variable can be set to overwrite it.
   #[serde(alias = "development-team")]
   pub development_team: Option<String>,
+  /// The development team per build configuration, keyed by the configuration name (`debug` or `release`),
+  /// for projects signing debug and release builds with different Apple teams.
+  /// Configurations without an entry use the `developmentTeam` value.
+  ///
+  /// The `APPLE_DEVELOPMENT_TEAM` environment variable can be set to overwrite it.
+  /// Note that you need to recreate the iOS project for the changes to be applied.
+  #[serde(alias = "development-teams")]
+  pub development_teams: Option<HashMap<String, String>>,
+  /// Glob pattern, e.g. `MyApp-*-dist`, selecting the installed provisioning profile used to sign the app
+  /// when the `IOS_MOBILE_PROVISION` environment variable is not set.
+  /// When several profiles match the name and the bundle identifier, the latest expiring one is used.
+  ///
+  /// The `IOS_PROVISIONING_PROFILE_NAME` environment variable can be set to overwrite it.
+  #[serde(alias = "provisioning-profile-name")]
+  pub provisioning_profile_name: Option<String>,
+  /// The device families supported by the application. Defaults to both iPhone and iPad.
+  ///
+  /// This sets the `TARGETED_DEVICE_FAMILY` build setting of the Xcode project.
+  /// Note that you need to recreate the iOS project for the changes to be applied.
+  #[serde(alias = "device-families")]
+  pub device_families: Option<Vec<IosDeviceFamily>>,
+  /// Whether to enable bitcode. Defaults to `false`.
+  ///
+  /// Bitcode is deprecated since Xcode 14, only enable it for legacy distribution flows that require it.
+  /// This sets the `ENABLE_BITCODE` build setting of the Xcode project.
+  /// Note that you need to recreate the iOS project for the changes to be applied.
+  #[serde(alias = "enable-bitcode")]
+  pub enable_bitcode: Option<bool>,
+  /// Whether to strip the debug symbols of the app binary in release builds, to reduce its size.
+  /// Defaults to the Xcode behavior.
+  ///
+  /// This sets the `STRIP_INSTALLED_PRODUCT` and `DEPLOYMENT_POSTPROCESSING` build settings of the release builds of `tauri ios build`,
+  /// debug builds always keep their symbols. A dSYM is still generated so crash reports can be symbolicated.
+  #[serde(alias = "strip-symbols")]
+  pub strip_symbols: Option<bool>,
+  /// Path to the directory where the Xcode project is generated, relative to the Tauri directory.
+  /// Defaults to `gen/apple`.
+  ///
+  /// Note that you need to recreate the iOS project for the changes to be applied.
+  #[serde(alias = "project-dir")]
+  pub project_dir: Option<String>,
+  /// A shell command to run in the generated Xcode project directory after `tauri ios init`,
+  /// e.g. `pod install`. The initialization fails if the command exits with a non-zero status.
+  #[serde(alias = "post-init-script")]
+  pub post_init_script: Option<String>,
+  /// Additional Info.plist sources merged into the app Info.plist, relative to the Tauri directory.
+  /// Each file can be a `.plist`, a `.json` or a `.yaml` file.
+  ///
+  /// The files are merged in order after `Info.plist` and `Info.ios.plist`,
+  /// so later entries override the top-level keys of the previous ones.
+  /// Set a key to the `$delete` string to remove it from the app Info.plist instead.
+  ///
+  /// JSON and YAML values map to strings, booleans, integers, reals, arrays and dictionaries, and `null` is rejected.
+  /// Dates and data values must be written in a `.plist` file.
+  #[serde(alias = "info-plist-files")]
+  pub info_plist_files: Option<Vec<PathBuf>>,
+  /// Info.plist sources of a build configuration, keyed by the configuration name (`debug` or `release`),
+  /// e.g. `{ "release": ["Info.release.plist"] }`. Paths are relative to the Tauri directory.
+  ///
+  /// The files of the active configuration are merged instead of `infoPlistFiles`,
+  /// which is used for the configurations without an entry. The keys set by the files of one configuration
+  /// are restored to their previous value in the builds of another one.
+  #[serde(alias = "info-plist-configuration-files")]
+  pub info_plist_configuration_files: Option<HashMap<String, Vec<PathBuf>>>,
+  /// URL schemes opening the app, e.g. `myapp` for `myapp://` links,
+  /// added to the `CFBundleURLTypes` Info.plist entry.
+  #[serde(alias = "url-schemes")]
+  pub url_schemes: Option<Vec<String>>,
+  /// Associated domains of the app, e.g. `applinks:example.com` for universal links,
+  /// added to the `com.apple.developer.associated-domains` entitlement.
+  ///
+  /// Each entry must be prefixed with its service: `applinks`, `webcredentials`, `activitycontinuation` or `appclips`.
+  #[serde(alias = "associated-domains")]
+  pub associated_domains: Option<Vec<String>>,
+  /// Entitlements of the Xcode project targets, keyed by the target name, e.g. the app target and a share extension,
+  /// as paths to `.entitlements`, `.plist` or `.json` files relative to the Tauri directory.
+  ///
+  /// The entries are merged into the entitlements file of each target, set by its `CODE_SIGN_ENTITLEMENTS` build setting,
+  /// when the Xcode project is generated with `tauri ios init`, after the `postInitScript` which can add the extension targets.
+  /// The keys removed from the configuration are removed from the entitlements files on the next generation.
+  #[serde(alias = "target-entitlements")]
+  pub target_entitlements: Option<HashMap<String, PathBuf>>,
+  /// The minimum Xcode version required to build the app, e.g. `15.4`.
+  /// `tauri ios dev` and `tauri ios build` fail early when the active Xcode, selected with `xcode-select`
+  /// or the `DEVELOPER_DIR` environment variable, is older.
+  #[serde(alias = "minimum-xcode-version")]
+  pub minimum_xcode_version: Option<String>,
+  /// A suffix appended to the `identifier` to build the app bundle identifier, e.g. `.internal`,
+  /// to install internal builds side by side with the production app.
+  /// The suffixed identifier is also used to select the provisioning profile.
+  #[serde(alias = "bundle-id-suffix")]
+  pub bundle_id_suffix: Option<String>,
+  /// The privacy manifest of the app, written to `PrivacyInfo.xcprivacy` in the Xcode project.
+  /// Apple requires it to declare the collected data and the reasons the app uses the required reason APIs.
+  ///
+  /// See <https://developer.apple.com/documentation/bundleresources/privacy_manifest_files>.
+  #[serde(alias = "privacy-manifest")]
+  pub privacy_manifest: Option<IosPrivacyManifest>,
+  /// A shell command to run in the generated Xcode project directory for each dSYM bundle of the archive
+  /// created by `tauri ios build`, e.g. to upload it to a crash reporting service.
+  /// The `TAURI_IOS_DSYM_PATH` environment variable is set to the dSYM path and `TAURI_IOS_DSYM_UUIDS`
+  /// to its comma-separated UUIDs. The build fails if the command exits with a non-zero status.
+  #[serde(alias = "dsym-upload-script")]
+  pub dsym_upload_script: Option<String>,
+  /// The On-Demand Resources of the app, files tagged so the App Store hosts them
+  /// and the app downloads them when needed instead of shipping them in the app bundle.
+  ///
+  /// See <https://developer.apple.com/library/archive/documentation/FileManagement/Conceptual/On_Demand_Resources_Guide>.
+  /// Note that you need to recreate the iOS project for the changes to be applied.
+  #[serde(alias = "on-demand-resources")]
+  pub on_demand_resources: Option<IosOnDemandResources>,
+  /// The launch screen of the app, a solid background color with an optional centered image,
+  /// generated as the `LaunchScreen.storyboard` of the Xcode project.
+  /// Defaults to the system background color.
+  ///
+  /// Note that you need to recreate the iOS project for the changes to be applied.
+  #[serde(alias = "launch-screen")]
+  pub launch_screen: Option<IosLaunchScreen>,
+  /// The Metal shader compiler options of the `tauri ios dev` and `tauri ios build` builds.
+  /// Options that are not set keep the Xcode defaults.
+  pub metal: Option<IosMetalConfig>,
+  /// Filters the `bundle > resources` copied to the asset directory of the Xcode project,
+  /// e.g. to leave out source maps or development-only files.
+  pub assets: Option<IosAssets>,
+}
+
+/// Glob patterns selecting the resources bundled with an iOS app.
+///
+/// The patterns are matched against the path of the resource in the app bundle, e.g. `*.map` or `dev/**`.
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct IosAssets {
+  /// Only the resources matching one of these patterns are copied. Defaults to all resources.
+  pub include: Option<Vec<String>>,
+  /// The resources matching one of these patterns are not copied, even when they match `include`.
+  pub exclude: Option<Vec<String>>,
+}
+
+/// The Metal shader compiler options of an iOS app.
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct IosMetalConfig {
+  /// The shader optimization level, `default`, `none` or `size`, mapped to `MTL_OPTIMIZATION_LEVEL`.
+  #[serde(alias = "optimization-level")]
+  pub optimization_level: Option<String>,
+  /// The debug information of the compiled shaders, `none`, `symbols` or `source`, mapped to `MTL_ENABLE_DEBUG_INFO`.
+  /// `source` also embeds the shader source, required to debug the shaders in Xcode.
+  /// Xcode defaults to `source` in debug builds and `none` in release builds.
+  #[serde(alias = "debug-info")]
+  pub debug_info: Option<String>,
+}
+
+/// The launch screen of an iOS app, shown while the app starts.
+#[skip_serializing_none]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct IosLaunchScreen {
+  /// The background color, as a `#RRGGBB` hex color, e.g. `#1F2937`.
+  /// Defaults to the system background color, which follows the light or dark appearance.
+  #[serde(alias = "background-color")]
+  pub background_color: Option<String>,
+  /// Path to a PNG image centered on the launch screen, e.g. a logo, relative to the Tauri directory.
+  /// The image is shown at a third of its pixel size, e.g. a 600x600 image covers 200x200 points.
+  pub image: Option<PathBuf>,
+}
+
+/// The On-Demand Resources of an iOS app.
+///
+/// Tags that are neither installed with the app nor prefetched are only downloaded when the app requests them.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct IosOnDemandResources {
+  /// The files and folders of each asset tag, relative to the Tauri directory,
+  /// e.g. `{ "level-2": ["assets/level-2"] }`.
+  pub tags: HashMap<String, Vec<PathBuf>>,
+  /// Tags downloaded with the app install, sets the `ON_DEMAND_RESOURCES_INITIAL_INSTALL_TAGS` build setting.
+  #[serde(default, alias = "initial-install")]
+  pub initial_install: Vec<String>,
+  /// Tags downloaded in order right after the app install, sets the `ON_DEMAND_RESOURCES_PREFETCH_ORDER` build setting.
+  #[serde(default)]
+  pub prefetch: Vec<String>,
+}
+
+/// The privacy manifest of an iOS app.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct IosPrivacyManifest {
+  /// Whether the app uses data for tracking as defined by the App Tracking Transparency framework.
+  #[serde(default)]
+  pub tracking: bool,
+  /// The internet domains the app connects to that engage in tracking.
+  #[serde(default, alias = "tracking-domains")]
+  pub tracking_domains: Vec<String>,
+  /// The types of data collected by the app.
+  #[serde(default, alias = "collected-data-types")]
+  pub collected_data_types: Vec<IosPrivacyCollectedDataType>,
+  /// The required reason APIs used by the app.
+  #[serde(default, alias = "accessed-api-types")]
+  pub accessed_api_types: Vec<IosPrivacyAccessedApiType>,
+}
+
+/// A type of data collected by an iOS app.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct IosPrivacyCollectedDataType {
+  /// The collected data type, e.g. `NSPrivacyCollectedDataTypeEmailAddress`.
+  #[serde(rename = "type")]
+  pub data_type: String,
+  /// Whether the data is linked to the identity of the user.
+  #[serde(default)]
+  pub linked: bool,
+  /// Whether the data is used to track the user.
+  #[serde(default)]
+  pub tracking: bool,
+  /// The reasons the data is collected, e.g. `NSPrivacyCollectedDataTypePurposeAppFunctionality`.
+  #[serde(default)]
+  pub purposes: Vec<String>,
+}
+
+/// A required reason API used by an iOS app.
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct IosPrivacyAccessedApiType {
+  /// The API category, e.g. `NSPrivacyAccessedAPICategoryUserDefaults`.
+  #[serde(rename = "type")]
+  pub api_type: String,
+  /// The approved reason codes for using the API, e.g. `CA92.1`.
+  pub reasons: Vec<String>,
+}
+
+/// An iOS device family.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum IosDeviceFamily {
+  /// iPhone and iPod touch devices.
+  Iphone,
+  /// iPad devices.
+  Ipad,
 }
 
 /// General configuration for the iOS target.
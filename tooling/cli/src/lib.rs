@@ -188,10 +188,24 @@ where
       }
     });
     log::error!("{message}");
-    exit(1);
+    exit(exit_code(&e));
   }
 }
 
+/// Resolves the process exit code for an error returned by [`try_run`].
+///
+/// Known failure categories (see `mobile::ios::ErrorKind`) map to distinct exit codes,
+/// everything else exits with `1`.
+#[cfg(target_os = "macos")]
+fn exit_code(e: &anyhow::Error) -> i32 {
+  mobile::ios::exit_code(e).unwrap_or(1)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn exit_code(_e: &anyhow::Error) -> i32 {
+  1
+}
+
 /// Run the Tauri CLI with the passed arguments.
 ///
 /// It is similar to [`run`], but instead of exiting on an error, it returns a result.
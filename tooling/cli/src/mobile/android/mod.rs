@@ -29,8 +29,8 @@ use sublime_fuzzy::best_match;
 use super::{
   ensure_init, get_app,
   init::{command as init_command, configure_cargo},
-  log_finished, read_options, CliOptions, OptionsHandle, Target as MobileTarget,
-  MIN_DEVICE_MATCH_SCORE,
+  log_finished, min_device_match_score, read_options, CliOptions, OptionsHandle,
+  Target as MobileTarget,
 };
 use crate::{helpers::config::Config as TauriConfig, Result};
 
@@ -187,7 +187,7 @@ fn adb_device_prompt<'a>(env: &'_ Env, target: Option<&str>) -> Result<Device<'a
         .max_by_key(|(_, score)| *score)
         // we already checked the list is not empty
         .unwrap();
-      if score > MIN_DEVICE_MATCH_SCORE {
+      if score > min_device_match_score() {
         device
       } else {
         anyhow::bail!("Could not find an Android device matching {t}")
@@ -231,7 +231,7 @@ fn emulator_prompt(env: &'_ Env, target: Option<&str>) -> Result<emulator::Emula
         .max_by_key(|(_, score)| *score)
         // we already checked the list is not empty
         .unwrap();
-      if score > MIN_DEVICE_MATCH_SCORE {
+      if score > min_device_match_score() {
         device
       } else {
         anyhow::bail!("Could not find an Android Emulator matching {t}")
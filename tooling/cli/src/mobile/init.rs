@@ -39,7 +39,10 @@ pub fn command(
   let tauri_init_config = TauriInitConfig {
     #[cfg(target_os = "macos")]
     ios: {
-      let (keychain, provisioning_profile) = super::ios::signing_from_env()?;
+      let tauri_config = get_tauri_config(target.platform_target(), None)?;
+      let tauri_config_guard = tauri_config.lock().unwrap();
+      let (keychain, provisioning_profile) =
+        super::ios::signing_from_env(tauri_config_guard.as_ref().unwrap(), false)?;
       super::ios::init_config(keychain.as_ref(), provisioning_profile.as_ref())?
     },
   };
@@ -217,9 +220,43 @@ pub fn exec(
     #[cfg(target_os = "macos")]
     // Generate Xcode project
     Target::Ios => {
-      let (config, metadata) =
-        super::ios::get_config(&app, tauri_config_, None, &Default::default());
+      super::ios::frameworks_manifest::install(&crate::helpers::app_paths::tauri_dir())?;
+      let (config, metadata) = super::ios::get_config(
+        &app,
+        tauri_config_,
+        None,
+        &Default::default(),
+        None,
+        Default::default(),
+      )?;
       map.insert("apple", &config);
+      map.insert(
+        "ios-targeted-device-family",
+        super::ios::targeted_device_family(tauri_config_)?,
+      );
+      map.insert(
+        "ios-configuration-settings",
+        super::ios::configuration_settings(&config, tauri_config_)?,
+      );
+      map.insert(
+        "ios-enable-bitcode",
+        super::ios::enable_bitcode(tauri_config_),
+      );
+      map.insert(
+        "ios-on-demand-resources",
+        super::ios::on_demand_resources::tagged_sources(tauri_config_),
+      );
+      map.insert(
+        "ios-on-demand-resources-settings",
+        super::ios::on_demand_resources::build_settings(tauri_config_),
+      );
+      let tauri_dir = crate::helpers::app_paths::tauri_dir();
+      map.insert(
+        "ios-launch-screen",
+        super::ios::launch_screen::template_data(tauri_config_, &tauri_dir)?,
+      );
+      super::ios::launch_screen::write_image(&config.project_dir(), &tauri_dir, tauri_config_)?;
+      super::ios::privacy_manifest::write(&config, tauri_config_)?;
       super::ios::project::gen(
         &config,
         &metadata,
@@ -229,6 +266,10 @@ pub fn exec(
         reinstall_deps,
         skip_targets_install,
       )?;
+      if let Some(script) = &tauri_config_.bundle.ios.post_init_script {
+        super::ios::project::run_post_init_script(script, &config.project_dir())?;
+      }
+      super::ios::target_entitlements::apply(&config, tauri_config_)?;
       app
     }
   };
@@ -0,0 +1,202 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::ErrorKind;
+use crate::{helpers::app_paths::tauri_dir, CommandExt, Result};
+
+use anyhow::Context;
+use clap::Parser;
+use tauri_utils::config::parse::ConfigFormat;
+
+use std::{
+  fs::{create_dir_all, read_to_string, remove_dir_all, write},
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+/// Directory, relative to the Tauri directory, where vendor frameworks are copied to.
+pub const VENDOR_FRAMEWORKS_DIR: &str = "Frameworks";
+
+#[derive(Debug, Clone, Parser)]
+#[clap(
+  about = "Add a vendor framework or xcframework to the iOS project",
+  long_about = "Add a vendor framework or xcframework to the iOS project.
+The framework is copied to the `src-tauri/Frameworks` directory and added to the `bundle > iOS > frameworks` configuration."
+)]
+pub struct Options {
+  /// Path to the `.framework` or `.xcframework` bundle.
+  pub path: PathBuf,
+}
+
+pub fn command(options: Options) -> Result<()> {
+  validate_framework(&options.path)?;
+
+  let tauri_dir = tauri_dir();
+  let file_name = options.path.file_name().unwrap().to_string_lossy();
+  let vendor_dir = tauri_dir.join(VENDOR_FRAMEWORKS_DIR);
+  let dest = vendor_dir.join(&*file_name);
+
+  if dunce::canonicalize(&options.path)? != dunce::canonicalize(&dest).unwrap_or_default() {
+    create_dir_all(&vendor_dir)?;
+    if dest.exists() {
+      remove_dir_all(&dest)
+        .with_context(|| format!("failed to remove existing framework {}", dest.display()))?;
+    }
+    // use `cp` to preserve the symlinks of macOS style framework bundles
+    Command::new("cp")
+      .arg("-R")
+      .arg(&options.path)
+      .arg(&vendor_dir)
+      .output_ok()
+      .with_context(|| format!("failed to copy framework to {}", vendor_dir.display()))?;
+    log::info!(action = "Copied"; "{} to {}", file_name, dunce::simplified(&dest).display());
+  }
+
+  let framework = format!("{VENDOR_FRAMEWORKS_DIR}/{file_name}");
+  add_to_config(&tauri_dir, &framework)?;
+
+  Ok(())
+}
+
+/// Checks that the path points to a framework or xcframework bundle.
+fn validate_framework(path: &Path) -> Result<()> {
+  let is_framework = path
+    .extension()
+    .is_some_and(|ext| ext == "framework" || ext == "xcframework");
+  if !is_framework {
+    return Err(
+      anyhow::anyhow!(
+        "{} is not a framework, expected a `.framework` or `.xcframework` bundle",
+        path.display()
+      )
+      .context(ErrorKind::InvalidConfig),
+    );
+  }
+
+  let has_info_plist = path.join("Info.plist").is_file()
+    || path.join("Resources").join("Info.plist").is_file()
+    || path
+      .join("Versions")
+      .join("Current")
+      .join("Resources")
+      .join("Info.plist")
+      .is_file();
+  if !path.is_dir() || !has_info_plist {
+    return Err(
+      anyhow::anyhow!(
+        "{} is not a valid framework bundle, it must be a directory containing an Info.plist file",
+        path.display()
+      )
+      .context(ErrorKind::InvalidConfig),
+    );
+  }
+
+  Ok(())
+}
+
+/// Adds the framework to the `bundle > iOS > frameworks` config of the Tauri configuration file.
+fn add_to_config(tauri_dir: &Path, framework: &str) -> Result<()> {
+  let json_path = tauri_dir.join(ConfigFormat::Json.into_file_name());
+  let toml_path = tauri_dir.join(ConfigFormat::Toml.into_file_name());
+
+  let (path, added) = if json_path.exists() {
+    let mut config: serde_json::Value = serde_json::from_str(&read_to_string(&json_path)?)
+      .with_context(|| format!("failed to parse {}", json_path.display()))?;
+    let added = insert_json_framework(&mut config, framework);
+    if added {
+      write(&json_path, serde_json::to_string_pretty(&config)? + "\n")?;
+    }
+    (json_path, added)
+  } else if toml_path.exists() {
+    let mut config = read_to_string(&toml_path)?
+      .parse::<toml_edit::Document>()
+      .with_context(|| format!("failed to parse {}", toml_path.display()))?;
+    let added = insert_toml_framework(&mut config, framework);
+    if added {
+      write(&toml_path, config.to_string())?;
+    }
+    (toml_path, added)
+  } else {
+    log::warn!(
+      "Could not update the Tauri configuration file, add `{framework}` to the `bundle > iOS > frameworks` config manually"
+    );
+    return Ok(());
+  };
+
+  if added {
+    log::info!(action = "Added"; "`{framework}` to `bundle > iOS > frameworks` in {}", dunce::simplified(&path).display());
+  } else {
+    log::info!("`{framework}` is already in `bundle > iOS > frameworks`");
+  }
+
+  Ok(())
+}
+
+fn insert_json_framework(config: &mut serde_json::Value, framework: &str) -> bool {
+  let Some(root) = config.as_object_mut() else {
+    return false;
+  };
+  let bundle = root
+    .entry("bundle")
+    .or_insert_with(|| serde_json::Value::Object(Default::default()));
+  let Some(bundle) = bundle.as_object_mut() else {
+    return false;
+  };
+  let key = if bundle.contains_key("ios") {
+    "ios"
+  } else {
+    "iOS"
+  };
+  let ios = bundle
+    .entry(key)
+    .or_insert_with(|| serde_json::Value::Object(Default::default()));
+  let Some(frameworks) = ios.as_object_mut().and_then(|ios| {
+    ios
+      .entry("frameworks")
+      .or_insert_with(|| serde_json::Value::Array(Vec::new()))
+      .as_array_mut()
+  }) else {
+    return false;
+  };
+
+  if frameworks.iter().any(|f| f.as_str() == Some(framework)) {
+    false
+  } else {
+    frameworks.push(framework.into());
+    true
+  }
+}
+
+fn insert_toml_framework(config: &mut toml_edit::Document, framework: &str) -> bool {
+  let bundle = config.entry("bundle").or_insert(toml_edit::table());
+  let Some(bundle) = bundle.as_table_like_mut() else {
+    return false;
+  };
+  let key = if bundle.contains_key("ios") {
+    "ios"
+  } else {
+    "iOS"
+  };
+  let Some(ios) = bundle
+    .entry(key)
+    .or_insert(toml_edit::table())
+    .as_table_like_mut()
+  else {
+    return false;
+  };
+  let Some(frameworks) = ios
+    .entry("frameworks")
+    .or_insert(toml_edit::value(toml_edit::Array::new()))
+    .as_array_mut()
+  else {
+    return false;
+  };
+
+  if frameworks.iter().any(|f| f.as_str() == Some(framework)) {
+    false
+  } else {
+    frameworks.push(framework);
+    true
+  }
+}
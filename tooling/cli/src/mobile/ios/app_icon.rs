@@ -0,0 +1,132 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Fills the app icon asset catalog of the Xcode project from the `bundle > icon` configuration.
+
+use super::project::TEMPLATE_DIR;
+use crate::Result;
+
+use anyhow::Context;
+use image::{imageops::FilterType, DynamicImage, ImageBuffer, Rgba};
+use serde::Deserialize;
+
+use std::{
+  fs::{create_dir_all, read, read_to_string, write},
+  path::{Path, PathBuf},
+};
+
+const APP_ICON_SET: &str = "Assets.xcassets/AppIcon.appiconset";
+const DEFAULT_CONTENTS: &str = include_str!(concat!(
+  env!("CARGO_MANIFEST_DIR"),
+  "/templates/mobile/ios/Assets.xcassets/AppIcon.appiconset/Contents.json"
+));
+
+#[derive(Deserialize)]
+struct Contents {
+  images: Vec<Image>,
+}
+
+#[derive(Deserialize)]
+struct Image {
+  size: String,
+  scale: String,
+  filename: Option<String>,
+}
+
+impl Image {
+  /// The icon width and height in pixels, e.g. 120 for a 60x60@2x icon.
+  fn pixel_size(&self) -> Option<u32> {
+    let points = self.size.split('x').next()?.parse::<f32>().ok()?;
+    let scale = self.scale.trim_end_matches('x').parse::<f32>().ok()?;
+    Some((points * scale) as u32)
+  }
+}
+
+/// Generates the icons of the app icon set that are missing or still the placeholders of the project template,
+/// using the exact size icon of `icons` when there is one and resizing the largest icon otherwise.
+/// Other existing icons, e.g. generated by `tauri icon`, are never overwritten.
+pub fn populate(project_dir: &Path, tauri_dir: &Path, icons: &[String]) -> Result<()> {
+  let icon_set = project_dir.join(APP_ICON_SET);
+  let contents_path = icon_set.join("Contents.json");
+  if !contents_path.exists() {
+    create_dir_all(&icon_set)?;
+    write(&contents_path, DEFAULT_CONTENTS)?;
+  }
+  let contents: Contents = serde_json::from_str(&read_to_string(&contents_path)?)
+    .with_context(|| format!("failed to parse {}", contents_path.display()))?;
+
+  let placeholders = contents
+    .images
+    .iter()
+    .filter_map(|image| {
+      let filename = image.filename.as_ref()?;
+      let path = icon_set.join(filename);
+      is_placeholder(&path, filename).then_some((path, image))
+    })
+    .collect::<Vec<_>>();
+  if placeholders.is_empty() {
+    return Ok(());
+  }
+
+  let sources = icons
+    .iter()
+    .map(|icon| tauri_dir.join(icon))
+    .filter(|path| path.extension().is_some_and(|ext| ext == "png"))
+    .filter_map(|path| {
+      let (width, height) = image::image_dimensions(&path).ok()?;
+      (width == height).then_some((path, width))
+    })
+    .collect::<Vec<(PathBuf, u32)>>();
+  let Some((largest, largest_size)) = sources.iter().max_by_key(|(_, size)| *size) else {
+    log::warn!(
+      "No square PNG icon found in `bundle > icon`, the iOS app icons are missing. Run `tauri icon` to generate them."
+    );
+    return Ok(());
+  };
+  let largest_image =
+    image::open(largest).with_context(|| format!("failed to read {}", largest.display()))?;
+
+  for (path, image) in placeholders {
+    let Some(size) = image.pixel_size() else {
+      log::warn!(
+        "Skipping the iOS app icon {} with an invalid size",
+        path.display()
+      );
+      continue;
+    };
+    let icon = match sources.iter().find(|(_, s)| *s == size) {
+      Some((source, _)) => {
+        image::open(source).with_context(|| format!("failed to read {}", source.display()))?
+      }
+      None => {
+        if size > *largest_size {
+          log::warn!(
+            "No {size}x{size} icon found in `bundle > icon`, upscaling {} for the iOS app icon",
+            largest.display()
+          );
+        }
+        largest_image.resize_exact(size, size, FilterType::Lanczos3)
+      }
+    };
+    // iOS app icons must not have transparency
+    let mut opaque = ImageBuffer::from_pixel(size, size, Rgba([255, 255, 255, 255]));
+    image::imageops::overlay(&mut opaque, &icon, 0, 0);
+    DynamicImage::ImageRgba8(opaque)
+      .save(&path)
+      .with_context(|| format!("failed to write {}", path.display()))?;
+    log::info!(action = "iOS"; "Created app icon {}", dunce::simplified(&path).display());
+  }
+
+  Ok(())
+}
+
+/// Whether the icon is missing or still the placeholder icon of the project template.
+fn is_placeholder(path: &Path, filename: &str) -> bool {
+  match read(path) {
+    Ok(contents) => TEMPLATE_DIR
+      .get_file(Path::new(APP_ICON_SET).join(filename))
+      .is_some_and(|template| template.contents() == contents),
+    Err(_) => true,
+  }
+}
@@ -0,0 +1,134 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Copies the `bundle > resources` to the asset directory of the Xcode project,
+//! filtered by the `bundle > iOS > assets` patterns.
+
+use super::ErrorKind;
+use crate::{helpers::config::Config as TauriConfig, Result};
+
+use anyhow::Context;
+use glob::Pattern;
+use tauri_utils::{
+  config::{BundleResources, IosAssets},
+  resources::ResourcePaths,
+};
+
+use std::{
+  fs::{copy, create_dir_all, remove_file},
+  path::Path,
+};
+
+/// The compiled `bundle > iOS > assets` patterns.
+struct Filter {
+  include: Option<Vec<Pattern>>,
+  exclude: Vec<Pattern>,
+}
+
+impl Filter {
+  fn new(assets: Option<&IosAssets>) -> Result<Self> {
+    let compile = |key: &str, patterns: &[String]| {
+      patterns
+        .iter()
+        .map(|pattern| {
+          Pattern::new(pattern)
+            .with_context(|| {
+              format!("`{pattern}` in `bundle > iOS > assets > {key}` is not a valid glob pattern")
+            })
+            .context(ErrorKind::InvalidConfig)
+        })
+        .collect::<Result<Vec<_>>>()
+    };
+    Ok(Self {
+      include: assets
+        .and_then(|assets| assets.include.as_deref())
+        .map(|patterns| compile("include", patterns))
+        .transpose()?,
+      exclude: compile(
+        "exclude",
+        assets
+          .and_then(|assets| assets.exclude.as_deref())
+          .unwrap_or_default(),
+      )?,
+    })
+  }
+
+  fn is_included(&self, target: &Path) -> bool {
+    self.include.as_ref().map_or(true, |include| {
+      include.iter().any(|p| p.matches_path(target))
+    }) && !self.exclude.iter().any(|p| p.matches_path(target))
+  }
+}
+
+/// Checks that the `bundle > iOS > assets` patterns are valid glob patterns.
+pub fn validate(assets: &IosAssets) -> Result<()> {
+  Filter::new(Some(assets)).map(|_| ())
+}
+
+/// Copies the resources selected by the `bundle > iOS > assets` patterns to the asset directory,
+/// removing the previously copied resources that are now excluded.
+///
+/// Resource paths are relative to the Tauri directory, which must be the current directory.
+pub fn copy_resources(asset_dir: &Path, tauri_config: &TauriConfig) -> Result<()> {
+  let Some(resources) = &tauri_config.bundle.resources else {
+    return Ok(());
+  };
+  let filter = Filter::new(tauri_config.bundle.ios.assets.as_ref())?;
+  let paths = match resources {
+    BundleResources::List(list) => ResourcePaths::new(list, true),
+    BundleResources::Map(map) => ResourcePaths::from_map(map, true),
+  };
+
+  let (mut included, mut excluded) = (0, 0);
+  for resource in paths.iter() {
+    let resource = resource
+      .context("failed to resolve the `bundle > resources`")
+      .context(ErrorKind::InvalidConfig)?;
+    let dest = asset_dir.join(resource.target());
+    if filter.is_included(resource.target()) {
+      if let Some(parent) = dest.parent() {
+        create_dir_all(parent)?;
+      }
+      copy(resource.path(), &dest).with_context(|| {
+        format!(
+          "failed to copy the resource {} to the Xcode project",
+          resource.path().display()
+        )
+      })?;
+      included += 1;
+    } else {
+      if dest.is_file() {
+        remove_file(&dest)?;
+      }
+      excluded += 1;
+    }
+  }
+
+  log::info!(action = "Copied"; "{included} resource(s) to the Xcode project, {excluded} excluded by `bundle > iOS > assets`");
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Filter;
+  use tauri_utils::config::IosAssets;
+
+  use std::path::Path;
+
+  #[test]
+  fn excludes_take_priority_over_includes() {
+    let assets = IosAssets {
+      include: Some(vec!["assets/**".into()]),
+      exclude: Some(vec!["*.map".into(), "assets/dev/**".into()]),
+    };
+    let filter = Filter::new(Some(&assets)).unwrap();
+    assert!(filter.is_included(Path::new("assets/logo.png")));
+    assert!(!filter.is_included(Path::new("assets/js/app.js.map")));
+    assert!(!filter.is_included(Path::new("assets/dev/fixtures.json")));
+    assert!(!filter.is_included(Path::new("licenses/LICENSE")));
+
+    let filter = Filter::new(None).unwrap();
+    assert!(filter.is_included(Path::new("licenses/LICENSE")));
+  }
+}
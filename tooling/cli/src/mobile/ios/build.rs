@@ -3,8 +3,22 @@
 // SPDX-License-Identifier: MIT
 
 use super::{
-  configure_cargo, detect_target_ok, ensure_init, env, get_app, get_config, inject_assets,
-  log_finished, merge_plist, open_and_wait, MobileTarget, OptionsHandle,
+  app_entitlements_path, apply_build_settings, build_env, built_app_path, bundle_identifier,
+  bundle_identifier_build_setting, cargo_profile_args, catalyst, clean_xcode_project,
+  configure_cargo, derived_data_build_settings, detect_target_ok, disable_code_signing, dsym,
+  ensure_init, ensure_minimum_xcode_version, ensure_xcode, env,
+  features_matrix::merge_features,
+  frameworks_manifest, get_app, get_config, info_plist_files, inject_assets, is_simulator_triple,
+  log_file, log_finished, merge_associated_domains, merge_bundle_id_suffix, merge_info_plist,
+  merge_plist, metal_build_settings, open_and_wait, parse_build_setting, print_command,
+  privacy_manifest, project_env_vars,
+  signing_fingerprint::Fingerprint,
+  strip_symbols_build_settings,
+  summary::{Summary, SummaryFormat},
+  suppress_development_team_warning,
+  timings::{Timings, TimingsFormat},
+  url_types_plist, validate, xcodebuild_env_vars, ConfigOptions, ErrorKind, MobileTarget,
+  OptionsHandle, PlistKind, XcodeOpenTarget,
 };
 use crate::{
   build::Options as BuildOptions,
@@ -15,7 +29,7 @@ use crate::{
   },
   interface::{AppInterface, AppSettings, Interface, Options as InterfaceOptions},
   mobile::{write_options, CliOptions},
-  ConfigValue, Result,
+  CommandExt, ConfigValue, Result,
 };
 use clap::{ArgAction, Parser, ValueEnum};
 
@@ -26,8 +40,16 @@ use cargo_mobile2::{
   opts::{NoiseLevel, Profile},
   target::{call_for_targets_with_fallback, TargetInvalid, TargetTrait},
 };
+use tauri_macos_sign::{ProfileType, ProvisioningProfile};
 
-use std::{env::set_current_dir, fs};
+use std::{
+  collections::HashMap,
+  env::set_current_dir,
+  ffi::OsString,
+  fs,
+  path::{Path, PathBuf},
+  process::Command,
+};
 
 #[derive(Debug, Clone, Parser)]
 #[clap(
@@ -38,13 +60,12 @@ pub struct Options {
   /// Builds with the debug flag
   #[clap(short, long)]
   pub debug: bool,
-  /// Which targets to build.
+  /// Which targets to build. Defaults to `aarch64`, or to the simulator target of this machine with `--no-codesign`.
   #[clap(
     short,
     long = "target",
     action = ArgAction::Append,
     num_args(0..),
-    default_value = Target::DEFAULT_KEY,
     value_parser(clap::builder::PossibleValuesParser::new(Target::name_list()))
   )]
   pub targets: Vec<String>,
@@ -57,24 +78,156 @@ pub struct Options {
   /// Build number to append to the app version.
   #[clap(long)]
   pub build_number: Option<u32>,
+  /// Derive the build number from the CI environment or the git history, so each build number is higher than the previous one.
+  ///
+  /// The first set of `CI_PIPELINE_IID` (GitLab), `GITHUB_RUN_NUMBER` (GitHub Actions), `CIRCLE_BUILD_NUM` (CircleCI),
+  /// `BITRISE_BUILD_NUMBER` (Bitrise) and `BUILD_NUMBER` (Jenkins) is used,
+  /// falling back to the commit count of the current git branch.
+  #[clap(long, conflicts_with = "build_number")]
+  pub auto_build_number: bool,
   /// Open Xcode
   #[clap(short, long)]
   pub open: bool,
+  /// The Xcode file to open. Defaults to the workspace when it exists, falling back to the project.
+  #[clap(long, value_enum)]
+  pub open_target: Option<XcodeOpenTarget>,
   /// Skip prompting for values
   #[clap(long, env = "CI")]
   pub ci: bool,
   /// Describes how Xcode should export the archive.
   ///
   /// Use this to create a package ready for the App Store (app-store-connect option) or TestFlight (release-testing option).
+  /// The `app-store`, `ad-hoc` and `development` names used before Xcode 15.3 are accepted as aliases.
+  ///
+  /// When signing manually, the provisioning profile must match the method.
+  /// Without an explicit profile, the latest expiring installed profile of the method that can sign the app is used.
   #[clap(long, value_enum)]
   pub export_method: Option<ExportMethod>,
+  /// Path to a plist file with the export options used to export the archive to an IPA,
+  /// e.g. `uploadSymbols` or `manifest`. Its values override the export options generated by Tauri for this build only.
+  ///
+  /// See `xcodebuild -help` for the available keys.
+  #[clap(long, value_name = "PATH", conflicts_with = "no_codesign")]
+  pub export_options: Option<PathBuf>,
+  /// Skip checking that each exported IPA embeds the provisioning profile selected for manual signing.
+  #[clap(long, env = "TAURI_IOS_NO_VERIFY_PROFILE")]
+  pub no_verify_profile: bool,
+  /// Directory to copy the generated IPAs to, created if needed.
+  ///
+  /// Each IPA is named `<app name>_<arch>.ipa` so it can be picked up by scripts.
+  #[clap(long)]
+  pub out_dir: Option<PathBuf>,
+  /// Directory to copy the dSYM bundles of the archive to, created if needed, e.g. for crash reporting services.
+  ///
+  /// The dSYMs of each target are copied to an `<arch>` subdirectory.
+  #[clap(long, value_name = "DIR", conflicts_with = "no_codesign")]
+  pub dsym_out: Option<PathBuf>,
+  /// Sets a build-time environment variable (KEY=VALUE) for the Rust build. Can be used multiple times.
+  #[clap(long = "build-env", value_name = "KEY=VALUE", value_parser = build_env::parse_key_value, action = ArgAction::Append)]
+  pub build_env: Vec<(String, String)>,
+  /// Path to a `.env` file with build-time environment variables.
+  ///
+  /// Variables set with `--build-env` take priority over the ones defined in this file.
+  #[clap(long)]
+  pub build_env_file: Option<PathBuf>,
+  /// Also write the build-time environment variables to the app Info.plist.
+  #[clap(long)]
+  pub build_env_plist: bool,
+  /// Sets an Xcode build setting (KEY=VALUE) for the xcodebuild invocation. Can be used multiple times.
+  ///
+  /// These settings override the ones defined in the Xcode project, e.g. `--build-setting SWIFT_VERSION=5.0`.
+  #[clap(long = "build-setting", value_name = "KEY=VALUE", value_parser = parse_build_setting, action = ArgAction::Append)]
+  pub build_settings: Vec<(String, String)>,
+  /// Appended to the `identifier` to build the app bundle identifier, e.g. `.internal`,
+  /// to install the app side by side with the production build. Overrides `bundle > iOS > bundleIdSuffix`.
+  #[clap(long, env = "TAURI_IOS_BUNDLE_ID_SUFFIX")]
+  pub bundle_id_suffix: Option<String>,
+  /// Also write the output of the command, including the xcodebuild output, to the given file.
+  ///
+  /// The parent directories are created if needed.
+  #[clap(long, value_name = "PATH")]
+  pub log_file: Option<PathBuf>,
+  /// Path to a JSON or TOML features matrix file describing the cargo features to enable
+  /// for device and simulator targets and debug and release configurations.
+  ///
+  /// The resolved features are merged with the ones passed with `--features`.
+  /// The first target passed with `--target` is used to resolve the matrix.
+  #[clap(long, value_name = "PATH")]
+  pub features_matrix: Option<PathBuf>,
+  /// Do not warn when the development team is not configured, e.g. when relying on Xcode-managed signing.
+  #[clap(long, env = "TAURI_IOS_NO_DEVELOPMENT_TEAM_WARNING")]
+  pub no_development_team_warning: bool,
+  /// Print where each resolved iOS config value comes from: environment variables, `tauri.conf.json` or defaults.
+  #[clap(long)]
+  pub explain_config: bool,
+  /// Skip code signing. Only supported when building for simulators.
+  #[clap(long)]
+  pub no_codesign: bool,
+  /// Clean the build products of the Xcode project before building to avoid stale incremental builds.
+  #[clap(long)]
+  pub clean: bool,
+  /// Directory for the build products and intermediates of xcodebuild, like its `-derivedDataPath`,
+  /// instead of the shared Xcode DerivedData directory. Combined with `--clean`, the build directories created in it are removed.
+  #[clap(long, value_name = "PATH")]
+  pub derived_data: Option<PathBuf>,
+  /// The cargo profile used to build the Rust library, e.g. a custom `release-with-debug` profile,
+  /// independently of the Xcode configuration. Custom profiles must be defined in the Cargo.toml of the app or its workspace.
+  #[clap(long, value_name = "NAME")]
+  pub cargo_profile: Option<String>,
+  /// Build against the given iOS SDK version, e.g. `17.5`, instead of the latest SDK of the selected Xcode.
+  /// The SDK must be installed.
+  #[clap(long)]
+  pub sdk: Option<String>,
+  /// Print how long each build phase took once the build finishes.
+  ///
+  /// The Rust code is compiled by the Xcode build phase.
+  #[clap(long, value_enum)]
+  pub timings: Option<TimingsFormat>,
+  /// Print the xcodebuild commands, with the environment variables Tauri sets for them, and exit without building.
+  ///
+  /// Environment variable values that look like secrets are redacted.
+  #[clap(long, conflicts_with_all = ["open", "timings", "summary"])]
+  pub print_command: bool,
+  /// Build and archive the Mac Catalyst variant of the app, to run it on macOS, instead of the iOS targets.
+  ///
+  /// Mac Catalyst requires iOS 13.1 as deployment target. The `aarch64-apple-ios-macabi` and `x86_64-apple-ios-macabi`
+  /// Rust targets must be installed and the Xcode project must be generated by a Tauri version supporting Mac Catalyst.
+  #[clap(long, conflicts_with_all = ["sdk", "no_codesign", "export_method", "print_command"])]
+  pub catalyst: bool,
+  /// Always archive and sign the app. By default, the IPA of the last successful export is reused when the app built
+  /// by Xcode was not modified since then and neither the signing identity, provisioning profile, entitlements
+  /// nor export options changed.
+  ///
+  /// Any change of the app, including its code, requires signing it again since the signature covers its contents.
+  #[clap(long, conflicts_with = "no_codesign")]
+  pub force_sign: bool,
+  /// Print a single line summarizing the build once it succeeds, with the artifact paths, bundle identifier,
+  /// version and signing identity, for CI scripts to extract the build metadata.
+  #[clap(long, value_enum, value_name = "FORMAT", conflicts_with = "open")]
+  pub summary: Option<SummaryFormat>,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum ExportMethod {
+  #[value(alias = "app-store")]
   AppStoreConnect,
+  #[value(alias = "ad-hoc")]
   ReleaseTesting,
+  #[value(alias = "development")]
   Debugging,
+  Enterprise,
+}
+
+impl ExportMethod {
+  /// The type of the provisioning profiles that can sign an export with this method.
+  fn profile_type(self) -> ProfileType {
+    match self {
+      Self::AppStoreConnect => ProfileType::AppStore,
+      Self::ReleaseTesting => ProfileType::AdHoc,
+      Self::Debugging => ProfileType::Development,
+      Self::Enterprise => ProfileType::Enterprise,
+    }
+  }
 }
 
 impl std::fmt::Display for ExportMethod {
@@ -83,6 +236,7 @@ impl std::fmt::Display for ExportMethod {
       Self::AppStoreConnect => write!(f, "app-store-connect"),
       Self::ReleaseTesting => write!(f, "release-testing"),
       Self::Debugging => write!(f, "debugging"),
+      Self::Enterprise => write!(f, "enterprise"),
     }
   }
 }
@@ -92,9 +246,10 @@ impl std::str::FromStr for ExportMethod {
 
   fn from_str(s: &str) -> Result<Self, Self::Err> {
     match s {
-      "app-store-connect" => Ok(Self::AppStoreConnect),
-      "release-testing" => Ok(Self::ReleaseTesting),
-      "debugging" => Ok(Self::Debugging),
+      "app-store-connect" | "app-store" => Ok(Self::AppStoreConnect),
+      "release-testing" | "ad-hoc" => Ok(Self::ReleaseTesting),
+      "debugging" | "development" => Ok(Self::Debugging),
+      "enterprise" => Ok(Self::Enterprise),
       _ => Err("unknown ios target"),
     }
   }
@@ -117,8 +272,91 @@ impl From<Options> for BuildOptions {
 }
 
 pub fn command(options: Options, noise_level: NoiseLevel) -> Result<()> {
-  let mut build_options: BuildOptions = options.clone().into();
-  build_options.target = Some(
+  let log_file = options.log_file.as_deref().map(log_file::tee).transpose()?;
+  let result = run_command(options, noise_level);
+  if let (Some(log_file), Err(e)) = (&log_file, &result) {
+    log_file.record_error(e);
+  }
+  result
+}
+
+/// The simulator target running natively on this machine.
+fn host_simulator_target() -> &'static str {
+  if cfg!(target_arch = "x86_64") {
+    "x86_64"
+  } else {
+    "aarch64-sim"
+  }
+}
+
+fn run_command(mut options: Options, noise_level: NoiseLevel) -> Result<()> {
+  let mut timings = Timings::new();
+  let developer_dir = ensure_xcode()?;
+  if options.no_development_team_warning || options.no_codesign {
+    suppress_development_team_warning();
+  }
+  if options.catalyst {
+    catalyst::ensure_rust_targets()?;
+    // inserted first so an explicit `--build-setting` wins
+    options
+      .build_settings
+      .splice(0..0, catalyst::build_settings());
+  }
+
+  if options.targets.is_empty() {
+    // resolved here instead of falling back to the detected device, so `--no-codesign` is checked against the built targets
+    options.targets = vec![if options.no_codesign {
+      host_simulator_target().into()
+    } else {
+      Target::DEFAULT_KEY.into()
+    }];
+  }
+
+  if options.no_codesign {
+    if let Some(target) = options
+      .targets
+      .iter()
+      .find(|t| !is_simulator_triple(Target::all().get(t.as_str()).unwrap().triple))
+    {
+      return Err(
+        anyhow::anyhow!(
+          "--no-codesign is only supported on simulator targets, but {target} is a device target"
+        )
+        .context(ErrorKind::InvalidConfig),
+      );
+    }
+  }
+
+  if options.auto_build_number {
+    options.build_number = Some(auto_build_number()?);
+  }
+
+  if let Some(sdk_version) = &options.sdk {
+    let installed_sdks = installed_sdks()?;
+    for target in &options.targets {
+      let target = Target::all().get(target.as_str()).unwrap();
+      let sdk = format!("{}{sdk_version}", target.sdk);
+      if !installed_sdks.contains(&sdk) {
+        return Err(
+          anyhow::anyhow!(
+            "the {sdk} SDK is not installed. Available SDKs: {}",
+            installed_sdks.join(", ")
+          )
+          .context(ErrorKind::XcodeMissing),
+        );
+      }
+    }
+  }
+
+  let target_triple = if options.catalyst {
+    catalyst::rust_triple(if cfg!(target_arch = "aarch64") {
+      "arm64"
+    } else {
+      "x86_64"
+    })
+    .unwrap()
+    .1
+  } else {
     Target::all()
       .get(
         options
@@ -129,29 +367,81 @@ pub fn command(options: Options, noise_level: NoiseLevel) -> Result<()> {
       )
       .unwrap()
       .triple
-      .into(),
-  );
+  };
+  merge_features(
+    &mut options.features,
+    options.features_matrix.as_deref(),
+    target_triple,
+    options.debug,
+  )
+  .context(ErrorKind::InvalidConfig)?;
+
+  if let Some(suffix) = &options.bundle_id_suffix {
+    merge_bundle_id_suffix(&mut options.config, suffix);
+  }
+  let mut build_options: BuildOptions = options.clone().into();
+  build_options.target = Some(target_triple.into());
 
   let tauri_config = get_tauri_config(
     tauri_utils::platform::Target::Ios,
     options.config.as_ref().map(|c| &c.0),
-  )?;
+  )
+  .context(ErrorKind::InvalidConfig)?;
+  let tauri_config_guard = tauri_config.lock().unwrap();
+  let tauri_config_ = tauri_config_guard.as_ref().unwrap();
+  ensure_minimum_xcode_version(tauri_config_, &developer_dir)?;
+  // inserted first so an explicit `--build-setting PRODUCT_BUNDLE_IDENTIFIER=` wins
+  if let Some(setting) = bundle_identifier_build_setting(tauri_config_) {
+    options.build_settings.insert(0, setting);
+  }
+  // inserted first so an explicit `--build-setting` wins
+  options.build_settings.splice(
+    0..0,
+    strip_symbols_build_settings(
+      tauri_config_,
+      if options.debug {
+        Profile::Debug
+      } else {
+        Profile::Release
+      },
+    ),
+  );
+  options
+    .build_settings
+    .splice(0..0, metal_build_settings(tauri_config_)?);
+  if let Some(derived_data) = &mut options.derived_data {
+    // resolved before changing the current directory
+    *derived_data = std::env::current_dir()?.join(&*derived_data);
+    options
+      .build_settings
+      .splice(0..0, derived_data_build_settings(derived_data));
+  }
   let (interface, app, config) = {
-    let tauri_config_guard = tauri_config.lock().unwrap();
-    let tauri_config_ = tauri_config_guard.as_ref().unwrap();
-
     let interface = AppInterface::new(tauri_config_, build_options.target.clone())?;
     interface.build_options(&mut Vec::new(), &mut build_options.features, true);
 
     let app = get_app(tauri_config_, &interface);
+    // the frameworks are only downloaded by the commands building the app, before they are read by `get_config`
+    frameworks_manifest::install(&tauri_dir())?;
     let (config, _metadata) = get_config(
       &app,
       tauri_config_,
       build_options.features.as_ref(),
       &Default::default(),
-    );
+      Some(if options.debug {
+        Profile::Debug
+      } else {
+        Profile::Release
+      }),
+      ConfigOptions {
+        explain: options.explain_config,
+        catalyst: options.catalyst,
+      },
+    )?;
     (interface, app, config)
   };
+  // `ensure_init` locks the configuration itself
+  drop(tauri_config_guard);
 
   let tauri_path = tauri_dir();
   set_current_dir(&tauri_path).with_context(|| "failed to change current working directory")?;
@@ -161,60 +451,259 @@ pub fn command(options: Options, noise_level: NoiseLevel) -> Result<()> {
     config.app(),
     config.project_dir(),
     MobileTarget::Ios,
+  )
+  .context(ErrorKind::InvalidConfig)?;
+  let tauri_config_guard = tauri_config.lock().unwrap();
+  let tauri_config_ = tauri_config_guard.as_ref().unwrap();
+  inject_assets(&config, tauri_config_)?;
+
+  let build_env = build_env::resolve(options.build_env_file.as_deref(), &options.build_env)?;
+
+  let mut src_plists: Vec<PlistKind> = Vec::new();
+  // generated from the configuration first so the plist files can override it
+  src_plists.extend(url_types_plist(tauri_config_).map(PlistKind::from));
+  src_plists.push(tauri_path.join("Info.plist").into());
+  src_plists.push(tauri_path.join("Info.ios.plist").into());
+  let mut build_plists = info_plist_files(
+    tauri_config_,
+    &tauri_path,
+    if options.debug {
+      Profile::Debug
+    } else {
+      Profile::Release
+    },
   )?;
-  inject_assets(&config)?;
-
-  let info_plist_path = config
-    .project_dir()
-    .join(config.scheme())
-    .join("Info.plist");
-  merge_plist(
-    vec![
-      tauri_path.join("Info.plist").into(),
-      tauri_path.join("Info.ios.plist").into(),
-    ],
-    &info_plist_path,
-  )?;
+  if options.build_env_plist && !build_env.is_empty() {
+    build_plists.push(build_env::to_plist(&build_env).into());
+  }
+  merge_info_plist(&config, src_plists, build_plists)?;
+  merge_associated_domains(&config, tauri_config_)?;
+  privacy_manifest::sync(&config, tauri_config_)?;
 
   let mut env = env()?;
+  for (key, value) in build_env.iter().chain(&project_env_vars(&config)) {
+    env.insert_env_var(key.clone(), value.clone());
+  }
+  // written before printing the commands, which read the cargo configuration and the build settings file
   configure_cargo(&app, None)?;
+  apply_build_settings(&config, &mut env, &options.build_settings)?;
 
-  let (keychain, provisioning_profile) = super::signing_from_env()?;
-  let init_config = super::init_config(keychain.as_ref(), provisioning_profile.as_ref())?;
-  if let Some(export_options_plist) =
-    create_export_options(&app, &init_config, options.export_method)
-  {
-    let export_options_plist_path = config.project_dir().join("ExportOptions.plist");
+  if options.print_command {
+    print_xcodebuild_commands(&options, &config, &build_env);
+    return Ok(());
+  }
 
-    merge_plist(
-      vec![
-        export_options_plist_path.clone().into(),
-        export_options_plist.into(),
-      ],
-      &export_options_plist_path,
-    )?;
+  if options.clean {
+    clean_xcode_project(&config, options.derived_data.as_deref())?;
+  }
+  timings.lap("configuration");
+
+  let mut expected_profile_uuid = None;
+  let mut signing_identity = "none".to_string();
+  // the keychain created for the certificate is deleted when dropped, so it must outlive the build
+  let mut _keychain = None;
+  // the `--export-options` are removed from the export options of the project when dropped, after the export
+  let mut _export_options = None;
+  if options.no_codesign {
+    disable_code_signing(&mut env);
+  } else {
+    let (keychain, mut provisioning_profile) =
+      super::signing_from_env(tauri_config_, options.catalyst)?;
+    if let Some(method) = options.export_method {
+      provisioning_profile = export_method_provisioning_profile(
+        method,
+        provisioning_profile,
+        keychain.is_some(),
+        &bundle_identifier(tauri_config_),
+        options.catalyst,
+      )?;
+    }
+    let init_config = super::init_config(keychain.as_ref(), provisioning_profile.as_ref())?;
+    _keychain = keychain;
+    signing_identity = init_config
+      .code_sign_identity
+      .clone()
+      .unwrap_or_else(|| "automatic".into());
+    // Xcode picks the profile itself when signing automatically
+    if !options.no_verify_profile
+      && matches!(
+        init_config.code_sign_style,
+        super::super::init::CodeSignStyle::Manual
+      )
+    {
+      expected_profile_uuid = init_config.provisioning_profile_uuid.clone();
+    }
+    let bundle_id = bundle_identifier(tauri_config_);
+    let export_options_plist_path = config.project_dir().join("ExportOptions.plist");
+    ExportOptionsOverride::restore_interrupted(&export_options_plist_path)?;
+    if let Some(export_options_plist) =
+      create_export_options(&bundle_id, &init_config, options.export_method)
+    {
+      merge_plist(
+        vec![
+          export_options_plist_path.clone().into(),
+          export_options_plist.into(),
+        ],
+        &export_options_plist_path,
+      )?;
+    }
+    if let Some(path) = &options.export_options {
+      let export_options_plist = plist::Value::from_file(path)
+        .with_context(|| format!("failed to read the export options {}", path.display()))
+        .context(ErrorKind::InvalidConfig)?;
+      _export_options = Some(ExportOptionsOverride::apply(
+        &export_options_plist_path,
+        export_options_plist,
+      )?);
+    }
+    validate_export_options(&export_options_plist_path, &bundle_id)?;
   }
 
+  timings.lap("signing");
+
   let open = options.open;
-  let _handle = run_build(
+  let open_target = options.open_target;
+  let timings_format = options.timings;
+  let summary_format = options.summary;
+  let bundle_id = bundle_identifier(tauri_config_);
+  let mut version = config.bundle_version().clone();
+  if let Some(build_number) = options.build_number {
+    version.push_extra(build_number);
+  }
+  // `run_build` locks the configuration itself
+  drop(tauri_config_guard);
+  let (_handle, artifacts) = run_build(
     interface,
     options,
     build_options,
     tauri_config,
     &config,
     &mut env,
+    build_env,
+    &signing_identity,
+    expected_profile_uuid.as_deref(),
     noise_level,
+    &mut timings,
   )?;
 
+  if let Some(format) = timings_format {
+    timings.print(format);
+  }
+
+  if let Some(format) = summary_format {
+    Summary {
+      artifacts,
+      bundle_id,
+      version: version.to_string(),
+      signing_identity,
+    }
+    .print(format);
+  }
+
   if open {
-    open_and_wait(&config, &env);
+    open_and_wait(&config, &env, open_target);
   }
 
   Ok(())
 }
 
+/// Prints the build, archive and export commands of each target for `--print-command`.
+fn print_xcodebuild_commands(
+  options: &Options,
+  config: &AppleConfig,
+  build_env: &HashMap<String, OsString>,
+) {
+  let profile = if options.debug {
+    Profile::Debug
+  } else {
+    Profile::Release
+  };
+  let env_vars = xcodebuild_env_vars(
+    config,
+    build_env,
+    options.no_codesign,
+    &options.build_settings,
+  );
+  let mut build_number = config.bundle_version().clone();
+  if let Some(number) = options.build_number {
+    build_number.push_extra(number);
+  }
+
+  for target in &options.targets {
+    let target = Target::all().get(target.as_str()).unwrap();
+    let sdk = options
+      .sdk
+      .as_ref()
+      .map(|version| format!("{}{version}", target.sdk));
+    let target = &match &sdk {
+      Some(sdk) => Target {
+        sdk,
+        ..target.clone()
+      },
+      None => target.clone(),
+    };
+
+    print_command::print(
+      &env_vars,
+      &print_command::build_args(config, target, profile),
+    );
+    if !options.no_codesign {
+      print_command::print(
+        &env_vars,
+        &print_command::archive_args(config, target, profile, Some(build_number.to_string())),
+      );
+      print_command::print(&env_vars, &print_command::export_args(config));
+    }
+  }
+}
+
+/// Checks that the provisioning profile can sign an export with the given method,
+/// selecting an installed profile of the method when none is provided and the app is signed with a certificate.
+fn export_method_provisioning_profile(
+  method: ExportMethod,
+  provisioning_profile: Option<ProvisioningProfile>,
+  has_certificate: bool,
+  bundle_id: &str,
+  catalyst: bool,
+) -> Result<Option<ProvisioningProfile>> {
+  let expected = method.profile_type();
+  if let Some(profile) = provisioning_profile {
+    let profile_type = profile.profile_type().context(ErrorKind::SigningMissing)?;
+    if profile_type != expected {
+      return Err(
+        anyhow::anyhow!(
+          "the {method} export method requires a {expected} provisioning profile, but {} is a {profile_type} profile",
+          profile.name().unwrap_or_else(|_| profile.path().display().to_string())
+        )
+        .context(ErrorKind::SigningMissing),
+      );
+    }
+    return Ok(Some(profile));
+  }
+
+  // Xcode provisions the profile itself when signing automatically
+  if !has_certificate {
+    return Ok(None);
+  }
+  match super::find_installed_provisioning_profile_of_type(expected, bundle_id, catalyst)? {
+    Some(profile) => {
+      log::info!(
+        "Using {expected} provisioning profile {} for the {method} export method",
+        profile.name().unwrap_or_else(|_| profile.path().display().to_string())
+      );
+      Ok(Some(profile))
+    }
+    None => Err(
+      anyhow::anyhow!(
+        "no valid installed {expected} provisioning profile can sign {bundle_id}, required by the {method} export method"
+      )
+      .context(ErrorKind::SigningMissing),
+    ),
+  }
+}
+
 fn create_export_options(
-  app: &cargo_mobile2::config::app::App,
+  bundle_id: &str,
   config: &super::super::init::IosInitConfig,
   export_method: Option<ExportMethod>,
 ) -> Option<plist::Value> {
@@ -238,7 +727,7 @@ fn create_export_options(
 
   if let Some(profile_uuid) = &config.provisioning_profile_uuid {
     let mut provisioning_profiles = plist::Dictionary::new();
-    provisioning_profiles.insert(app.reverse_identifier(), profile_uuid.clone().into());
+    provisioning_profiles.insert(bundle_id.to_string(), profile_uuid.clone().into());
     plist.insert(
       "provisioningProfiles".to_string(),
       provisioning_profiles.into(),
@@ -248,6 +737,165 @@ fn create_export_options(
   (!plist.is_empty()).then(|| plist.into())
 }
 
+/// The export methods accepted by xcodebuild, including the names deprecated in Xcode 15.3.
+const EXPORT_METHODS: &[&str] = &[
+  "app-store-connect",
+  "release-testing",
+  "debugging",
+  "enterprise",
+  "validation",
+  "app-store",
+  "ad-hoc",
+  "development",
+  "developer-id",
+];
+
+/// The export options of the Xcode project while the `--export-options` are merged into them.
+const EXPORT_OPTIONS_BACKUP_FILE_NAME: &str = ".ExportOptions.backup.plist";
+
+/// Merges the `--export-options` into the export options of the Xcode project for the current build,
+/// restoring the previous export options when dropped.
+struct ExportOptionsOverride {
+  path: PathBuf,
+  backup: PathBuf,
+}
+
+impl ExportOptionsOverride {
+  fn apply(path: &Path, export_options: plist::Value) -> Result<Self> {
+    let backup = path.with_file_name(EXPORT_OPTIONS_BACKUP_FILE_NAME);
+    if path.exists() {
+      fs::copy(path, &backup)
+        .with_context(|| format!("failed to back up the export options {}", path.display()))?;
+    } else {
+      // restored to an empty dictionary, like a missing file is merged
+      plist::Value::from(plist::Dictionary::new()).to_file_xml(&backup)?;
+    }
+    let export_options_override = Self {
+      path: path.to_path_buf(),
+      backup,
+    };
+    merge_plist(vec![export_options.into()], path)?;
+    Ok(export_options_override)
+  }
+
+  /// Restores the export options left by a build interrupted before the export.
+  fn restore_interrupted(path: &Path) -> Result<()> {
+    let backup = path.with_file_name(EXPORT_OPTIONS_BACKUP_FILE_NAME);
+    if backup.exists() {
+      fs::rename(&backup, path)
+        .with_context(|| format!("failed to restore the export options {}", path.display()))?;
+    }
+    Ok(())
+  }
+}
+
+impl Drop for ExportOptionsOverride {
+  fn drop(&mut self) {
+    let _ = fs::rename(&self.backup, &self.path);
+  }
+}
+
+/// The keys required by the `manifest` export option, used for over-the-air installation.
+const EXPORT_MANIFEST_KEYS: &[&str] = &["appURL", "displayImageURL", "fullSizeImageURL"];
+
+/// Checks the export options have the keys required by their export method and signing style.
+fn validate_export_options(path: &Path, bundle_id: &str) -> Result<()> {
+  if !path.exists() {
+    return Ok(());
+  }
+  let export_options = plist::Value::from_file(path)
+    .with_context(|| format!("failed to read plist file {}", path.display()))?;
+  let Some(export_options) = export_options.as_dictionary() else {
+    return Err(
+      anyhow::anyhow!("the export options {} must be a dictionary", path.display())
+        .context(ErrorKind::InvalidConfig),
+    );
+  };
+  let string = |key: &str| export_options.get(key).and_then(|value| value.as_string());
+
+  let mut errors = Vec::new();
+  // xcodebuild defaults to development exports
+  let method = string("method").unwrap_or("development");
+  if !EXPORT_METHODS.contains(&method) {
+    errors.push(format!(
+      "unknown `method` `{method}`, expected one of {}",
+      EXPORT_METHODS.join(", ")
+    ));
+  }
+  match string("signingStyle") {
+    Some("manual") => {
+      let has_profile = export_options
+        .get("provisioningProfiles")
+        .and_then(|profiles| profiles.as_dictionary())
+        .and_then(|profiles| profiles.get(bundle_id))
+        .and_then(|profile| profile.as_string())
+        .is_some();
+      // the certificate alone lets xcodebuild pick a matching installed profile
+      if !has_profile && string("signingCertificate").is_none() {
+        errors.push(format!(
+          "manual signing requires a `signingCertificate` or a `provisioningProfiles` entry for {bundle_id}"
+        ));
+      }
+    }
+    Some("automatic") | None => {}
+    Some(style) => errors.push(format!(
+      "unknown `signingStyle` `{style}`, expected `manual` or `automatic`"
+    )),
+  }
+  if let Some(destination) = string("destination") {
+    if destination != "export" && destination != "upload" {
+      errors.push(format!(
+        "unknown `destination` `{destination}`, expected `export` or `upload`"
+      ));
+    } else if destination == "upload" && !matches!(method, "app-store-connect" | "app-store") {
+      errors.push(format!(
+        "the `upload` destination requires the `app-store-connect` method, not `{method}`"
+      ));
+    }
+  }
+  if let Some(manifest) = export_options.get("manifest") {
+    if matches!(method, "app-store-connect" | "app-store") {
+      errors.push(format!(
+        "the `manifest` option is not supported by the `{method}` method"
+      ));
+    }
+    let missing = EXPORT_MANIFEST_KEYS
+      .iter()
+      .filter(|key| {
+        manifest
+          .as_dictionary()
+          .and_then(|manifest| manifest.get(key))
+          .is_none()
+      })
+      .copied()
+      .collect::<Vec<_>>();
+    if !missing.is_empty() {
+      errors.push(format!(
+        "the `manifest` option is missing {}",
+        missing.join(", ")
+      ));
+    }
+  }
+
+  if errors.is_empty() {
+    Ok(())
+  } else {
+    Err(
+      anyhow::anyhow!(
+        "invalid export options {}:\n{}",
+        path.display(),
+        errors
+          .iter()
+          .map(|error| format!("- {error}"))
+          .collect::<Vec<_>>()
+          .join("\n")
+      )
+      .context(ErrorKind::InvalidConfig),
+    )
+  }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn run_build(
   interface: AppInterface,
   options: Options,
@@ -255,8 +903,12 @@ fn run_build(
   tauri_config: ConfigHandle,
   config: &AppleConfig,
   env: &mut Env,
+  build_env: HashMap<String, OsString>,
+  signing_identity: &str,
+  expected_profile_uuid: Option<&str>,
   noise_level: NoiseLevel,
-) -> Result<OptionsHandle> {
+  timings: &mut Timings,
+) -> Result<(OptionsHandle, Vec<PathBuf>)> {
   let profile = if options.debug {
     Profile::Debug
   } else {
@@ -264,6 +916,7 @@ fn run_build(
   };
 
   crate::build::setup(&interface, &mut build_options, tauri_config.clone(), true)?;
+  timings.lap("frontend");
 
   let app_settings = interface.app_settings();
   let bin_path = app_settings.app_binary_path(&InterfaceOptions {
@@ -274,19 +927,52 @@ fn run_build(
   let out_dir = bin_path.parent().unwrap();
   let _lock = flock::open_rw(out_dir.join("lock").with_extension("ios"), "iOS")?;
 
+  let catalyst_vars = options.catalyst.then(|| {
+    xcodebuild_env_vars(
+      config,
+      &build_env,
+      options.no_codesign,
+      &options.build_settings,
+    )
+  });
   let cli_options = CliOptions {
     dev: false,
     features: build_options.features.clone(),
-    args: build_options.args.clone(),
+    args: build_options
+      .args
+      .iter()
+      .cloned()
+      .chain(cargo_profile_args(options.cargo_profile.as_deref())?)
+      .collect(),
     noise_level,
-    vars: Default::default(),
+    vars: build_env
+      .into_iter()
+      .chain(project_env_vars(config))
+      .collect(),
   };
   let handle = write_options(
     &tauri_config.lock().unwrap().as_ref().unwrap().identifier,
     cli_options,
   )?;
 
+  if let Some(vars) = &catalyst_vars {
+    let app_path = catalyst::archive(config, vars, profile, options.build_number)?;
+    timings.lap("archive");
+    log_finished(vec![app_path.clone()], "Mac Catalyst app");
+    return Ok((handle, vec![app_path]));
+  }
+
   let mut out_files = Vec::new();
+  let mut dsym_files = Vec::new();
+  let dsym_upload_script = tauri_config
+    .lock()
+    .unwrap()
+    .as_ref()
+    .unwrap()
+    .bundle
+    .ios
+    .dsym_upload_script
+    .clone();
 
   call_for_targets_with_fallback(
     options.targets.iter(),
@@ -298,24 +984,167 @@ fn run_build(
         app_version.push_extra(build_number);
       }
 
-      target.build(config, env, NoiseLevel::FranklyQuitePedantic, profile)?;
-      target.archive(config, env, noise_level, profile, Some(app_version))?;
-      target.export(config, env, noise_level)?;
+      let sdk = options
+        .sdk
+        .as_ref()
+        .map(|version| format!("{}{version}", target.sdk));
+      let target = &match &sdk {
+        Some(sdk) => Target {
+          sdk,
+          ..target.clone()
+        },
+        None => target.clone(),
+      };
+
+      target
+        .build(config, env, NoiseLevel::FranklyQuitePedantic, profile)
+        .context(ErrorKind::BuildFailed)?;
+      timings.lap("xcodebuild");
+
+      // unsigned simulator builds cannot be archived and exported as an IPA
+      if options.no_codesign {
+        log::info!(action = "Built"; "unsigned {} app for the simulator", target.arch);
+        return Ok(());
+      }
+
+      let out_dir = config.export_dir().join(target.arch);
+      let fingerprint = if options.force_sign {
+        None
+      } else {
+        Some(Fingerprint::compute(
+          &[
+            signing_identity,
+            expected_profile_uuid.unwrap_or_default(),
+            &app_version.to_string(),
+          ],
+          &[
+            &app_entitlements_path(config),
+            &config.project_dir().join("ExportOptions.plist"),
+          ],
+        ))
+      };
+      let app = built_app_path(config, target.sdk, profile)?;
+
+      let reused_ipa = fingerprint
+        .as_ref()
+        .and_then(|fingerprint| fingerprint.exported_ipa(&out_dir, &app));
+      let ipa = if let Some(ipa) = reused_ipa.clone() {
+        log::info!(action = "Skipping"; "archive and signing of the unchanged {} app, reusing {}", target.arch, ipa.display());
+        Some(ipa)
+      } else {
+        target
+          .archive(config, env, noise_level, profile, Some(app_version))
+          .context(ErrorKind::BuildFailed)?;
+        timings.lap("archive");
+        target
+          .export(config, env, noise_level)
+          .context(ErrorKind::BuildFailed)?;
+
+        match config.ipa_path() {
+          Ok(ipa_path) => {
+            fs::create_dir_all(&out_dir)?;
+            let path = out_dir.join(ipa_path.file_name().unwrap());
+            fs::rename(&ipa_path, &path)?;
+            if let Some(uuid) = expected_profile_uuid {
+              validate::verify_embedded_profile(&path, uuid)?;
+            }
+            if let Some(fingerprint) = &fingerprint {
+              fingerprint.store(&path)?;
+            }
+            Some(path)
+          }
+          Err(_) => None,
+        }
+      };
+
+      // the dSYMs of a reused IPA were already processed when it was archived
+      if reused_ipa.is_none() && (options.dsym_out.is_some() || dsym_upload_script.is_some()) {
+        dsym_files.extend(dsym::process(
+          config,
+          target.arch,
+          options.dsym_out.as_deref(),
+          dsym_upload_script.as_deref(),
+        )?);
+      }
 
-      if let Ok(ipa_path) = config.ipa_path() {
-        let out_dir = config.export_dir().join(target.arch);
-        fs::create_dir_all(&out_dir)?;
-        let path = out_dir.join(ipa_path.file_name().unwrap());
-        fs::rename(&ipa_path, &path)?;
-        out_files.push(path);
+      if let Some(path) = ipa {
+        if let Some(user_out_dir) = &options.out_dir {
+          fs::create_dir_all(user_out_dir).with_context(|| {
+            format!(
+              "failed to create output directory {}",
+              user_out_dir.display()
+            )
+          })?;
+          let user_path = user_out_dir.join(format!("{}_{}.ipa", config.app().name(), target.arch));
+          fs::copy(&path, &user_path)
+            .with_context(|| format!("failed to copy IPA to {}", user_path.display()))?;
+          out_files.push(user_path);
+        } else {
+          out_files.push(path);
+        }
       }
+      timings.lap("packaging");
 
       Ok(())
     },
   )
   .map_err(|e: TargetInvalid| anyhow::anyhow!(e.to_string()))??;
 
-  log_finished(out_files, "IPA");
+  log_finished(out_files.clone(), "IPA");
+  log_finished(dsym_files, "dSYM");
 
-  Ok(handle)
+  Ok((handle, out_files))
+}
+
+/// Environment variables holding a monotonic build number on CI providers, in lookup order.
+const CI_BUILD_NUMBER_ENV_VARS: &[&str] = &[
+  "CI_PIPELINE_IID",
+  "GITHUB_RUN_NUMBER",
+  "CIRCLE_BUILD_NUM",
+  "BITRISE_BUILD_NUMBER",
+  "BUILD_NUMBER",
+];
+
+/// Resolves the `--auto-build-number` build number from the CI environment or the git commit count.
+fn auto_build_number() -> Result<u32> {
+  for var in CI_BUILD_NUMBER_ENV_VARS {
+    if let Ok(value) = std::env::var(var) {
+      let build_number = value.trim().parse().with_context(|| {
+        format!("the `{var}` environment variable ({value}) is not a valid build number")
+      })?;
+      log::info!("Using build number {build_number} from the `{var}` environment variable");
+      return Ok(build_number);
+    }
+  }
+
+  let output = Command::new("git")
+    .args(["rev-list", "--count", "HEAD"])
+    .output_ok()
+    .context(
+      "failed to count the git commits to derive the build number, use `--build-number` instead",
+    )?;
+  let build_number = String::from_utf8_lossy(&output.stdout)
+    .trim()
+    .parse()
+    .context("failed to parse the git commit count")?;
+  log::info!("Using build number {build_number} from the git commit count");
+  Ok(build_number)
+}
+
+/// Lists the canonical names of the installed SDKs, e.g. `iphoneos17.5`.
+fn installed_sdks() -> Result<Vec<String>> {
+  let output = Command::new("xcodebuild")
+    .args(["-showsdks", "-json"])
+    .output_ok()
+    .context("failed to list the installed SDKs")
+    .context(ErrorKind::XcodeMissing)?;
+  let sdks: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)
+    .context("failed to parse the installed SDKs")
+    .context(ErrorKind::XcodeMissing)?;
+  Ok(
+    sdks
+      .iter()
+      .filter_map(|sdk| sdk.get("canonicalName")?.as_str().map(ToString::to_string))
+      .collect(),
+  )
 }
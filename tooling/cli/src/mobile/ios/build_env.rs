@@ -0,0 +1,119 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Build-time environment variables injected with `--build-env` and `--build-env-file`.
+
+use crate::Result;
+
+use anyhow::Context;
+
+use std::{collections::HashMap, ffi::OsString, fs::read_to_string, path::Path};
+
+/// Parses a `KEY=VALUE` pair.
+pub fn parse_key_value(s: &str) -> std::result::Result<(String, String), String> {
+  let (key, value) = s
+    .split_once('=')
+    .ok_or_else(|| format!("invalid KEY=VALUE pair: no `=` found in `{s}`"))?;
+  let key = key.trim();
+  if key.is_empty() {
+    return Err(format!("invalid KEY=VALUE pair: empty key in `{s}`"));
+  }
+  Ok((key.to_string(), value.to_string()))
+}
+
+/// Parses the contents of a `.env` file.
+///
+/// Empty lines and lines starting with `#` are ignored, an optional `export ` prefix is allowed
+/// and values can be wrapped in single or double quotes.
+fn parse_dotenv(contents: &str) -> std::result::Result<Vec<(String, String)>, String> {
+  let mut vars = Vec::new();
+  for line in contents.lines() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let line = line.strip_prefix("export ").unwrap_or(line);
+    let (key, value) = parse_key_value(line)?;
+    let value = value.trim();
+    let value = value
+      .strip_prefix('"')
+      .and_then(|v| v.strip_suffix('"'))
+      .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+      .unwrap_or(value);
+    vars.push((key, value.to_string()));
+  }
+  Ok(vars)
+}
+
+/// Collects the build environment variables from the `.env` file and the command line.
+///
+/// Variables passed on the command line take priority over the ones defined in the file.
+pub fn resolve(
+  file: Option<&Path>,
+  vars: &[(String, String)],
+) -> Result<HashMap<String, OsString>> {
+  let mut env = HashMap::new();
+
+  if let Some(file) = file {
+    let contents = read_to_string(file)
+      .with_context(|| format!("failed to read build environment file {}", file.display()))?;
+    let file_vars = parse_dotenv(&contents)
+      .map_err(|e| anyhow::anyhow!("{e}"))
+      .with_context(|| format!("invalid build environment file {}", file.display()))?;
+    for (key, value) in file_vars {
+      env.insert(key, value.into());
+    }
+  }
+
+  for (key, value) in vars {
+    env.insert(key.clone(), value.into());
+  }
+
+  Ok(env)
+}
+
+/// Creates an Info.plist dictionary from the build environment variables.
+pub fn to_plist(env: &HashMap<String, OsString>) -> plist::Value {
+  let mut plist = plist::Dictionary::new();
+  for (key, value) in env {
+    plist.insert(key.clone(), value.to_string_lossy().into_owned().into());
+  }
+  plist.into()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{parse_dotenv, parse_key_value};
+
+  #[test]
+  fn parses_key_value() {
+    assert_eq!(
+      parse_key_value("API_URL=https://example.com?a=b"),
+      Ok(("API_URL".into(), "https://example.com?a=b".into()))
+    );
+    assert!(parse_key_value("API_URL").is_err());
+    assert!(parse_key_value("=value").is_err());
+  }
+
+  #[test]
+  fn parses_dotenv() {
+    let vars = parse_dotenv(
+      r#"
+# comment
+API_URL="https://example.com"
+export TOKEN='abc'
+EMPTY=
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+      vars,
+      vec![
+        ("API_URL".into(), "https://example.com".into()),
+        ("TOKEN".into(), "abc".into()),
+        ("EMPTY".into(), "".into()),
+      ]
+    );
+  }
+}
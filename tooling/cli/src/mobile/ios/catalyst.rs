@@ -0,0 +1,116 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Mac Catalyst builds of the iOS app, enabled with `tauri ios build --catalyst`.
+
+use super::{configuration_name, ErrorKind};
+use crate::{CommandExt, Result};
+
+use anyhow::Context;
+use cargo_mobile2::{apple::config::Config as AppleConfig, opts::Profile};
+
+use std::{collections::HashMap, ffi::OsString, path::PathBuf, process::Command};
+
+/// The lowest iOS deployment target supported by Mac Catalyst, which maps to macOS 10.15.
+pub const IOS_VERSION: &str = "13.1";
+
+/// The xcodebuild destination of Mac Catalyst builds.
+const DESTINATION: &str = "generic/platform=macOS,variant=Mac Catalyst";
+
+/// Whether Xcode is building the Mac Catalyst variant, read by `tauri ios xcode-script`.
+pub fn is_xcode_building() -> bool {
+  std::env::var("IS_MACCATALYST").is_ok_and(|value| value == "YES")
+}
+
+/// The Rust target triple, and its environment variable form, of a Mac Catalyst architecture.
+pub fn rust_triple(arch: &str) -> Option<(&'static str, &'static str)> {
+  match arch {
+    "arm64" => Some(("aarch64_apple_ios_macabi", "aarch64-apple-ios-macabi")),
+    "x86_64" => Some(("x86_64_apple_ios_macabi", "x86_64-apple-ios-macabi")),
+    _ => None,
+  }
+}
+
+/// The build settings enabling Mac Catalyst, which requires a newer deployment target than the iOS app.
+pub fn build_settings() -> Vec<(String, String)> {
+  vec![
+    ("SUPPORTS_MACCATALYST".into(), "YES".into()),
+    ("IPHONEOS_DEPLOYMENT_TARGET".into(), IOS_VERSION.into()),
+  ]
+}
+
+/// Checks that the Rust targets of Mac Catalyst are installed, since `tauri ios init` only installs the iOS ones.
+pub fn ensure_rust_targets() -> Result<()> {
+  let installed = crate::interface::rust::installation::installed_targets().unwrap_or_default();
+  let missing = ["arm64", "x86_64"]
+    .into_iter()
+    .filter_map(rust_triple)
+    .map(|(_, triple)| triple)
+    .filter(|triple| !installed.iter().any(|t| t == triple))
+    .collect::<Vec<_>>();
+  if missing.is_empty() {
+    Ok(())
+  } else {
+    Err(
+      anyhow::anyhow!(
+        "the Mac Catalyst Rust targets are not installed, run `rustup target add {}`",
+        missing.join(" ")
+      )
+      .context(ErrorKind::InvalidConfig),
+    )
+  }
+}
+
+/// Archives the Mac Catalyst variant of the app, returning the path of the archived app.
+pub fn archive(
+  config: &AppleConfig,
+  vars: &HashMap<String, OsString>,
+  profile: Profile,
+  build_number: Option<u32>,
+) -> Result<PathBuf> {
+  let archive_path = config
+    .archive_dir()
+    .join(format!("{}-maccatalyst.xcarchive", config.scheme()));
+  let mut command = Command::new("xcodebuild");
+  command
+    .envs(vars)
+    .arg("-scheme")
+    .arg(config.scheme())
+    .arg("-workspace")
+    .arg(config.workspace_path())
+    .args(["-configuration", configuration_name(profile)])
+    .args(["-destination", DESTINATION]);
+  if let Some(build_number) = build_number {
+    command.arg(format!("CURRENT_PROJECT_VERSION={build_number}"));
+  }
+  let status = command
+    .args(["-allowProvisioningUpdates", "archive", "-archivePath"])
+    .arg(&archive_path)
+    .piped()
+    .context("failed to archive the Mac Catalyst app")
+    .context(ErrorKind::BuildFailed)?;
+  if !status.success() {
+    return Err(
+      anyhow::anyhow!("xcodebuild failed to archive the Mac Catalyst app ({status})")
+        .context(ErrorKind::BuildFailed),
+    );
+  }
+
+  let applications = archive_path.join("Products/Applications");
+  std::fs::read_dir(&applications)
+    .ok()
+    .and_then(|entries| {
+      entries
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "app"))
+    })
+    .ok_or_else(|| {
+      anyhow::anyhow!(
+        "could not find the archived app in {}",
+        applications.display()
+      )
+      .context(ErrorKind::BuildFailed)
+    })
+}
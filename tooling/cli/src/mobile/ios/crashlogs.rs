@@ -0,0 +1,378 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{
+  bundle_identifier, device_prompt, ensure_xcode, env, get_app, get_config, ErrorKind,
+  SimulatorOptions,
+};
+use crate::{
+  helpers::config::get as get_tauri_config,
+  interface::{AppInterface, Interface},
+  CommandExt, ConfigValue, Result,
+};
+
+use anyhow::Context;
+use cargo_mobile2::apple::device::DeviceKind;
+use clap::Parser;
+
+use std::{
+  collections::HashMap,
+  fs::{copy, create_dir_all, read_dir, read_to_string, remove_dir_all},
+  path::{Path, PathBuf},
+  process::Command,
+  time::SystemTime,
+};
+
+#[derive(Debug, Clone, Parser)]
+#[clap(
+  about = "Copy the crash reports of the app from a device or simulator",
+  long_about = "Copy the crash reports of the app from a device or simulator.
+Physical devices require Xcode 15 or newer. Simulator crash reports are read from `~/Library/Logs/DiagnosticReports`."
+)]
+pub struct Options {
+  /// The device name or physical device UDID to read the crash reports from.
+  #[clap(env = "TAURI_IOS_DEVICE")]
+  pub device: Option<String>,
+  /// JSON string or path to JSON file to merge with tauri.conf.json
+  #[clap(short, long)]
+  pub config: Option<ConfigValue>,
+  /// Skip prompting for values
+  #[clap(long, env = "CI")]
+  pub ci: bool,
+  /// Include the crash reports of every app instead of only the ones of this app.
+  #[clap(long)]
+  pub all: bool,
+  /// Maximum number of crash reports to copy, most recent first.
+  #[clap(long, default_value_t = 10)]
+  pub limit: usize,
+  /// Directory to copy the crash reports to. Defaults to `crashlogs` in the Xcode project build directory.
+  #[clap(long)]
+  pub out_dir: Option<PathBuf>,
+  /// Symbolicate the crashed thread of the copied reports with `atos`.
+  #[clap(long)]
+  pub symbolicate: bool,
+  /// The dSYM bundle, or a directory of dSYM bundles, used to symbolicate the reports.
+  /// Defaults to the dSYMs of the archive created by `tauri ios build`.
+  #[clap(long, requires = "symbolicate")]
+  pub dsym: Option<PathBuf>,
+}
+
+struct CrashReport {
+  path: PathBuf,
+  bundle_id: Option<String>,
+  modified: SystemTime,
+}
+
+pub fn command(options: Options) -> Result<()> {
+  ensure_xcode()?;
+  let tauri_config = get_tauri_config(
+    tauri_utils::platform::Target::Ios,
+    options.config.as_ref().map(|c| &c.0),
+  )
+  .context(ErrorKind::InvalidConfig)?;
+  let (identifier, config) = {
+    let tauri_config_guard = tauri_config.lock().unwrap();
+    let tauri_config_ = tauri_config_guard.as_ref().unwrap();
+    let interface = AppInterface::new(tauri_config_, None)?;
+    let app = get_app(tauri_config_, &interface);
+    let (config, _metadata) = get_config(
+      &app,
+      tauri_config_,
+      None,
+      &Default::default(),
+      None,
+      Default::default(),
+    )?;
+    (bundle_identifier(tauri_config_), config)
+  };
+
+  let env = env()?;
+  // the simulator crash reports are written on the host, so the simulator does not need to run
+  let device = device_prompt(
+    &env,
+    options.device.as_deref(),
+    None,
+    None,
+    None,
+    SimulatorOptions {
+      skip_boot: true,
+      ..Default::default()
+    },
+    options.ci,
+  )?;
+
+  let pulled_dir = std::env::temp_dir().join(format!("tauri-ios-crashlogs-{}", std::process::id()));
+  let reports_dir = if matches!(device.kind(), DeviceKind::Simulator) {
+    simulator_reports_dir()
+  } else {
+    create_dir_all(&pulled_dir)?;
+    Command::new("xcrun")
+      .args(["devicectl", "device", "copy", "from", "--device"])
+      .arg(device.id())
+      .args(["--domain-type", "systemCrashLogs", "--source", "/"])
+      .arg("--destination")
+      .arg(&pulled_dir)
+      .output_ok()
+      .with_context(|| format!("failed to copy the crash reports from {}", device.name()))
+      .context(ErrorKind::NoDevice)?;
+    pulled_dir.clone()
+  };
+
+  let mut reports = Vec::new();
+  collect_reports(&reports_dir, &mut reports);
+  if !options.all {
+    reports.retain(|report| report.bundle_id.as_deref() == Some(identifier.as_str()));
+  }
+  reports.sort_by(|a, b| b.modified.cmp(&a.modified));
+  reports.truncate(options.limit);
+
+  let out_dir = options
+    .out_dir
+    .clone()
+    .unwrap_or_else(|| config.archive_dir().join("crashlogs"));
+  let copied = copy_reports(&reports, &out_dir);
+  let _ = remove_dir_all(&pulled_dir);
+  let copied = copied?;
+
+  if copied.is_empty() {
+    log::info!(
+      "No crash reports found for {} on {}",
+      if options.all {
+        "any app"
+      } else {
+        identifier.as_str()
+      },
+      device.name()
+    );
+    return Ok(());
+  }
+
+  let dsym = options.dsym.clone().unwrap_or_else(|| {
+    config
+      .archive_dir()
+      .join(format!("{}.xcarchive", config.scheme()))
+      .join("dSYMs")
+  });
+  let dwarf_files = if options.symbolicate {
+    let files = dwarf_files(&dsym);
+    if files.is_empty() {
+      log::warn!(
+        "No dSYM found at {}, the crash reports cannot be symbolicated",
+        dsym.display()
+      );
+    }
+    files
+  } else {
+    HashMap::new()
+  };
+
+  for report in copied {
+    log::info!(action = "Copied"; "crash report {}", report.display());
+    if !dwarf_files.is_empty() {
+      match symbolicate(&report, &dwarf_files) {
+        Ok(frames) => {
+          for frame in frames {
+            println!("  {frame}");
+          }
+        }
+        Err(e) => log::warn!("Failed to symbolicate {}: {e}", report.display()),
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// The simulators write the crash reports of their apps to the crash reports directory of the host.
+fn simulator_reports_dir() -> PathBuf {
+  PathBuf::from(std::env::var_os("HOME").unwrap_or_default())
+    .join("Library")
+    .join("Logs")
+    .join("DiagnosticReports")
+}
+
+/// Finds the most recent crash report of the app on a simulator written after `since`.
+pub fn latest_simulator_report(bundle_id: &str, since: SystemTime) -> Option<PathBuf> {
+  let mut reports = Vec::new();
+  collect_reports(&simulator_reports_dir(), &mut reports);
+  reports
+    .into_iter()
+    .filter(|report| report.modified >= since && report.bundle_id.as_deref() == Some(bundle_id))
+    .max_by_key(|report| report.modified)
+    .map(|report| report.path)
+}
+
+/// Describes the exception and termination reason of the crash, e.g. `EXC_CRASH (SIGABRT)`.
+pub fn crash_summary(path: &Path) -> Option<String> {
+  let contents = read_to_string(path).ok()?;
+  if path.extension().is_some_and(|ext| ext == "ips") {
+    // the JSON body follows the single line header
+    let (_header, body) = contents.split_once('\n')?;
+    let body: serde_json::Value = serde_json::from_str(body).ok()?;
+    let exception = body.get("exception")?;
+    let mut summary = exception.get("type")?.as_str()?.to_string();
+    if let Some(signal) = exception.get("signal").and_then(|s| s.as_str()) {
+      summary.push_str(&format!(" ({signal})"));
+    }
+    if let Some(indicator) = body
+      .pointer("/termination/indicator")
+      .and_then(|i| i.as_str())
+    {
+      summary.push_str(&format!(": {indicator}"));
+    }
+    Some(summary)
+  } else {
+    let field = |name: &str| {
+      contents
+        .lines()
+        .find_map(|line| line.strip_prefix(name))
+        .map(|value| value.trim().to_string())
+    };
+    let mut summary = field("Exception Type:")?;
+    if let Some(reason) = field("Termination Reason:") {
+      summary.push_str(&format!(": {reason}"));
+    }
+    Some(summary)
+  }
+}
+
+fn collect_reports(dir: &Path, reports: &mut Vec<CrashReport>) {
+  let Ok(entries) = read_dir(dir) else {
+    return;
+  };
+  for entry in entries.flatten() {
+    let path = entry.path();
+    if path.is_dir() {
+      collect_reports(&path, reports);
+    } else if path
+      .extension()
+      .is_some_and(|ext| ext == "ips" || ext == "crash")
+    {
+      let modified = entry
+        .metadata()
+        .and_then(|m| m.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+      reports.push(CrashReport {
+        bundle_id: report_bundle_id(&path),
+        path,
+        modified,
+      });
+    }
+  }
+}
+
+/// Reads the bundle identifier of the crashed app from the report header.
+fn report_bundle_id(path: &Path) -> Option<String> {
+  let contents = read_to_string(path).ok()?;
+  if path.extension().is_some_and(|ext| ext == "ips") {
+    // `.ips` reports start with a single line JSON header
+    let header: serde_json::Value = serde_json::from_str(contents.lines().next()?).ok()?;
+    header
+      .get("bundleID")
+      .and_then(|id| id.as_str())
+      .map(ToString::to_string)
+  } else {
+    contents
+      .lines()
+      .find_map(|line| line.strip_prefix("Identifier:"))
+      .map(|id| id.trim().to_string())
+  }
+}
+
+fn copy_reports(reports: &[CrashReport], out_dir: &Path) -> Result<Vec<PathBuf>> {
+  create_dir_all(out_dir)
+    .with_context(|| format!("failed to create directory {}", out_dir.display()))?;
+  reports
+    .iter()
+    .map(|report| {
+      let dest = out_dir.join(report.path.file_name().unwrap());
+      copy(&report.path, &dest)
+        .with_context(|| format!("failed to copy {}", report.path.display()))?;
+      Ok(dest)
+    })
+    .collect()
+}
+
+/// Maps the binary names to their DWARF file, from a dSYM bundle or a directory of dSYM bundles.
+fn dwarf_files(dsym: &Path) -> HashMap<String, PathBuf> {
+  let bundles = if dsym.extension().is_some_and(|ext| ext == "dSYM") {
+    vec![dsym.to_path_buf()]
+  } else {
+    read_dir(dsym)
+      .map(|entries| {
+        entries
+          .flatten()
+          .map(|entry| entry.path())
+          .filter(|path| path.extension().is_some_and(|ext| ext == "dSYM"))
+          .collect()
+      })
+      .unwrap_or_default()
+  };
+
+  bundles
+    .iter()
+    .filter_map(|bundle| read_dir(bundle.join("Contents/Resources/DWARF")).ok())
+    .flat_map(|entries| entries.flatten())
+    .map(|entry| {
+      (
+        entry.file_name().to_string_lossy().into_owned(),
+        entry.path(),
+      )
+    })
+    .collect()
+}
+
+/// Symbolicates the frames of the crashed thread of an `.ips` report with `atos`.
+fn symbolicate(report: &Path, dwarf_files: &HashMap<String, PathBuf>) -> Result<Vec<String>> {
+  let contents = read_to_string(report)?;
+  let (_header, body) = contents
+    .split_once('\n')
+    .ok_or_else(|| anyhow::anyhow!("only `.ips` crash reports can be symbolicated"))?;
+  let body: serde_json::Value =
+    serde_json::from_str(body).context("only `.ips` crash reports can be symbolicated")?;
+
+  let images = body["usedImages"].as_array().cloned().unwrap_or_default();
+  let faulting_thread = body["faultingThread"].as_u64().unwrap_or_default() as usize;
+  let frames = body["threads"][faulting_thread]["frames"]
+    .as_array()
+    .cloned()
+    .unwrap_or_default();
+
+  let mut symbolicated = Vec::new();
+  for (index, frame) in frames.iter().enumerate() {
+    let image = frame["imageIndex"]
+      .as_u64()
+      .and_then(|i| images.get(i as usize));
+    let image_name = image
+      .and_then(|image| image["name"].as_str())
+      .unwrap_or("???");
+    let image_base = image
+      .and_then(|image| image["base"].as_u64())
+      .unwrap_or_default();
+    let offset = frame["imageOffset"].as_u64().unwrap_or_default();
+
+    let symbol = match (image, dwarf_files.get(image_name)) {
+      (Some(image), Some(dwarf)) => {
+        let arch = image["arch"].as_str().unwrap_or("arm64");
+        let output = Command::new("atos")
+          .arg("-o")
+          .arg(dwarf)
+          .args(["-arch", arch])
+          .arg("-l")
+          .arg(format!("{image_base:#x}"))
+          .arg(format!("{:#x}", image_base + offset))
+          .output_ok()
+          .context("failed to run atos")?;
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+      }
+      _ => frame["symbol"]
+        .as_str()
+        .map(ToString::to_string)
+        .unwrap_or_else(|| format!("{offset:#x}")),
+    };
+    symbolicated.push(format!("{index:<3} {image_name:<30} {symbol}"));
+  }
+
+  Ok(symbolicated)
+}
@@ -3,8 +3,18 @@
 // SPDX-License-Identifier: MIT
 
 use super::{
-  configure_cargo, device_prompt, ensure_init, env, get_app, get_config, inject_assets,
-  merge_plist, open_and_wait, MobileTarget,
+  apply_build_settings, build_env, built_app_path, bundle_identifier,
+  bundle_identifier_build_setting, cargo_profile_args, clean_xcode_project, configure_cargo,
+  crashlogs, derived_data_build_settings, device_prompt, disable_code_signing, ensure_init,
+  ensure_minimum_xcode_version, ensure_xcode, env,
+  features_matrix::merge_features,
+  find_xcode_file, frameworks_manifest, get_app, get_config, info_plist_files, inject_assets,
+  log_file, merge_associated_domains, merge_bundle_id_suffix, merge_info_plist,
+  metal_build_settings, open_and_wait, parse_build_setting, print_command, privacy_manifest,
+  project_env_vars, provisioning_profile_from_env,
+  simctl::{self, Appearance, PrivacyService},
+  suppress_development_team_warning, url_types_plist, wait_forever, xcodebuild_env_vars,
+  ConfigOptions, ErrorKind, MobileTarget, PlistKind, SimulatorOptions, XcodeOpenTarget,
 };
 use crate::{
   dev::Options as DevOptions,
@@ -15,7 +25,7 @@ use crate::{
   },
   interface::{AppInterface, AppSettings, Interface, MobileOptions, Options as InterfaceOptions},
   mobile::{write_options, CliOptions, DevChild, DevProcess},
-  ConfigValue, Result,
+  CommandExt, ConfigValue, Result,
 };
 use clap::{ArgAction, Parser};
 
@@ -24,18 +34,59 @@ use cargo_mobile2::{
   apple::{
     config::Config as AppleConfig,
     device::{Device, DeviceKind},
+    target::Target,
   },
   config::app::App,
   env::Env,
   opts::{NoiseLevel, Profile},
+  ChildHandle,
 };
 
 use std::{
+  collections::HashMap,
   env::set_current_dir,
+  ffi::OsString,
+  fs::create_dir_all,
   net::{IpAddr, Ipv4Addr, SocketAddr},
+  path::{Path, PathBuf},
+  process::Command,
   sync::OnceLock,
+  time::{Duration, Instant, SystemTime},
 };
 
+const SCREENSHOT_DELAY: Duration = Duration::from_secs(5);
+const INSTALL_RETRY_DELAY: Duration = Duration::from_secs(3);
+const FRONTEND_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const SMOKE_TEST_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// How long to wait for the crash report of an app that exited during the smoke test.
+const CRASH_REPORT_WAIT: Duration = Duration::from_secs(10);
+
+/// Install errors that usually go away on their own, e.g. while the device shows the trust dialog.
+const TRANSIENT_INSTALL_ERRORS: &[&str] = &[
+  "device is busy",
+  "kamdbusyerror",
+  "device is locked",
+  "kamdmobileimagemounterdevicelocked",
+  "trust this computer",
+  "pairing",
+  "timed out",
+  "connection was interrupted",
+  "lost connection",
+  "0xe8000065",
+];
+
+/// Install errors that retrying cannot fix, checked before the transient ones.
+const PERMANENT_INSTALL_ERRORS: &[&str] = &[
+  "signature",
+  "code signing",
+  "codesign",
+  "provisioning profile",
+  "entitlement",
+  "0xe8008015",
+  "0xe800801c",
+  "0xe8008016",
+];
+
 #[derive(Debug, Clone, Parser)]
 #[clap(
   about = "Run your app in development mode on iOS",
@@ -70,8 +121,97 @@ pub struct Options {
   /// Open Xcode instead of trying to run on a connected device
   #[clap(short, long)]
   pub open: bool,
-  /// Runs on the given device name
+  /// The Xcode file to open. Defaults to the workspace when it exists, falling back to the project.
+  #[clap(long, value_enum)]
+  pub open_target: Option<XcodeOpenTarget>,
+  /// Restricts the targets the app is built for. Running fails early when the selected device or simulator
+  /// architecture is not one of them, instead of failing to install the app.
+  ///
+  /// When opening Xcode, the first target is used to resolve the cargo features. Defaults to the target of the selected device.
+  #[clap(
+    short,
+    long = "target",
+    action = ArgAction::Append,
+    num_args(0..),
+    value_parser(clap::builder::PossibleValuesParser::new(Target::name_list()))
+  )]
+  pub targets: Vec<String>,
+  /// Runs on the given device name, or physical device UDID. Falls back to the `TAURI_IOS_DEVICE` environment variable.
+  #[clap(env = "TAURI_IOS_DEVICE")]
   pub device: Option<String>,
+  /// Skip prompting for values
+  #[clap(long, env = "CI")]
+  pub ci: bool,
+  /// Runs on the connected device at the given zero-based index, without prompting.
+  /// Devices are ordered by name, then UDID, the order listed by the device prompt. Simulators are not considered.
+  #[clap(long, value_name = "N", conflicts_with_all = ["device", "open"])]
+  pub device_index: Option<usize>,
+  /// Only runs on a connected device whose iOS version matches the requirement,
+  /// e.g. `16` for any iOS 16.x release or `>=16.4, <17`. Simulators are not considered.
+  #[clap(long)]
+  pub os_version: Option<semver::VersionReq>,
+  /// Waits for a physical device to be connected when none is detected, for up to the given number of seconds.
+  /// Simulators are not considered.
+  #[clap(
+    long,
+    value_name = "SECONDS",
+    num_args = 0..=1,
+    default_missing_value = "60",
+    conflicts_with = "open"
+  )]
+  pub wait_for_device: Option<u64>,
+  /// Wait until the frontend dev server responds to HTTP requests before installing and launching the app,
+  /// for up to the given number of seconds (defaults to 60), to avoid a blank screen on slow frontend tooling.
+  #[clap(long, value_name = "SECONDS", num_args = 0..=1, default_missing_value = "60")]
+  pub wait_for_frontend: Option<u64>,
+  /// Runs on a simulator of the given runtime, e.g. `iOS 16.4` or `16.4`. Connected devices are not considered.
+  ///
+  /// Combined with a device name, e.g. `tauri ios dev "iPhone 15" --runtime "iOS 16.4"`,
+  /// the simulator is created when the device type exists but not for that runtime.
+  #[clap(long, conflicts_with_all = ["device_index", "os_version", "wait_for_device", "open"])]
+  pub runtime: Option<String>,
+  /// Sets the simulator appearance before launching the app. Defaults to keeping the current appearance.
+  #[clap(long, value_enum)]
+  pub appearance: Option<Appearance>,
+  /// Sets the simulator language before launching the app, e.g. `fr` or `pt-BR`. Defaults to keeping the current language.
+  #[clap(long, value_name = "LANGUAGE", value_parser = simctl::parse_language)]
+  pub locale: Option<String>,
+  /// Sets the simulator region before launching the app, e.g. `CA`. Defaults to keeping the current region.
+  #[clap(long, value_parser = simctl::parse_region)]
+  pub region: Option<String>,
+  /// Overrides the simulator status bar with full battery and signal before launching the app, for clean screenshots.
+  ///
+  /// Only supported when running on a simulator.
+  #[clap(long)]
+  pub clean_status_bar: bool,
+  /// The time shown in the status bar overridden by `--clean-status-bar`. Defaults to `9:41`.
+  #[clap(long, value_name = "TIME", requires = "clean_status_bar")]
+  pub status_bar_time: Option<String>,
+  /// Erases the simulator content and settings before launching the app.
+  #[clap(long)]
+  pub reset_simulator: bool,
+  /// Resets the app permission for the given privacy service on the simulator before launching the app,
+  /// so the permission prompt shows again. Can be used multiple times.
+  ///
+  /// Only supported when running on a simulator.
+  #[clap(long = "reset-permission", value_name = "SERVICE", value_enum, action = ArgAction::Append)]
+  pub reset_permissions: Vec<PrivacyService>,
+  /// Ignore the cached simulator list and query the available simulators again.
+  #[clap(long)]
+  pub refresh_devices: bool,
+  /// Runs on the booted simulator without prompting, failing if none or several simulators are booted.
+  /// Connected devices are not considered.
+  #[clap(
+    long,
+    conflicts_with_all = ["device", "device_index", "runtime", "os_version", "wait_for_device", "open"]
+  )]
+  pub booted: bool,
+  /// Creates the simulator named by the device argument when it does not exist, e.g. on fresh CI images,
+  /// for the `--runtime` or the latest installed iOS runtime. The device name must be a simulator device type, e.g. `iPhone 15`.
+  ///
+  /// Delete the created simulators with `tauri ios simulators clean`.
+  #[clap(long, requires = "device", conflicts_with_all = ["booted", "open"])]
+  pub create_simulator: bool,
   /// Force prompting for an IP to use to connect to the dev server on mobile.
   #[clap(long)]
   pub force_ip_prompt: bool,
@@ -89,12 +229,147 @@ pub struct Options {
   /// on the public network address.
   #[clap(long)]
   pub host: Option<Option<IpAddr>>,
+  /// Name of the network interface, e.g. `en0`, whose address the device uses to reach this machine.
+  ///
+  /// Useful for wirelessly paired devices on machines with several interfaces, e.g. VPN, Wi-Fi and Ethernet,
+  /// where the detected address is not routable from the device. Equivalent to `--host` with the interface address.
+  #[clap(
+    long,
+    value_name = "NAME",
+    env = "TAURI_IOS_NETWORK_INTERFACE",
+    conflicts_with_all = ["host", "force_ip_prompt"]
+  )]
+  pub network_interface: Option<String>,
   /// Disable the built-in dev server for static files.
   #[clap(long)]
   pub no_dev_server: bool,
   /// Specify port for the built-in dev server for static files. Defaults to 1430.
   #[clap(long, env = "TAURI_CLI_PORT")]
   pub port: Option<u16>,
+  /// Sets a build-time environment variable (KEY=VALUE) for the Rust build. Can be used multiple times.
+  #[clap(long = "build-env", value_name = "KEY=VALUE", value_parser = build_env::parse_key_value, action = ArgAction::Append)]
+  pub build_env: Vec<(String, String)>,
+  /// Path to a `.env` file with build-time environment variables.
+  ///
+  /// Variables set with `--build-env` take priority over the ones defined in this file.
+  #[clap(long)]
+  pub build_env_file: Option<PathBuf>,
+  /// Also write the build-time environment variables to the app Info.plist.
+  #[clap(long)]
+  pub build_env_plist: bool,
+  /// Sets an Xcode build setting (KEY=VALUE) for the xcodebuild invocation. Can be used multiple times.
+  ///
+  /// These settings override the ones defined in the Xcode project, e.g. `--build-setting SWIFT_VERSION=5.0`.
+  #[clap(long = "build-setting", value_name = "KEY=VALUE", value_parser = parse_build_setting, action = ArgAction::Append)]
+  pub build_settings: Vec<(String, String)>,
+  /// Appended to the `identifier` to build the app bundle identifier, e.g. `.internal`,
+  /// to install the app side by side with the production build. Overrides `bundle > iOS > bundleIdSuffix`.
+  #[clap(long, env = "TAURI_IOS_BUNDLE_ID_SUFFIX")]
+  pub bundle_id_suffix: Option<String>,
+  /// Also write the output of the command, including the xcodebuild output, to the given file.
+  ///
+  /// The parent directories are created if needed.
+  #[clap(long, value_name = "PATH")]
+  pub log_file: Option<PathBuf>,
+  /// Captures a screenshot of the simulator a few seconds after the app is launched and saves it to the given path.
+  ///
+  /// Only supported when running on a simulator.
+  #[clap(long, value_name = "PATH")]
+  pub screenshot: Option<PathBuf>,
+  /// Builds and installs the app on the device without launching it, e.g. to start it from a debugger.
+  ///
+  /// The dev server keeps running until the command is stopped.
+  #[clap(long, conflicts_with_all = ["open", "screenshot", "launch_args"])]
+  pub install_only: bool,
+  /// Path to a JSON or TOML features matrix file describing the cargo features to enable
+  /// for device and simulator targets and debug and release configurations.
+  ///
+  /// The resolved features are merged with the ones passed with `--features`.
+  #[clap(long, value_name = "PATH")]
+  pub features_matrix: Option<PathBuf>,
+  /// Argument passed to the app process when it is launched. Can be used multiple times.
+  ///
+  /// The app is launched with `xcrun simctl launch` on simulators
+  /// and with `xcrun devicectl` on physical devices, which requires Xcode 16 or newer.
+  #[clap(
+    long = "launch-arg",
+    value_name = "ARG",
+    action = ArgAction::Append,
+    allow_hyphen_values = true
+  )]
+  pub launch_args: Vec<String>,
+  /// Do not warn when the development team is not configured, e.g. when relying on Xcode-managed signing.
+  #[clap(long, env = "TAURI_IOS_NO_DEVELOPMENT_TEAM_WARNING")]
+  pub no_development_team_warning: bool,
+  /// Print where each resolved iOS config value comes from: environment variables, `tauri.conf.json` or defaults.
+  #[clap(long)]
+  pub explain_config: bool,
+  /// Skip code signing. Only supported when running on simulators.
+  #[clap(long)]
+  pub no_codesign: bool,
+  /// Clean the build products of the Xcode project before running to avoid stale incremental builds.
+  #[clap(long)]
+  pub clean: bool,
+  /// Directory for the build products and intermediates of xcodebuild, like its `-derivedDataPath`,
+  /// instead of the shared Xcode DerivedData directory. Combined with `--clean`, the build directories created in it are removed.
+  #[clap(long, value_name = "PATH")]
+  pub derived_data: Option<PathBuf>,
+  /// The cargo profile used to build the Rust library, e.g. a custom `release-with-debug` profile,
+  /// independently of the Xcode configuration. Custom profiles must be defined in the Cargo.toml of the app or its workspace.
+  #[clap(long, value_name = "NAME")]
+  pub cargo_profile: Option<String>,
+  /// Print the xcodebuild command, with the environment variables Tauri sets for it, and exit without running the app.
+  ///
+  /// Environment variable values that look like secrets are redacted.
+  #[clap(long, conflicts_with_all = ["open", "install_only", "screenshot"])]
+  pub print_command: bool,
+  /// How many times installing the app on a physical device is retried when it fails with a transient error,
+  /// e.g. when the device is busy or waiting for the trust dialog.
+  ///
+  /// Signing errors are never retried.
+  #[clap(long, default_value_t = 2, env = "TAURI_IOS_INSTALL_RETRIES")]
+  pub install_retries: u32,
+  /// Launch the app under the given Instruments template, e.g. `Time Profiler`, and open the recorded trace.
+  ///
+  /// The recording stops when the app exits. Run `xcrun xctrace list templates` to list the available templates.
+  #[clap(
+    long,
+    value_name = "TEMPLATE",
+    conflicts_with_all = ["open", "install_only", "screenshot", "print_command"]
+  )]
+  pub profile: Option<String>,
+  /// Name of an app extension target of the Xcode project, e.g. a widget or share extension,
+  /// to attach to alongside the app. The extension is installed with the app and its output is streamed to the console.
+  ///
+  /// Only supported when running on a simulator.
+  #[clap(
+    long,
+    value_name = "NAME",
+    conflicts_with_all = ["open", "install_only", "print_command", "profile"]
+  )]
+  pub run_extension: Option<String>,
+  /// Launch the app suspended and drop into an LLDB session attached to it, to debug the native side of the app.
+  ///
+  /// On simulators LLDB attaches to the app process directly.
+  /// On physical devices LLDB attaches through the debugserver of the device, which requires Xcode 16 or newer.
+  #[clap(
+    long,
+    conflicts_with_all = ["open", "install_only", "screenshot", "print_command", "profile", "run_extension"]
+  )]
+  pub debug: bool,
+  /// Installs and launches the app, then checks that it is still running after the given number of seconds
+  /// (defaults to 10) and exits, as a launch smoke test for CI.
+  ///
+  /// When the app exits earlier, e.g. because it crashed on launch, the command fails with the exit code 16
+  /// and reports the crash report of the app. Only supported when running on a simulator.
+  #[clap(
+    long,
+    value_name = "SECONDS",
+    num_args = 0..=1,
+    default_missing_value = "10",
+    conflicts_with_all = ["open", "install_only", "print_command", "profile", "run_extension", "debug"]
+  )]
+  pub smoke_test: Option<u64>,
 }
 
 impl From<Options> for DevOptions {
@@ -117,20 +392,100 @@ impl From<Options> for DevOptions {
 }
 
 pub fn command(options: Options, noise_level: NoiseLevel) -> Result<()> {
+  let log_file = options.log_file.as_deref().map(log_file::tee).transpose()?;
   let result = run_command(options, noise_level);
-  if result.is_err() {
+  if let Err(e) = &result {
     crate::dev::kill_before_dev_process();
+    if let Some(log_file) = &log_file {
+      log_file.record_error(e);
+    }
   }
   result
 }
 
-fn run_command(options: Options, noise_level: NoiseLevel) -> Result<()> {
-  let env = env()?;
+fn run_command(mut options: Options, noise_level: NoiseLevel) -> Result<()> {
+  let developer_dir = ensure_xcode()?;
+  if let Some(suffix) = &options.bundle_id_suffix {
+    merge_bundle_id_suffix(&mut options.config, suffix);
+  }
+  let tauri_config = get_tauri_config(
+    tauri_utils::platform::Target::Ios,
+    options.config.as_ref().map(|c| &c.0),
+  )
+  .context(ErrorKind::InvalidConfig)?;
+  let tauri_config_guard = tauri_config.lock().unwrap();
+  let tauri_config_ = tauri_config_guard.as_ref().unwrap();
+  ensure_minimum_xcode_version(tauri_config_, &developer_dir)?;
+  if options.no_development_team_warning || options.no_codesign {
+    suppress_development_team_warning();
+  }
+  if let Some(interface) = &options.network_interface {
+    options.host = Some(Some(network_interface_address(interface)?));
+  }
+  // inserted first so an explicit `--build-setting PRODUCT_BUNDLE_IDENTIFIER=` wins
+  if let Some(setting) = bundle_identifier_build_setting(tauri_config_) {
+    options.build_settings.insert(0, setting);
+  }
+  // inserted first so an explicit `--build-setting` wins
+  options
+    .build_settings
+    .splice(0..0, metal_build_settings(tauri_config_)?);
+  if let Some(derived_data) = &mut options.derived_data {
+    // resolved before changing the current directory
+    *derived_data = std::env::current_dir()?.join(&*derived_data);
+    options
+      .build_settings
+      .splice(0..0, derived_data_build_settings(derived_data));
+  }
+  if let Some(template) = &options.profile {
+    validate_instruments_template(template)?;
+  }
+
+  let build_env = build_env::resolve(options.build_env_file.as_deref(), &options.build_env)?;
+  let mut env = env()?;
+  for (key, value) in &build_env {
+    env.insert_env_var(key.clone(), value.clone());
+  }
+
   let device = if options.open {
     None
   } else {
-    match device_prompt(&env, options.device.as_deref()) {
+    let simulator_options = SimulatorOptions {
+      appearance: options.appearance,
+      language: options.locale.clone(),
+      region: options.region.clone(),
+      reset: options.reset_simulator,
+      refresh_list: options.refresh_devices,
+      runtime: options.runtime.clone(),
+      booted: options.booted,
+      create: options.create_simulator,
+      clean_status_bar_time: options.clean_status_bar.then(|| {
+        options
+          .status_bar_time
+          .clone()
+          .unwrap_or_else(|| simctl::CLEAN_STATUS_BAR_TIME.into())
+      }),
+      skip_boot: false,
+    };
+    match device_prompt(
+      &env,
+      options.device.as_deref(),
+      options.device_index,
+      options.os_version.as_ref(),
+      options.wait_for_device.map(Duration::from_secs),
+      simulator_options,
+      options.ci,
+    ) {
       Ok(d) => Some(d),
+      // opening Xcode would silently ignore the requested device, OS version requirement or the device we waited for
+      Err(e)
+        if options.booted
+          || options.device_index.is_some()
+          || options.os_version.is_some()
+          || options.wait_for_device.is_some() =>
+      {
+        return Err(e)
+      }
       Err(e) => {
         log::error!("{e}");
         None
@@ -138,32 +493,148 @@ fn run_command(options: Options, noise_level: NoiseLevel) -> Result<()> {
     }
   };
 
-  let mut dev_options: DevOptions = options.clone().into();
+  if let Some(device) = &device {
+    if options.no_codesign && !matches!(device.kind(), DeviceKind::Simulator) {
+      return Err(
+        anyhow::anyhow!(
+          "--no-codesign is only supported on simulators, but {} is a physical device",
+          device.name()
+        )
+        .context(ErrorKind::InvalidConfig),
+      );
+    }
+
+    if options.reset_simulator && !matches!(device.kind(), DeviceKind::Simulator) {
+      return Err(
+        anyhow::anyhow!(
+          "--reset-simulator can only be used with simulators, refusing to erase physical device {}",
+          device.name()
+        )
+        .context(ErrorKind::InvalidConfig),
+      );
+    }
+
+    if options.clean_status_bar && !matches!(device.kind(), DeviceKind::Simulator) {
+      return Err(
+        anyhow::anyhow!(
+          "overriding the status bar is only supported on simulators, but {} is a physical device",
+          device.name()
+        )
+        .context(ErrorKind::InvalidConfig),
+      );
+    }
+
+    if !options.reset_permissions.is_empty() {
+      if !matches!(device.kind(), DeviceKind::Simulator) {
+        return Err(
+          anyhow::anyhow!(
+            "resetting permissions is only supported on simulators, but {} is a physical device",
+            device.name()
+          )
+          .context(ErrorKind::InvalidConfig),
+        );
+      }
+      reset_permissions(
+        device,
+        &options.reset_permissions,
+        &bundle_identifier(tauri_config_),
+      )?;
+    }
+
+    if options.screenshot.is_some() && !matches!(device.kind(), DeviceKind::Simulator) {
+      return Err(
+        anyhow::anyhow!(
+          "capturing a screenshot is only supported on simulators, but {} is a physical device",
+          device.name()
+        )
+        .context(ErrorKind::InvalidConfig),
+      );
+    }
+
+    if options.smoke_test.is_some() && !matches!(device.kind(), DeviceKind::Simulator) {
+      return Err(
+        anyhow::anyhow!(
+          "the launch smoke test is only supported on simulators, but {} is a physical device",
+          device.name()
+        )
+        .context(ErrorKind::InvalidConfig),
+      );
+    }
+
+    if options.run_extension.is_some() && !matches!(device.kind(), DeviceKind::Simulator) {
+      return Err(
+        anyhow::anyhow!(
+          "running an app extension is only supported on simulators, but {} is a physical device",
+          device.name()
+        )
+        .context(ErrorKind::InvalidConfig),
+      );
+    }
+
+    if !matches!(device.kind(), DeviceKind::Simulator) {
+      if let Some(provisioning_profile) = provisioning_profile_from_env(tauri_config_, false)? {
+        ensure_device_provisioned(device, &provisioning_profile)?;
+      }
+    }
+
+    if !options.targets.is_empty() {
+      ensure_device_target_built(device, &options.targets)?;
+    }
+  }
+
   let target_triple = device
     .as_ref()
     .map(|d| d.target().triple.to_string())
+    .or_else(|| {
+      options
+        .targets
+        .first()
+        .map(|t| Target::all().get(t.as_str()).unwrap().triple.to_string())
+    })
     .unwrap_or_else(|| "aarch64-apple-ios".into());
+  merge_features(
+    &mut options.features,
+    options.features_matrix.as_deref(),
+    &target_triple,
+    !options.release_mode,
+  )
+  .context(ErrorKind::InvalidConfig)?;
+
+  let mut dev_options: DevOptions = options.clone().into();
   dev_options.target = Some(target_triple.clone());
 
-  let tauri_config = get_tauri_config(
-    tauri_utils::platform::Target::Ios,
-    options.config.as_ref().map(|c| &c.0),
-  )?;
   let (interface, app, config) = {
-    let tauri_config_guard = tauri_config.lock().unwrap();
-    let tauri_config_ = tauri_config_guard.as_ref().unwrap();
-
     let interface = AppInterface::new(tauri_config_, Some(target_triple))?;
 
     let app = get_app(tauri_config_, &interface);
+    // the frameworks are only downloaded by the commands building the app, before they are read by `get_config`
+    frameworks_manifest::install(&tauri_dir())?;
     let (config, _metadata) = get_config(
       &app,
       tauri_config_,
       dev_options.features.as_ref(),
       &Default::default(),
-    );
+      Some(if options.release_mode {
+        Profile::Release
+      } else {
+        Profile::Debug
+      }),
+      ConfigOptions {
+        explain: options.explain_config,
+        ..Default::default()
+      },
+    )?;
     (interface, app, config)
   };
+  // `ensure_init` locks the configuration itself
+  drop(tauri_config_guard);
+  for (key, value) in project_env_vars(&config) {
+    env.insert_env_var(key, value);
+  }
+  if options.no_codesign {
+    disable_code_signing(&mut env);
+  }
+  apply_build_settings(&config, &mut env, &options.build_settings)?;
 
   let tauri_path = tauri_dir();
   set_current_dir(&tauri_path).with_context(|| "failed to change current working directory")?;
@@ -173,20 +644,64 @@ fn run_command(options: Options, noise_level: NoiseLevel) -> Result<()> {
     config.app(),
     config.project_dir(),
     MobileTarget::Ios,
-  )?;
-  inject_assets(&config)?;
+  )
+  .context(ErrorKind::InvalidConfig)?;
+  let tauri_config_guard = tauri_config.lock().unwrap();
+  let tauri_config_ = tauri_config_guard.as_ref().unwrap();
+  inject_assets(&config, tauri_config_)?;
+  if let Some(extension) = &options.run_extension {
+    ensure_extension_target(&config, extension)?;
+  }
 
-  let info_plist_path = config
-    .project_dir()
-    .join(config.scheme())
-    .join("Info.plist");
-  merge_plist(
-    vec![
-      tauri_path.join("Info.plist").into(),
-      tauri_path.join("Info.ios.plist").into(),
-    ],
-    &info_plist_path,
+  let mut src_plists: Vec<PlistKind> = Vec::new();
+  // generated from the configuration first so the plist files can override it
+  src_plists.extend(url_types_plist(tauri_config_).map(PlistKind::from));
+  src_plists.push(tauri_path.join("Info.plist").into());
+  src_plists.push(tauri_path.join("Info.ios.plist").into());
+  let mut build_plists = info_plist_files(
+    tauri_config_,
+    &tauri_path,
+    if options.release_mode {
+      Profile::Release
+    } else {
+      Profile::Debug
+    },
   )?;
+  if options.build_env_plist && !build_env.is_empty() {
+    build_plists.push(build_env::to_plist(&build_env).into());
+  }
+  merge_info_plist(&config, src_plists, build_plists)?;
+  merge_associated_domains(&config, tauri_config_)?;
+  privacy_manifest::sync(&config, tauri_config_)?;
+  drop(tauri_config_guard);
+
+  if options.print_command {
+    let device = device.as_ref().ok_or_else(|| {
+      anyhow::anyhow!("no device or simulator found to print the xcodebuild command for")
+        .context(ErrorKind::NoDevice)
+    })?;
+    let profile = if options.release_mode {
+      Profile::Release
+    } else {
+      Profile::Debug
+    };
+    // the printed command builds the Rust library with the cargo configuration written by the dev command
+    configure_cargo(&app, None)?;
+    print_command::print(
+      &xcodebuild_env_vars(
+        &config,
+        &build_env,
+        options.no_codesign,
+        &options.build_settings,
+      ),
+      &print_command::build_args(&config, device.target(), profile),
+    );
+    return Ok(());
+  }
+
+  if options.clean {
+    clean_xcode_project(&config, options.derived_data.as_deref())?;
+  }
 
   run_dev(
     interface,
@@ -195,12 +710,82 @@ fn run_command(options: Options, noise_level: NoiseLevel) -> Result<()> {
     tauri_config,
     device,
     env,
+    build_env,
     &app,
     &config,
     noise_level,
   )
 }
 
+/// Checks that the device is included in the provisioning profile so the install does not fail later on.
+fn ensure_device_provisioned(
+  device: &Device<'_>,
+  provisioning_profile: &tauri_macos_sign::ProvisioningProfile,
+) -> Result<()> {
+  // App Store and enterprise profiles are not restricted to a list of devices
+  if let Some(provisioned_devices) = provisioning_profile.provisioned_devices()? {
+    if !provisioned_devices
+      .iter()
+      .any(|udid| udid.eq_ignore_ascii_case(device.id()))
+    {
+      return Err(
+        anyhow::anyhow!(
+          "device {} ({}) is not included in the provisioning profile. Register the device in your Apple Developer account, add it to the provisioning profile and download it again",
+          device.name(),
+          device.id()
+        )
+        .context(ErrorKind::SigningMissing),
+      );
+    }
+  }
+  Ok(())
+}
+
+/// Checks that the device architecture is one of the targets to build so the install does not fail later on.
+fn ensure_device_target_built(device: &Device<'_>, targets: &[String]) -> Result<()> {
+  let triple = device.target().triple;
+  if targets
+    .iter()
+    .any(|t| Target::all().get(t.as_str()).unwrap().triple == triple)
+  {
+    return Ok(());
+  }
+
+  let kind = if matches!(device.kind(), DeviceKind::Simulator) {
+    "simulator"
+  } else {
+    "device"
+  };
+  let suggestion = Target::all()
+    .iter()
+    .find(|(_, target)| target.triple == triple)
+    .map(|(name, _)| format!(", use `--target {name}` to build it"))
+    .unwrap_or_default();
+  Err(
+    anyhow::anyhow!(
+      "{kind} {} requires the {triple} target, which is not one of the targets to build ({}){suggestion}",
+      device.name(),
+      targets.join(", ")
+    )
+    .context(ErrorKind::InvalidConfig),
+  )
+}
+
+/// Resets the app permissions for the privacy services on the simulator.
+fn reset_permissions(
+  device: &Device<'_>,
+  services: &[PrivacyService],
+  bundle_id: &str,
+) -> Result<()> {
+  let simulator = simctl::find_udid(device.id())?;
+  simctl::wait_booted(&simulator)?;
+  for service in services {
+    log::info!("Resetting the {service} permission of {bundle_id}");
+    simctl::reset_privacy(&simulator, *service, bundle_id)?;
+  }
+  Ok(())
+}
+
 fn local_ip_address(force: bool) -> &'static IpAddr {
   static LOCAL_IP: OnceLock<IpAddr> = OnceLock::new();
   LOCAL_IP.get_or_init(|| {
@@ -245,6 +830,37 @@ fn local_ip_address(force: bool) -> &'static IpAddr {
   })
 }
 
+/// Resolves the address of a network interface, preferring IPv4 since the dev server cannot be pinged over IPv6.
+fn network_interface_address(name: &str) -> Result<IpAddr> {
+  let interfaces =
+    local_ip_address::list_afinet_netifas().context("failed to list the network interfaces")?;
+  let mut addresses = interfaces
+    .iter()
+    .filter(|(interface, ip)| interface == name && !ip.is_loopback())
+    .map(|(_, ip)| *ip)
+    .collect::<Vec<_>>();
+  addresses.sort_by_key(|ip| ip.is_ipv6());
+  if let Some(ip) = addresses.first() {
+    log::info!("Using {ip} of the {name} network interface to access the development server.");
+    return Ok(*ip);
+  }
+
+  let mut names = interfaces
+    .iter()
+    .filter(|(_, ip)| !ip.is_loopback())
+    .map(|(interface, _)| interface.as_str())
+    .collect::<Vec<_>>();
+  names.sort_unstable();
+  names.dedup();
+  Err(
+    anyhow::anyhow!(
+      "network interface `{name}` does not exist or has no address. Available interfaces: {}",
+      names.join(", ")
+    )
+    .context(ErrorKind::InvalidConfig),
+  )
+}
+
 fn use_network_address_for_dev_url(
   config: &ConfigHandle,
   options: &mut Options,
@@ -340,6 +956,7 @@ fn run_dev(
   tauri_config: ConfigHandle,
   device: Option<Device>,
   env: Env,
+  build_env: HashMap<String, OsString>,
   app: &App,
   config: &AppleConfig,
   noise_level: NoiseLevel,
@@ -368,8 +985,20 @@ fn run_dev(
   configure_cargo(app, None)?;
 
   let open = options.open;
+  let open_target = options.open_target;
+  let screenshot = options.screenshot.clone();
+  let launch_args = options.launch_args.clone();
+  let install_only = options.install_only;
+  let install_retries = options.install_retries;
+  let profile_template = options.profile.clone();
+  let run_extension = options.run_extension.clone();
+  let debug = options.debug;
+  let smoke_test = options.smoke_test.map(Duration::from_secs);
+  let wait_for_frontend = options.wait_for_frontend.map(Duration::from_secs);
+  let bundle_id = bundle_identifier(tauri_config.lock().unwrap().as_ref().unwrap());
   let exit_on_panic = options.exit_on_panic;
   let no_watch = options.no_watch;
+  let profile_args = cargo_profile_args(options.cargo_profile.as_deref())?;
   interface.mobile_dev(
     MobileOptions {
       debug: true,
@@ -382,20 +1011,99 @@ fn run_dev(
       let cli_options = CliOptions {
         dev: true,
         features: options.features.clone(),
-        args: options.args.clone(),
+        args: options
+          .args
+          .iter()
+          .chain(&profile_args)
+          .cloned()
+          .collect(),
         noise_level,
-        vars: Default::default(),
+        vars: build_env
+          .clone()
+          .into_iter()
+          .chain(project_env_vars(config))
+          .collect(),
       };
-      let _handle = write_options(
-        &tauri_config.lock().unwrap().as_ref().unwrap().identifier,
-        cli_options,
-      )?;
+      let (identifier, dev_url) = {
+        let tauri_config_guard = tauri_config.lock().unwrap();
+        let tauri_config_ = tauri_config_guard.as_ref().unwrap();
+        (
+          tauri_config_.identifier.clone(),
+          tauri_config_.build.dev_url.clone(),
+        )
+      };
+      let _handle = write_options(&identifier, cli_options)?;
+
+      if let Some(timeout) = wait_for_frontend {
+        if let Some(url) = dev_url {
+          wait_for_frontend_response(&url, timeout)?;
+        }
+      }
 
       if open {
-        open_and_wait(config, &env)
+        open_and_wait(config, &env, open_target)
       } else if let Some(device) = &device {
-        match run(device, options, config, &env) {
+        if install_only {
+          install(device, &options, config, &env, install_retries)?;
+          log::info!(action = "Installed"; "{} on {}, the dev server keeps running until you stop it", bundle_id, device.name());
+          wait_forever()
+        }
+        if let Some(template) = &profile_template {
+          let app_path = install(device, &options, config, &env, install_retries)?;
+          let trace = record_trace(
+            device,
+            config,
+            &bundle_id,
+            template,
+            &app_path,
+            &launch_args,
+          )?;
+          log::info!(action = "Recorded"; "trace at {}, the dev server keeps running until you stop it", trace.display());
+          wait_forever()
+        }
+        if let Some(duration) = smoke_test {
+          let result = install(device, &options, config, &env, install_retries).and_then(|_| {
+            if let Some(path) = &screenshot {
+              capture_screenshot(device, path.clone());
+            }
+            smoke_test_app(device, &bundle_id, &launch_args, duration)
+          });
+          // stop the dev server whether the smoke test passed or not
+          crate::dev::kill_before_dev_process();
+          result?;
+          std::process::exit(0);
+        }
+        if debug {
+          install(device, &options, config, &env, install_retries)?;
+          debug_app(device, &bundle_id, &launch_args)?;
+          log::info!(action = "Detached"; "from {bundle_id}, the dev server keeps running until you stop it");
+          wait_forever()
+        }
+        let profile = if options.debug {
+          Profile::Debug
+        } else {
+          Profile::Release
+        };
+        match run(
+          device,
+          options,
+          config,
+          &env,
+          &bundle_id,
+          &launch_args,
+          install_retries,
+        ) {
           Ok(c) => {
+            if let Some(path) = &screenshot {
+              capture_screenshot(device, path.clone());
+            }
+            if let Some(extension) = &run_extension {
+              if let Err(e) = attach_extension(device, config, profile, extension) {
+                let _ = c.kill();
+                crate::dev::kill_before_dev_process();
+                return Err(e);
+              }
+            }
             crate::dev::wait_dev_process(c.clone(), move |status, reason| {
               crate::dev::on_app_exit(status, reason, exit_on_panic, no_watch)
             });
@@ -407,18 +1115,145 @@ fn run_dev(
           }
         }
       } else {
-        open_and_wait(config, &env)
+        open_and_wait(config, &env, open_target)
       }
     },
   )
 }
 
+/// Polls the frontend dev server until it answers an HTTP request, any status code counts as a response.
+fn wait_for_frontend_response(url: &url::Url, timeout: Duration) -> Result<()> {
+  let start = Instant::now();
+  let mut logged = false;
+  loop {
+    match ureq::get(url.as_str())
+      .timeout(FRONTEND_POLL_INTERVAL)
+      .call()
+    {
+      Ok(_) | Err(ureq::Error::Status(..)) => {
+        if logged {
+          log::info!(action = "Connected"; "to the frontend dev server at {url}");
+        }
+        return Ok(());
+      }
+      Err(ureq::Error::Transport(_)) if start.elapsed() < timeout => {
+        if !logged {
+          log::info!(action = "Waiting"; "for the frontend dev server to respond at {url} (timeout: {}s)", timeout.as_secs());
+          logged = true;
+        }
+        std::thread::sleep(FRONTEND_POLL_INTERVAL);
+      }
+      Err(e) => {
+        anyhow::bail!(
+          "the frontend dev server at {url} did not respond after {}s: {e}",
+          timeout.as_secs()
+        )
+      }
+    }
+  }
+}
+
+/// Captures a screenshot of the simulator in the background once the app had time to launch.
+fn capture_screenshot(device: &Device<'_>, path: PathBuf) {
+  let udid = device.id().to_string();
+  std::thread::spawn(move || {
+    std::thread::sleep(SCREENSHOT_DELAY);
+    match simctl::find_udid(&udid).and_then(|simulator| simctl::screenshot(&simulator, &path)) {
+      Ok(()) => log::info!(action = "Captured"; "screenshot at {}", path.display()),
+      Err(e) => log::error!("{e:#}"),
+    }
+  });
+}
+
+/// Launches the installed app on the simulator and checks that it keeps running for the given duration,
+/// the app is terminated afterwards.
+fn smoke_test_app(
+  device: &Device<'_>,
+  bundle_id: &str,
+  launch_args: &[String],
+  duration: Duration,
+) -> Result<()> {
+  let simulator = simctl::find_udid(device.id())?;
+  let launched_at = SystemTime::now();
+  let pid = simctl::launch(&simulator, bundle_id, launch_args).context(ErrorKind::LaunchFailed)?;
+  log::info!(action = "Smoke testing"; "{bundle_id} on {} for {}s", device.name(), duration.as_secs());
+
+  let deadline = Instant::now() + duration;
+  loop {
+    if !is_process_running(pid) {
+      return Err(app_exited_error(bundle_id, launched_at));
+    }
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    if remaining.is_zero() {
+      break;
+    }
+    std::thread::sleep(remaining.min(SMOKE_TEST_POLL_INTERVAL));
+  }
+
+  let _ = simctl::terminate(&simulator, bundle_id);
+  log::info!(action = "Passed"; "smoke test, {bundle_id} was still running after {}s", duration.as_secs());
+  Ok(())
+}
+
+/// Simulator apps are processes of the host, so they can be checked directly.
+fn is_process_running(pid: u32) -> bool {
+  let Ok(pid) = libc::pid_t::try_from(pid) else {
+    return false;
+  };
+  // signal 0 only checks that the process exists, EPERM means it exists but belongs to another user
+  unsafe { libc::kill(pid, 0) == 0 }
+  || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Describes the exit of the app during the smoke test with its crash report, which is written shortly after the crash.
+fn app_exited_error(bundle_id: &str, launched_at: SystemTime) -> anyhow::Error {
+  let start = Instant::now();
+  let report = loop {
+    if let Some(report) = crashlogs::latest_simulator_report(bundle_id, launched_at) {
+      break Some(report);
+    }
+    if start.elapsed() >= CRASH_REPORT_WAIT {
+      break None;
+    }
+    std::thread::sleep(SMOKE_TEST_POLL_INTERVAL);
+  };
+  let details = match report {
+    Some(report) => format!(
+      "crashed with {}, see the crash report at {}",
+      crashlogs::crash_summary(&report).unwrap_or_else(|| "an unknown exception".into()),
+      report.display()
+    ),
+    None => "exited without a crash report".into(),
+  };
+  anyhow::anyhow!("{bundle_id} did not survive the launch smoke test, it {details}")
+    .context(ErrorKind::LaunchFailed)
+}
+
 fn run(
   device: &Device<'_>,
   options: MobileOptions,
   config: &AppleConfig,
   env: &Env,
+  bundle_id: &str,
+  launch_args: &[String],
+  install_retries: u32,
 ) -> crate::Result<DevChild> {
+  // cargo-mobile2 builds, installs and launches the app in one step, so the install could not be retried alone,
+  // and it launches the identifier of the Xcode project, which does not have the bundle identifier suffix
+  install(device, &options, config, env, install_retries)?;
+  launch_app(device, bundle_id, launch_args).map(DevChild::new)
+}
+
+/// Builds the app for the device and installs it without launching it, returning the path of the built app.
+///
+/// Only the install is retried on physical devices when it fails with a transient error, see [`with_install_retries`].
+fn install(
+  device: &Device<'_>,
+  options: &MobileOptions,
+  config: &AppleConfig,
+  env: &Env,
+  retries: u32,
+) -> Result<PathBuf> {
   let profile = if options.debug {
     Profile::Debug
   } else {
@@ -426,13 +1261,383 @@ fn run(
   };
 
   device
-    .run(
-      config,
-      env,
-      NoiseLevel::FranklyQuitePedantic,
-      false, // do not quit on app exit
-      profile,
+    .target()
+    .build(config, env, NoiseLevel::FranklyQuitePedantic, profile)
+    .context(ErrorKind::BuildFailed)?;
+  let app_path = built_app_path(config, device.target().sdk, profile)?;
+  with_install_retries(device, retries, || install_app(device, &app_path))?;
+  Ok(app_path)
+}
+
+/// Installs the built app on the device.
+fn install_app(device: &Device<'_>, app_path: &Path) -> Result<()> {
+  let mut install = Command::new("xcrun");
+  if matches!(device.kind(), DeviceKind::Simulator) {
+    let simulator = simctl::find_udid(device.id())?;
+    simctl::wait_booted(&simulator)?;
+    install.args(["simctl", "install", simulator.udid.as_str()]);
+  } else {
+    install.args([
+      "devicectl",
+      "device",
+      "install",
+      "app",
+      "--device",
+      device.id(),
+    ]);
+  }
+  // read the output ourselves so the error includes the reason, used to detect transient failures
+  let output = install.arg(app_path).output()?;
+  if !output.status.success() {
+    anyhow::bail!(
+      "failed to install {} on {}: {}",
+      app_path.display(),
+      device.name(),
+      String::from_utf8_lossy(&output.stderr).trim()
+    );
+  }
+  Ok(())
+}
+
+/// Checks that the Instruments template is installed, listing the available ones otherwise.
+fn validate_instruments_template(template: &str) -> Result<()> {
+  let output = Command::new("xcrun")
+    .args(["xctrace", "list", "templates"])
+    .output_ok()
+    .context("failed to list the Instruments templates, `xctrace` requires Xcode 12 or newer")
+    .context(ErrorKind::XcodeMissing)?;
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  // the output lists the template names under `== Standard Templates ==` like headers
+  let templates = stdout
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty() && !line.starts_with("=="))
+    .collect::<Vec<_>>();
+
+  if templates
+    .iter()
+    .any(|name| name.eq_ignore_ascii_case(template))
+  {
+    Ok(())
+  } else {
+    Err(
+      anyhow::anyhow!(
+        "unknown Instruments template `{template}`, available templates: {}",
+        templates.join(", ")
+      )
+      .context(ErrorKind::InvalidConfig),
+    )
+  }
+}
+
+/// Launches the installed app under the Instruments template and opens the recorded trace once the app exits.
+fn record_trace(
+  device: &Device<'_>,
+  config: &AppleConfig,
+  bundle_id: &str,
+  template: &str,
+  app_path: &Path,
+  launch_args: &[String],
+) -> Result<PathBuf> {
+  let device_id = if matches!(device.kind(), DeviceKind::Simulator) {
+    simctl::find_udid(device.id())?.udid
+  } else {
+    device.id().to_string()
+  };
+
+  let traces_dir = config.archive_dir().join("traces");
+  create_dir_all(&traces_dir)?;
+  let timestamp = SystemTime::now()
+    .duration_since(SystemTime::UNIX_EPOCH)
+    .unwrap_or_default()
+    .as_secs();
+  let trace = traces_dir.join(format!("{}-{timestamp}.trace", config.scheme()));
+
+  log::info!(action = "Profiling"; "{bundle_id} on {} with the {template} template", device.name());
+  let status = Command::new("xcrun")
+    .args(["xctrace", "record", "--template", template, "--device"])
+    .arg(&device_id)
+    .arg("--output")
+    .arg(&trace)
+    .arg("--launch")
+    .arg("--")
+    .arg(app_path)
+    .args(launch_args)
+    .piped()?;
+  if !trace.exists() {
+    anyhow::bail!("Instruments did not record a trace (xctrace exited with {status})");
+  }
+
+  Command::new("open")
+    .arg(&trace)
+    .output_ok()
+    .with_context(|| format!("failed to open {}", trace.display()))?;
+
+  Ok(trace)
+}
+
+/// Launches the installed app suspended and runs an LLDB session attached to it, returning once LLDB exits.
+fn debug_app(device: &Device<'_>, bundle_id: &str, launch_args: &[String]) -> Result<()> {
+  let (pid, attach_commands) = if matches!(device.kind(), DeviceKind::Simulator) {
+    let simulator = simctl::find_udid(device.id())?;
+    simctl::wait_booted(&simulator)?;
+    let pid = simctl::launch_waiting_for_debugger(&simulator, bundle_id, launch_args)?;
+    (pid, vec![format!("process attach --pid {pid}")])
+  } else {
+    let pid = launch_suspended_on_device(device, bundle_id, launch_args)?;
+    (
+      pid,
+      vec![
+        format!("device select {}", device.id()),
+        format!("device process attach --pid {pid}"),
+      ],
+    )
+  };
+
+  log::info!(action = "Debugging"; "{bundle_id} (pid {pid}) on {}, set your breakpoints and run `continue` to resume the app", device.name());
+  log::info!(
+    "To attach from another LLDB session, run: {}",
+    attach_commands
+      .iter()
+      .map(|command| format!("`{command}`"))
+      .collect::<Vec<_>>()
+      .join(" then ")
+  );
+
+  // inherit the terminal so the LLDB prompt is interactive
+  let status = Command::new("xcrun")
+    .arg("lldb")
+    .args(attach_commands.iter().flat_map(|command| ["-o", command]))
+    .status()
+    .context("failed to run `xcrun lldb`")
+    .context(ErrorKind::XcodeMissing)?;
+  if !status.success() {
+    anyhow::bail!("LLDB exited with {status}");
+  }
+  Ok(())
+}
+
+/// Launches the installed app on a physical device stopped at its entry point, returning its process identifier.
+fn launch_suspended_on_device(
+  device: &Device<'_>,
+  bundle_id: &str,
+  launch_args: &[String],
+) -> Result<u32> {
+  let output_path =
+    std::env::temp_dir().join(format!("tauri-ios-launch-{}.json", std::process::id()));
+  Command::new("xcrun")
+    .args(["devicectl", "device", "process", "launch", "--device"])
+    .arg(device.id())
+    .args(["--start-stopped", "--terminate-existing", "--json-output"])
+    .arg(&output_path)
+    .arg(bundle_id)
+    .args(launch_args)
+    .output_ok()
+    .with_context(|| format!("failed to launch {bundle_id} on {} with `xcrun devicectl`, which requires Xcode 15 or newer", device.name()))
+    .context(ErrorKind::NoDevice)?;
+  let output = std::fs::read(&output_path);
+  let _ = std::fs::remove_file(&output_path);
+  let output: serde_json::Value = serde_json::from_slice(&output?)?;
+
+  output["result"]["process"]["processIdentifier"]
+    .as_u64()
+    .and_then(|pid| u32::try_from(pid).ok())
+    .with_context(|| {
+      format!(
+        "failed to read the process identifier of {bundle_id} from the `xcrun devicectl` output"
+      )
+    })
+}
+
+/// Checks that the extension is a target of the Xcode project, listing the available targets otherwise.
+fn ensure_extension_target(config: &AppleConfig, extension: &str) -> Result<()> {
+  let project_dir = config.project_dir();
+  let project = find_xcode_file(&project_dir, "xcodeproj").ok_or_else(|| {
+    anyhow::anyhow!(
+      "could not find the Xcode project in {}",
+      project_dir.display()
+    )
+    .context(ErrorKind::InvalidConfig)
+  })?;
+  let output = Command::new("xcodebuild")
+    .arg("-project")
+    .arg(&project)
+    .args(["-list", "-json"])
+    .output_ok()
+    .context("failed to list the Xcode project targets")?;
+  let list: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+  let targets = list["project"]["targets"]
+    .as_array()
+    .map(|targets| {
+      targets
+        .iter()
+        .filter_map(|t| t.as_str())
+        .collect::<Vec<_>>()
+    })
+    .unwrap_or_default();
+
+  if targets.contains(&extension) && extension != config.scheme() {
+    Ok(())
+  } else {
+    Err(
+      anyhow::anyhow!(
+        "unknown app extension target `{extension}`, the Xcode project targets are: {}",
+        targets.join(", ")
+      )
+      .context(ErrorKind::InvalidConfig),
+    )
+  }
+}
+
+/// Streams the output of the app extension installed with the app in the background.
+fn attach_extension(
+  device: &Device<'_>,
+  config: &AppleConfig,
+  profile: Profile,
+  extension: &str,
+) -> Result<()> {
+  let app_path = built_app_path(config, device.target().sdk, profile)?;
+  let appex = app_path.join("PlugIns").join(format!("{extension}.appex"));
+  if !appex.exists() {
+    return Err(
+      anyhow::anyhow!(
+        "the {extension} extension is not embedded in {}, add it to the Embed App Extensions build phase of the app target",
+        app_path.display()
+      )
+      .context(ErrorKind::InvalidConfig),
+    );
+  }
+  // the extension process is named after its executable, which usually matches the target name
+  let process = plist::Value::from_file(appex.join("Info.plist"))
+    .ok()
+    .and_then(|info| {
+      info
+        .as_dictionary()
+        .and_then(|d| d.get("CFBundleExecutable"))
+        .and_then(|e| e.as_string())
+        .map(ToString::to_string)
+    })
+    .unwrap_or_else(|| extension.to_string());
+
+  let simulator = simctl::find_udid(device.id())?;
+  log::info!(action = "Attaching"; "to the {extension} extension, it runs once its host presents it");
+  std::thread::spawn(move || {
+    let result = duct::cmd(
+      "xcrun",
+      [
+        "simctl",
+        "spawn",
+        simulator.udid.as_str(),
+        "log",
+        "stream",
+        "--style",
+        "compact",
+        "--predicate",
+        &format!("process == \"{process}\""),
+      ],
     )
-    .map(DevChild::new)
-    .map_err(Into::into)
+    .run();
+    if let Err(e) = result {
+      log::error!("failed to attach to the {process} extension: {e}");
+    }
+  });
+  Ok(())
+}
+
+/// Runs the install step, retrying it on physical devices when it fails with a transient error.
+fn with_install_retries<T>(
+  device: &Device<'_>,
+  retries: u32,
+  mut install: impl FnMut() -> Result<T>,
+) -> Result<T> {
+  let retries = if matches!(device.kind(), DeviceKind::Simulator) {
+    0
+  } else {
+    retries
+  };
+  let mut attempt = 0;
+  loop {
+    match install() {
+      Ok(value) => return Ok(value),
+      Err(e) if attempt < retries && is_transient_install_error(&e) => {
+        attempt += 1;
+        log::warn!(
+          "Installing on {} failed with a transient error, retrying in {}s ({attempt}/{retries}): {e:#}",
+          device.name(),
+          INSTALL_RETRY_DELAY.as_secs()
+        );
+        std::thread::sleep(INSTALL_RETRY_DELAY);
+      }
+      Err(e) => return Err(e),
+    }
+  }
+}
+
+fn is_transient_install_error(error: &anyhow::Error) -> bool {
+  let message = format!("{error:#}").to_lowercase();
+  !PERMANENT_INSTALL_ERRORS
+    .iter()
+    .any(|marker| message.contains(marker))
+    && TRANSIENT_INSTALL_ERRORS
+      .iter()
+      .any(|marker| message.contains(marker))
+}
+
+/// Launches the installed app with the given arguments, attaching to its console.
+/// A running instance of the app is terminated first.
+fn launch_app(device: &Device<'_>, bundle_id: &str, launch_args: &[String]) -> Result<ChildHandle> {
+  let mut args: Vec<String> = if matches!(device.kind(), DeviceKind::Simulator) {
+    let simulator = simctl::find_udid(device.id())?;
+    vec![
+      "simctl".into(),
+      "launch".into(),
+      "--console".into(),
+      "--terminate-running-process".into(),
+      simulator.udid,
+    ]
+  } else {
+    vec![
+      "devicectl".into(),
+      "device".into(),
+      "process".into(),
+      "launch".into(),
+      "--console".into(),
+      "--terminate-existing".into(),
+      "--device".into(),
+      device.id().to_string(),
+    ]
+  };
+  args.push(bundle_id.into());
+  args.extend(launch_args.iter().cloned());
+
+  if launch_args.is_empty() {
+    log::info!(action = "Launching"; "{bundle_id}");
+  } else {
+    log::info!(action = "Launching"; "{bundle_id} with arguments {}", launch_args.join(" "));
+  }
+  duct::cmd("xcrun", args)
+    .start()
+    .with_context(|| format!("failed to launch {bundle_id}"))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::is_transient_install_error;
+
+  #[test]
+  fn detects_transient_install_errors() {
+    assert!(is_transient_install_error(&anyhow::anyhow!(
+      "ERROR: The device is busy (kAMDBusyError)"
+    )));
+    assert!(is_transient_install_error(
+      &anyhow::anyhow!("the operation timed out").context("failed to install the app")
+    ));
+    // a signing failure is not retried even when it mentions a transient marker
+    assert!(!is_transient_install_error(&anyhow::anyhow!(
+      "timed out verifying the code signature (0xe8008015)"
+    )));
+    assert!(!is_transient_install_error(&anyhow::anyhow!(
+      "no such file or directory"
+    )));
+  }
 }
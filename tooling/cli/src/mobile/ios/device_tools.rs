@@ -0,0 +1,118 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Probes the Xcode device tooling before running device operations,
+//! turning known version incompatibilities into actionable messages.
+
+use super::{xcode_developer_dir, xcode_version, ErrorKind};
+use crate::Result;
+
+use std::{process::Command, sync::OnceLock};
+
+/// The kind of device a tool is used for.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ToolTarget {
+  Simulator,
+  PhysicalDevice,
+}
+
+/// A device tool and the oldest Xcode version it works with.
+struct ToolRequirement {
+  tool: &'static str,
+  target: ToolTarget,
+  minimum_xcode: u64,
+  /// Whether the device operations cannot work at all with older Xcode versions.
+  required: bool,
+  guidance: &'static str,
+}
+
+/// Known compatibility of the device tools with the Xcode versions.
+const TOOL_COMPATIBILITY: &[ToolRequirement] = &[
+  ToolRequirement {
+    tool: "simctl",
+    target: ToolTarget::Simulator,
+    minimum_xcode: 11,
+    required: true,
+    guidance: "`xcrun simctl bootstatus` and `xcrun simctl ui` are used to boot and configure the simulator",
+  },
+  ToolRequirement {
+    tool: "devicectl",
+    target: ToolTarget::PhysicalDevice,
+    minimum_xcode: 15,
+    required: false,
+    guidance: "devices running iOS 17 or newer can only be reached with `xcrun devicectl`, older Xcode versions fall back to `ios-deploy` which only supports iOS 16 and older",
+  },
+];
+
+/// Messages printed by simctl when the running CoreSimulator service belongs to another Xcode version.
+const CORE_SIMULATOR_MISMATCH_ERRORS: &[&str] = &[
+  "CoreSimulator is out of date",
+  "CoreSimulator.framework was changed while the process was running",
+  "Unable to locate device set",
+];
+
+/// Checks the device tooling of the active Xcode for simulators or physical devices, once per process.
+pub fn check(simulator: bool) -> Result<()> {
+  static CHECKED: OnceLock<()> = OnceLock::new();
+  if CHECKED.set(()).is_err() {
+    return Ok(());
+  }
+
+  let target = if simulator {
+    ToolTarget::Simulator
+  } else {
+    ToolTarget::PhysicalDevice
+  };
+
+  // the developer directory errors are reported by `ensure_xcode`, the probe is only advisory here
+  let Ok(developer_dir) = xcode_developer_dir() else {
+    return Ok(());
+  };
+  match xcode_version(&developer_dir) {
+    Ok(version) => {
+      for requirement in TOOL_COMPATIBILITY.iter().filter(|requirement| {
+        requirement.target == target && version.major < requirement.minimum_xcode
+      }) {
+        let message = format!(
+          "`{}` requires Xcode {} or newer but the active Xcode is version {version}: {}. Install a newer Xcode and select it with `sudo xcode-select --switch` or the `DEVELOPER_DIR` environment variable",
+          requirement.tool, requirement.minimum_xcode, requirement.guidance
+        );
+        if requirement.required {
+          return Err(anyhow::anyhow!(message).context(ErrorKind::XcodeMissing));
+        }
+        log::warn!("{message}");
+      }
+    }
+    Err(e) => log::debug!("skipping the device tools version check: {e:#}"),
+  }
+
+  if target == ToolTarget::Simulator {
+    check_core_simulator()?;
+  }
+
+  Ok(())
+}
+
+/// Detects a CoreSimulator service left running by another Xcode version, usually after an Xcode update.
+fn check_core_simulator() -> Result<()> {
+  let Ok(output) = Command::new("xcrun")
+    .args(["simctl", "list", "runtimes", "--json"])
+    .output()
+  else {
+    return Ok(());
+  };
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  if let Some(error) = CORE_SIMULATOR_MISMATCH_ERRORS
+    .iter()
+    .find(|error| stderr.contains(*error))
+  {
+    return Err(
+      anyhow::anyhow!(
+        "the simulator service does not match the active Xcode ({error}). Quit Simulator and Xcode, then restart the service with `sudo killall -9 com.apple.CoreSimulator.CoreSimulatorService` or reboot"
+      )
+      .context(ErrorKind::XcodeMissing),
+    );
+  }
+  Ok(())
+}
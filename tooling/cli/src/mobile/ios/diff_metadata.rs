@@ -0,0 +1,230 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{
+  validate::{extract, find_app},
+  ErrorKind,
+};
+use crate::Result;
+
+use anyhow::Context;
+use clap::Parser;
+use colored::Colorize;
+use plist::{Dictionary, Value};
+
+use std::{
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+#[derive(Debug, Clone, Parser)]
+#[clap(
+  about = "Compare the Info.plist and entitlements of two iOS builds",
+  long_about = "Compare the Info.plist and the code signing entitlements of two iOS builds, printing the added, removed and changed keys.
+Each build can be an IPA or an app bundle. Nested dictionaries are compared key by key."
+)]
+pub struct Options {
+  /// The IPA or app bundle of the previous build.
+  pub old: PathBuf,
+  /// The IPA or app bundle of the new build.
+  pub new: PathBuf,
+}
+
+/// The signing-relevant metadata of a build.
+struct Metadata {
+  info_plist: Dictionary,
+  entitlements: Dictionary,
+}
+
+/// A difference between the metadata of two builds.
+#[derive(Debug, PartialEq)]
+enum Change {
+  Added(String, Value),
+  Removed(String, Value),
+  Changed(String, Value, Value),
+}
+
+pub fn command(options: Options) -> Result<()> {
+  let old = read_metadata(&options.old)?;
+  let new = read_metadata(&options.new)?;
+
+  let mut identical = true;
+  for (name, old, new) in [
+    ("Info.plist", &old.info_plist, &new.info_plist),
+    ("Entitlements", &old.entitlements, &new.entitlements),
+  ] {
+    let changes = diff(old, new);
+    if changes.is_empty() {
+      continue;
+    }
+    identical = false;
+    println!("{}", name.bold());
+    for change in changes {
+      match change {
+        Change::Added(key, value) => {
+          println!("{}", format!("  + {key} = {}", describe(&value)).green())
+        }
+        Change::Removed(key, value) => {
+          println!("{}", format!("  - {key} = {}", describe(&value)).red())
+        }
+        Change::Changed(key, old, new) => println!(
+          "{}",
+          format!("  ~ {key}: {} -> {}", describe(&old), describe(&new)).yellow()
+        ),
+      }
+    }
+  }
+
+  if identical {
+    log::info!(
+      "{} and {} have the same Info.plist and entitlements",
+      options.old.display(),
+      options.new.display()
+    );
+  }
+
+  Ok(())
+}
+
+/// Reads the metadata of an IPA, extracted to a temporary directory, or of an app bundle.
+fn read_metadata(path: &Path) -> Result<Metadata> {
+  let metadata = if path.extension().is_some_and(|ext| ext == "ipa") {
+    let extract_dir = extract(path)?;
+    find_app(&extract_dir).and_then(|app| read_app_metadata(&app))
+  } else {
+    read_app_metadata(path)
+  };
+  metadata
+    .with_context(|| format!("failed to read the metadata of {}", path.display()))
+    .context(ErrorKind::ValidationFailed)
+}
+
+fn read_app_metadata(app: &Path) -> Result<Metadata> {
+  let info_plist_path = app.join("Info.plist");
+  let info_plist = Value::from_file(&info_plist_path)
+    .with_context(|| format!("failed to read {}", info_plist_path.display()))?
+    .into_dictionary()
+    .ok_or_else(|| anyhow::anyhow!("{} is not a dictionary", info_plist_path.display()))?;
+  Ok(Metadata {
+    info_plist,
+    entitlements: entitlements(app)?,
+  })
+}
+
+/// Reads the entitlements embedded in the code signature of the app.
+/// An app that is not code signed, e.g. a simulator build, has no entitlements.
+fn entitlements(app: &Path) -> Result<Dictionary> {
+  let output = Command::new("codesign")
+    .args(["--display", "--entitlements", "-", "--xml"])
+    .arg(app)
+    .output()
+    .context("failed to run codesign")?;
+  if !output.status.success() {
+    log::warn!(
+      "could not read the entitlements of {}: {}",
+      app.display(),
+      String::from_utf8_lossy(&output.stderr).trim()
+    );
+    return Ok(Dictionary::new());
+  }
+  if output.stdout.iter().all(u8::is_ascii_whitespace) {
+    return Ok(Dictionary::new());
+  }
+  Value::from_reader_xml(output.stdout.as_slice())
+    .context("failed to parse the entitlements")?
+    .into_dictionary()
+    .ok_or_else(|| anyhow::anyhow!("the entitlements are not a dictionary"))
+}
+
+/// Compares two dictionaries, descending into the dictionaries present in both.
+/// Nested keys are joined with a `.`, e.g. `NSAppTransportSecurity.NSAllowsArbitraryLoads`.
+fn diff(old: &Dictionary, new: &Dictionary) -> Vec<Change> {
+  let mut changes = Vec::new();
+  diff_into(&mut changes, None, old, new);
+  changes
+}
+
+fn diff_into(changes: &mut Vec<Change>, prefix: Option<&str>, old: &Dictionary, new: &Dictionary) {
+  let path = |key: &str| match prefix {
+    Some(prefix) => format!("{prefix}.{key}"),
+    None => key.to_string(),
+  };
+
+  let mut keys = old.keys().chain(new.keys()).collect::<Vec<_>>();
+  keys.sort();
+  keys.dedup();
+  for key in keys {
+    match (old.get(key), new.get(key)) {
+      (Some(Value::Dictionary(old)), Some(Value::Dictionary(new))) => {
+        diff_into(changes, Some(&path(key)), old, new)
+      }
+      (Some(old), Some(new)) if old != new => {
+        changes.push(Change::Changed(path(key), old.clone(), new.clone()))
+      }
+      (Some(old), None) => changes.push(Change::Removed(path(key), old.clone())),
+      (None, Some(new)) => changes.push(Change::Added(path(key), new.clone())),
+      _ => {}
+    }
+  }
+}
+
+/// A single-line description of a plist value.
+fn describe(value: &Value) -> String {
+  match value {
+    Value::String(s) => format!("\"{s}\""),
+    Value::Boolean(b) => b.to_string(),
+    Value::Integer(i) => i.to_string(),
+    Value::Real(r) => r.to_string(),
+    Value::Date(d) => d.to_xml_format(),
+    Value::Data(data) => format!("<{} bytes>", data.len()),
+    Value::Array(values) => format!(
+      "[{}]",
+      values.iter().map(describe).collect::<Vec<_>>().join(", ")
+    ),
+    Value::Dictionary(dict) => format!(
+      "{{{}}}",
+      dict
+        .iter()
+        .map(|(key, value)| format!("{key} = {}", describe(value)))
+        .collect::<Vec<_>>()
+        .join(", ")
+    ),
+    Value::Uid(uid) => uid.get().to_string(),
+    _ => "<unknown>".into(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{diff, Change};
+  use plist::{Dictionary, Value};
+
+  #[test]
+  fn diffs_nested_keys() {
+    let mut old = Dictionary::new();
+    old.insert("CFBundleVersion".into(), "1".into());
+    old.insert("UIRequiresFullScreen".into(), true.into());
+    let mut ats = Dictionary::new();
+    ats.insert("NSAllowsArbitraryLoads".into(), true.into());
+    old.insert("NSAppTransportSecurity".into(), ats.clone().into());
+
+    let mut new = old.clone();
+    new.insert("CFBundleVersion".into(), "2".into());
+    new.remove("UIRequiresFullScreen");
+    ats.insert("NSAllowsLocalNetworking".into(), true.into());
+    new.insert("NSAppTransportSecurity".into(), ats.into());
+
+    assert_eq!(
+      diff(&old, &new),
+      vec![
+        Change::Changed("CFBundleVersion".into(), "1".into(), "2".into()),
+        Change::Added(
+          "NSAppTransportSecurity.NSAllowsLocalNetworking".into(),
+          Value::Boolean(true)
+        ),
+        Change::Removed("UIRequiresFullScreen".into(), Value::Boolean(true)),
+      ]
+    );
+  }
+}
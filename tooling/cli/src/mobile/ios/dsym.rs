@@ -0,0 +1,123 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Hands the dSYM bundles of the archive over to crash symbolication services,
+//! with `tauri ios build --dsym-out` and the `bundle > iOS > dsymUploadScript` config.
+
+use super::ErrorKind;
+use crate::{CommandExt, Result};
+
+use anyhow::Context;
+use cargo_mobile2::apple::config::Config as AppleConfig;
+
+use std::{
+  fs::{create_dir_all, read_dir, remove_dir_all},
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+/// Copies the dSYM bundles of the archive to `out_dir` and runs the upload script for each of them.
+///
+/// Returns the copied dSYM bundles.
+pub fn process(
+  config: &AppleConfig,
+  arch: &str,
+  out_dir: Option<&Path>,
+  upload_script: Option<&str>,
+) -> Result<Vec<PathBuf>> {
+  let dsyms_dir = config
+    .archive_dir()
+    .join(format!("{}.xcarchive", config.scheme()))
+    .join("dSYMs");
+  let dsyms = read_dir(&dsyms_dir)
+    .map(|entries| {
+      entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "dSYM"))
+        .collect::<Vec<_>>()
+    })
+    .unwrap_or_default();
+  if dsyms.is_empty() {
+    log::warn!(
+      "No dSYM found in {}, make sure the `DEBUG_INFORMATION_FORMAT` build setting is `dwarf-with-dsym`",
+      dsyms_dir.display()
+    );
+    return Ok(Vec::new());
+  }
+
+  let mut copied = Vec::new();
+  for dsym in dsyms {
+    let uuids = uuids(&dsym)?;
+    log::info!(
+      "dSYM {} has UUID {}",
+      dsym.file_name().unwrap().to_string_lossy(),
+      uuids.join(", ")
+    );
+
+    let dsym = if let Some(out_dir) = out_dir {
+      let out_dir = out_dir.join(arch);
+      create_dir_all(&out_dir)
+        .with_context(|| format!("failed to create output directory {}", out_dir.display()))?;
+      let dest = out_dir.join(dsym.file_name().unwrap());
+      if dest.exists() {
+        remove_dir_all(&dest)?;
+      }
+      // ditto preserves the bundle structure and metadata
+      Command::new("ditto")
+        .arg(&dsym)
+        .arg(&dest)
+        .output_ok()
+        .with_context(|| format!("failed to copy {} to {}", dsym.display(), dest.display()))?;
+      copied.push(dest.clone());
+      dest
+    } else {
+      dsym
+    };
+
+    if let Some(script) = upload_script {
+      run_upload_script(script, &config.project_dir(), &dsym, &uuids)?;
+    }
+  }
+
+  Ok(copied)
+}
+
+/// Reads the UUIDs of the binaries of a dSYM bundle with `dwarfdump`, one per architecture.
+fn uuids(dsym: &Path) -> Result<Vec<String>> {
+  let output = Command::new("xcrun")
+    .args(["dwarfdump", "--uuid"])
+    .arg(dsym)
+    .output_ok()
+    .with_context(|| format!("failed to read the UUID of {}", dsym.display()))
+    .context(ErrorKind::BuildFailed)?;
+  // each line looks like `UUID: 8C5A0F4E-... (arm64) /path/to/binary`
+  Ok(
+    String::from_utf8_lossy(&output.stdout)
+      .lines()
+      .filter_map(|line| line.strip_prefix("UUID: "))
+      .filter_map(|line| line.split_whitespace().next())
+      .map(ToString::to_string)
+      .collect(),
+  )
+}
+
+fn run_upload_script(
+  script: &str,
+  project_dir: &Path,
+  dsym: &Path,
+  uuids: &[String],
+) -> Result<()> {
+  log::info!(action = "Running"; "dSYM upload script `{script}` for {}", dsym.display());
+  duct::cmd("sh", ["-c", script])
+    .dir(project_dir)
+    .env("TAURI_IOS_DSYM_PATH", dsym)
+    .env("TAURI_IOS_DSYM_UUIDS", uuids.join(","))
+    .stdout_file(os_pipe::dup_stdout().unwrap())
+    .stderr_file(os_pipe::dup_stderr().unwrap())
+    .run()
+    .with_context(|| format!("failed to run dSYM upload script `{script}`"))
+    .context(ErrorKind::BuildFailed)?;
+  Ok(())
+}
@@ -0,0 +1,77 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use std::fmt;
+
+/// Known failure categories of the iOS commands.
+///
+/// Each category is attached to the error as context and mapped to a distinct process exit code,
+/// so scripts wrapping the CLI can tell environment issues (usually retryable) apart from user errors.
+/// The codes start at 10 so they do not collide with the `2` clap exits with on invalid arguments:
+///
+/// | Exit code | Category                                  |
+/// |-----------|-------------------------------------------|
+/// | 1         | Unknown or uncategorized error            |
+/// | 10        | Invalid configuration                     |
+/// | 11        | No connected device or simulator          |
+/// | 12        | Code signing is missing or failed to load |
+/// | 13        | Xcode is missing                          |
+/// | 14        | The build failed                          |
+/// | 15        | The IPA validation failed                 |
+/// | 16        | The app exited during a launch smoke test |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+  /// The Tauri configuration or the Xcode project is invalid.
+  InvalidConfig,
+  /// No iOS device or simulator could be found.
+  NoDevice,
+  /// The code signing certificate or provisioning profile is missing or invalid.
+  SigningMissing,
+  /// Xcode is not installed or is not selected as the active developer directory.
+  XcodeMissing,
+  /// Building, archiving or exporting the app failed.
+  BuildFailed,
+  /// The IPA does not pass the `ios validate` checks.
+  ValidationFailed,
+  /// The app exited during the `ios dev --smoke-test` launch check.
+  LaunchFailed,
+}
+
+impl ErrorKind {
+  /// The process exit code associated with this category.
+  pub fn exit_code(self) -> i32 {
+    match self {
+      Self::InvalidConfig => 10,
+      Self::NoDevice => 11,
+      Self::SigningMissing => 12,
+      Self::XcodeMissing => 13,
+      Self::BuildFailed => 14,
+      Self::ValidationFailed => 15,
+      Self::LaunchFailed => 16,
+    }
+  }
+}
+
+impl fmt::Display for ErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::InvalidConfig => write!(f, "invalid iOS configuration"),
+      Self::NoDevice => write!(f, "no iOS device available"),
+      Self::SigningMissing => write!(f, "iOS code signing is not configured"),
+      Self::XcodeMissing => write!(f, "Xcode is not available"),
+      Self::BuildFailed => write!(f, "failed to build the iOS app"),
+      Self::ValidationFailed => write!(f, "the iOS app is invalid"),
+      Self::LaunchFailed => write!(f, "the iOS app failed to launch"),
+    }
+  }
+}
+
+impl std::error::Error for ErrorKind {}
+
+/// Finds the exit code of the outermost known iOS failure category attached to the error.
+pub fn exit_code(error: &anyhow::Error) -> Option<i32> {
+  error
+    .downcast_ref::<ErrorKind>()
+    .map(|kind| kind.exit_code())
+}
@@ -0,0 +1,92 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Cargo features matrix resolving the iOS features for the current target and configuration.
+
+use crate::Result;
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use std::{fs::read_to_string, path::Path};
+
+/// A JSON or TOML file describing which cargo features are enabled on each iOS build variant.
+///
+/// ```json
+/// {
+///   "common": ["analytics"],
+///   "device": ["hardware-keychain"],
+///   "simulator": ["mock-camera"],
+///   "debug": ["devtools"],
+///   "release": []
+/// }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FeaturesMatrix {
+  /// Features enabled on every build.
+  #[serde(default)]
+  common: Vec<String>,
+  /// Features enabled when building for a physical device.
+  #[serde(default)]
+  device: Vec<String>,
+  /// Features enabled when building for a simulator.
+  #[serde(default)]
+  simulator: Vec<String>,
+  /// Features enabled on debug builds.
+  #[serde(default)]
+  debug: Vec<String>,
+  /// Features enabled on release builds.
+  #[serde(default)]
+  release: Vec<String>,
+}
+
+impl FeaturesMatrix {
+  /// Reads the matrix from a `.json` or `.toml` file.
+  pub fn load(path: &Path) -> Result<Self> {
+    let contents = read_to_string(path)
+      .with_context(|| format!("failed to read features matrix {}", path.display()))?;
+    let matrix = if path.extension().is_some_and(|ext| ext == "toml") {
+      toml::from_str(&contents).map_err(anyhow::Error::from)
+    } else {
+      serde_json::from_str(&contents).map_err(anyhow::Error::from)
+    };
+    matrix.with_context(|| format!("invalid features matrix {}", path.display()))
+  }
+
+  /// Resolves the features that apply to the given target triple and configuration.
+  pub fn resolve(&self, target_triple: &str, debug: bool) -> Vec<String> {
+    let simulator = super::is_simulator_triple(target_triple);
+    self
+      .common
+      .iter()
+      .chain(if simulator {
+        &self.simulator
+      } else {
+        &self.device
+      })
+      .chain(if debug { &self.debug } else { &self.release })
+      .cloned()
+      .collect()
+  }
+}
+
+/// Merges the features from the matrix with the ones passed on the command line.
+pub fn merge_features(
+  features: &mut Option<Vec<String>>,
+  matrix: Option<&Path>,
+  target_triple: &str,
+  debug: bool,
+) -> Result<()> {
+  if let Some(path) = matrix {
+    let resolved = FeaturesMatrix::load(path)?.resolve(target_triple, debug);
+    let features = features.get_or_insert_with(Vec::new);
+    for feature in resolved {
+      if !features.contains(&feature) {
+        features.push(feature);
+      }
+    }
+  }
+  Ok(())
+}
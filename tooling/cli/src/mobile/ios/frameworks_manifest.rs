@@ -0,0 +1,199 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Lockfile-style manifest pinning the vendor frameworks of the iOS app.
+//!
+//! The `ios-frameworks.json` file in the Tauri directory lists the frameworks with their version
+//! and the SHA-256 checksum of their `.zip` archive:
+//!
+//! ```json
+//! {
+//!   "frameworks": [
+//!     {
+//!       "name": "MyLib.xcframework",
+//!       "url": "https://example.com/MyLib-1.2.0.xcframework.zip",
+//!       "version": "1.2.0",
+//!       "checksum": "<sha256>"
+//!     },
+//!     { "name": "Other.xcframework", "path": "vendor/Other.xcframework", "version": "3.0.1" }
+//!   ]
+//! }
+//! ```
+
+use super::{add_framework::VENDOR_FRAMEWORKS_DIR as FRAMEWORKS_DIR, ErrorKind};
+use crate::{CommandExt, Result};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use std::{
+  fs::{create_dir_all, read_to_string, remove_dir_all, write, File},
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+const MANIFEST_FILE_NAME: &str = "ios-frameworks.json";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct Manifest {
+  frameworks: Vec<Entry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct Entry {
+  /// Name of the framework bundle, e.g. `MyLib.xcframework`.
+  name: String,
+  /// Path to the framework bundle or its `.zip` archive, relative to the Tauri directory.
+  path: Option<PathBuf>,
+  /// URL of the `.zip` archive containing the framework bundle.
+  url: Option<String>,
+  /// The pinned framework version.
+  version: String,
+  /// SHA-256 checksum of the `.zip` archive.
+  checksum: Option<String>,
+}
+
+/// The paths of the frameworks declared in the manifest, if it exists, relative to the Tauri directory.
+///
+/// The frameworks are not downloaded nor extracted, see [`install`].
+pub fn paths(tauri_dir: &Path) -> Result<Vec<String>> {
+  Ok(
+    read_manifest(tauri_dir)?
+      .frameworks
+      .iter()
+      .map(|entry| match &entry.path {
+        Some(path) if !path.extension().is_some_and(|ext| ext == "zip") => {
+          path.to_string_lossy().into_owned()
+        }
+        _ => extracted_path(entry),
+      })
+      .collect(),
+  )
+}
+
+/// Downloads, verifies and extracts the frameworks declared in the manifest, if it exists.
+pub fn install(tauri_dir: &Path) -> Result<()> {
+  for entry in read_manifest(tauri_dir)?.frameworks {
+    resolve_entry(tauri_dir, &entry)
+      .with_context(|| {
+        format!(
+          "failed to resolve framework {} {}",
+          entry.name, entry.version
+        )
+      })
+      .context(ErrorKind::InvalidConfig)?;
+  }
+  Ok(())
+}
+
+fn read_manifest(tauri_dir: &Path) -> Result<Manifest> {
+  let manifest_path = tauri_dir.join(MANIFEST_FILE_NAME);
+  if !manifest_path.exists() {
+    return Ok(Manifest {
+      frameworks: Vec::new(),
+    });
+  }
+  serde_json::from_str(&read_to_string(&manifest_path)?)
+    .with_context(|| format!("invalid frameworks manifest {}", manifest_path.display()))
+    .context(ErrorKind::InvalidConfig)
+}
+
+/// The path of a framework extracted from its `.zip` archive, relative to the Tauri directory.
+fn extracted_path(entry: &Entry) -> String {
+  format!("{FRAMEWORKS_DIR}/{}", entry.name)
+}
+
+fn resolve_entry(tauri_dir: &Path, entry: &Entry) -> Result<()> {
+  let archive = match (&entry.path, &entry.url) {
+    (Some(path), None) => {
+      let path = tauri_dir.join(path);
+      if path.extension().is_some_and(|ext| ext == "zip") {
+        path
+      } else {
+        if entry.checksum.is_some() {
+          anyhow::bail!("checksums can only be verified on `.zip` archives");
+        }
+        if !path.is_dir() {
+          anyhow::bail!("{} does not exist", path.display());
+        }
+        return Ok(());
+      }
+    }
+    (None, Some(url)) => download(tauri_dir, entry, url)?,
+    _ => anyhow::bail!("exactly one of `path` or `url` must be set"),
+  };
+
+  let checksum = entry
+    .checksum
+    .as_deref()
+    .ok_or_else(|| anyhow::anyhow!("a `checksum` is required for `.zip` archives"))?;
+  let actual = sha256(&archive)?;
+  if !actual.eq_ignore_ascii_case(checksum) {
+    anyhow::bail!(
+      "checksum mismatch for {}: expected {checksum}, got {actual}",
+      archive.display()
+    );
+  }
+
+  let frameworks_dir = tauri_dir.join(FRAMEWORKS_DIR);
+  let framework_path = frameworks_dir.join(&entry.name);
+  // the stamp file stores the checksum of the extracted archive so pin updates are picked up
+  let stamp_path = frameworks_dir.join(format!(".{}.checksum", entry.name));
+  let extracted = framework_path.exists()
+    && read_to_string(&stamp_path).is_ok_and(|stamp| stamp.trim().eq_ignore_ascii_case(checksum));
+  if !extracted {
+    create_dir_all(&frameworks_dir)?;
+    if framework_path.exists() {
+      remove_dir_all(&framework_path)?;
+    }
+    Command::new("ditto")
+      .arg("-x")
+      .arg("-k")
+      .arg(&archive)
+      .arg(&frameworks_dir)
+      .output_ok()
+      .with_context(|| format!("failed to extract {}", archive.display()))?;
+    if !framework_path.exists() {
+      anyhow::bail!(
+        "the archive {} does not contain {} at its root",
+        archive.display(),
+        entry.name
+      );
+    }
+    write(&stamp_path, checksum)?;
+  }
+
+  Ok(())
+}
+
+/// Downloads the framework archive to the frameworks directory, reusing previous downloads.
+fn download(tauri_dir: &Path, entry: &Entry, url: &str) -> Result<PathBuf> {
+  let downloads_dir = tauri_dir.join(FRAMEWORKS_DIR).join(".downloads");
+  let archive = downloads_dir.join(format!("{}-{}.zip", entry.name, entry.version));
+  if !archive.exists() {
+    create_dir_all(&downloads_dir)?;
+    log::info!(action = "Downloading"; "{url}");
+    let response = ureq::get(url)
+      .call()
+      .with_context(|| format!("failed to download {url}"))?;
+    let mut file = File::create(&archive)?;
+    std::io::copy(&mut response.into_reader(), &mut file)?;
+  }
+  Ok(archive)
+}
+
+fn sha256(path: &Path) -> Result<String> {
+  let output = Command::new("shasum")
+    .args(["-a", "256"])
+    .arg(path)
+    .output_ok()
+    .with_context(|| format!("failed to compute the checksum of {}", path.display()))?;
+  String::from_utf8_lossy(&output.stdout)
+    .split_whitespace()
+    .next()
+    .map(ToString::to_string)
+    .ok_or_else(|| anyhow::anyhow!("failed to read the checksum of {}", path.display()))
+}
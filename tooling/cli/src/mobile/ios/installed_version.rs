@@ -0,0 +1,175 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{bundle_identifier, device_prompt, ensure_xcode, env, simctl, ErrorKind};
+use crate::{helpers::config::get as get_tauri_config, CommandExt, ConfigValue, Result};
+
+use anyhow::Context;
+use cargo_mobile2::apple::device::{Device, DeviceKind};
+use clap::Parser;
+use serde::Serialize;
+
+use std::{path::PathBuf, process::Command};
+
+#[derive(Debug, Clone, Parser)]
+#[clap(
+  about = "Print the version of the app installed on a device or simulator",
+  long_about = "Print the version and build number of the app installed on a device or simulator, or `not installed`.
+Physical devices require Xcode 15 or newer."
+)]
+pub struct Options {
+  /// The device name or physical device UDID to query.
+  #[clap(env = "TAURI_IOS_DEVICE")]
+  pub device: Option<String>,
+  /// JSON string or path to JSON file to merge with tauri.conf.json
+  #[clap(short, long)]
+  pub config: Option<ConfigValue>,
+  /// Skip prompting for values
+  #[clap(long, env = "CI")]
+  pub ci: bool,
+  /// Query the app with this bundle identifier instead of the configured `identifier`.
+  #[clap(long)]
+  pub bundle_id: Option<String>,
+  /// Print the result as JSON.
+  #[clap(long)]
+  pub json: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InstalledVersion {
+  bundle_id: String,
+  device: String,
+  installed: bool,
+  #[serde(flatten)]
+  app: Option<AppVersion>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AppVersion {
+  version: Option<String>,
+  build_number: Option<String>,
+}
+
+pub fn command(options: Options) -> Result<()> {
+  ensure_xcode()?;
+  let bundle_id = match options.bundle_id.clone() {
+    Some(bundle_id) => bundle_id,
+    None => {
+      let tauri_config = get_tauri_config(
+        tauri_utils::platform::Target::Ios,
+        options.config.as_ref().map(|c| &c.0),
+      )
+      .context(ErrorKind::InvalidConfig)?;
+      let tauri_config_guard = tauri_config.lock().unwrap();
+      bundle_identifier(tauri_config_guard.as_ref().unwrap())
+    }
+  };
+
+  let env = env()?;
+  let device = device_prompt(
+    &env,
+    options.device.as_deref(),
+    None,
+    None,
+    None,
+    Default::default(),
+    options.ci,
+  )?;
+
+  let installed = if matches!(device.kind(), DeviceKind::Simulator) {
+    simulator_installed_version(&device, &bundle_id)?
+  } else {
+    device_installed_version(&device, &bundle_id)?
+  };
+  let result = InstalledVersion {
+    bundle_id,
+    device: device.name().to_string(),
+    installed: installed.is_some(),
+    app: installed,
+  };
+
+  if options.json {
+    println!("{}", serde_json::to_string_pretty(&result)?);
+  } else if let Some(app) = &result.app {
+    println!(
+      "{} {}{} installed on {}",
+      result.bundle_id,
+      app.version.as_deref().unwrap_or("unknown version"),
+      app
+        .build_number
+        .as_ref()
+        .map(|build| format!(" ({build})"))
+        .unwrap_or_default(),
+      result.device
+    );
+  } else {
+    println!("{} not installed on {}", result.bundle_id, result.device);
+  }
+
+  Ok(())
+}
+
+/// Reads the version and build number of the app installed on a simulator, `None` if it is not installed.
+fn simulator_installed_version(device: &Device<'_>, bundle_id: &str) -> Result<Option<AppVersion>> {
+  let simulator = simctl::find_udid(device.id())?;
+  // simctl cannot query the apps of a shut down simulator
+  simctl::wait_booted(&simulator)?;
+  let Ok(output) = Command::new("xcrun")
+    .args([
+      "simctl",
+      "get_app_container",
+      simulator.udid.as_str(),
+      bundle_id,
+      "app",
+    ])
+    .output_ok()
+  else {
+    return Ok(None);
+  };
+
+  let app_path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+  let info_plist_path = app_path.join("Info.plist");
+  let info_plist = plist::Value::from_file(&info_plist_path)
+    .with_context(|| format!("failed to read {}", info_plist_path.display()))?;
+  let info_plist = info_plist.as_dictionary();
+  let read = |key: &str| {
+    info_plist
+      .and_then(|dict| dict.get(key))
+      .and_then(|value| value.as_string())
+      .map(ToString::to_string)
+  };
+  Ok(Some(AppVersion {
+    version: read("CFBundleShortVersionString"),
+    build_number: read("CFBundleVersion"),
+  }))
+}
+
+/// Reads the version and build number of the app installed on a physical device, `None` if it is not installed.
+fn device_installed_version(device: &Device<'_>, bundle_id: &str) -> Result<Option<AppVersion>> {
+  let output_path =
+    std::env::temp_dir().join(format!("tauri-ios-apps-{}.json", std::process::id()));
+  Command::new("xcrun")
+    .args(["devicectl", "device", "info", "apps", "--device"])
+    .arg(device.id())
+    .args(["--bundle-id", bundle_id, "--json-output"])
+    .arg(&output_path)
+    .output_ok()
+    .with_context(|| format!("failed to list the apps installed on {} with `xcrun devicectl`, which requires Xcode 15 or newer", device.name()))
+    .context(ErrorKind::NoDevice)?;
+  let output = std::fs::read(&output_path);
+  let _ = std::fs::remove_file(&output_path);
+  let output: serde_json::Value = serde_json::from_slice(&output?)?;
+
+  let app = output["result"]["apps"]
+    .as_array()
+    .into_iter()
+    .flatten()
+    .find(|app| app["bundleIdentifier"].as_str() == Some(bundle_id));
+  Ok(app.map(|app| AppVersion {
+    version: app["version"].as_str().map(ToString::to_string),
+    build_number: app["bundleVersion"].as_str().map(ToString::to_string),
+  }))
+}
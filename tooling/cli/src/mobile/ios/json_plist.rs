@@ -0,0 +1,94 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Converts the JSON plist sources of the configuration, e.g. `bundle > iOS > infoPlistFiles`, to plist values.
+//!
+//! Strings, booleans, numbers, arrays and objects map to their plist counterparts.
+//! Integers become plist integers and other numbers plist reals.
+//! JSON has no representation of the plist dates and data, which must be written in a `.plist` source instead,
+//! and `null` has no plist equivalent, so it is rejected with the path of the key instead of being coerced.
+
+use crate::Result;
+
+use serde_json::Value as JsonValue;
+
+/// Converts a JSON value to a plist value, failing on `null` values.
+pub fn to_plist(value: &JsonValue) -> Result<plist::Value> {
+  convert(value, "")
+}
+
+fn convert(value: &JsonValue, path: &str) -> Result<plist::Value> {
+  Ok(match value {
+    JsonValue::String(s) => s.clone().into(),
+    JsonValue::Bool(b) => (*b).into(),
+    JsonValue::Number(n) => {
+      if let Some(i) = n.as_i64() {
+        i.into()
+      } else if let Some(u) = n.as_u64() {
+        u.into()
+      } else {
+        // serde_json numbers are finite, so this always succeeds
+        n.as_f64().unwrap_or_default().into()
+      }
+    }
+    JsonValue::Array(values) => values
+      .iter()
+      .enumerate()
+      .map(|(i, value)| convert(value, &format!("{path}[{i}]")))
+      .collect::<Result<Vec<_>>>()?
+      .into(),
+    JsonValue::Object(object) => {
+      let mut dict = plist::Dictionary::new();
+      for (key, value) in object {
+        let path = if path.is_empty() {
+          key.clone()
+        } else {
+          format!("{path}.{key}")
+        };
+        dict.insert(key.clone(), convert(value, &path)?);
+      }
+      dict.into()
+    }
+    JsonValue::Null => anyhow::bail!(
+      "`{}` is null, which cannot be represented in a plist. Remove the key, or set it to `$delete` to remove it from the merged plist",
+      if path.is_empty() { "the value" } else { path }
+    ),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::to_plist;
+
+  #[test]
+  fn converts_supported_values() {
+    let json = serde_json::json!({
+      "CFBundleDisplayName": "Tauri",
+      "UIFileSharingEnabled": true,
+      "Build": 42,
+      "Ratio": 1.5,
+      "Schemes": ["tauri"],
+      "NSAppTransportSecurity": { "NSAllowsArbitraryLoads": false },
+    });
+    let plist = to_plist(&json).unwrap().into_dictionary().unwrap();
+    assert_eq!(plist["CFBundleDisplayName"].as_string(), Some("Tauri"));
+    assert_eq!(plist["UIFileSharingEnabled"].as_boolean(), Some(true));
+    assert_eq!(plist["Build"].as_signed_integer(), Some(42));
+    assert_eq!(plist["Ratio"].as_real(), Some(1.5));
+    assert_eq!(plist["Schemes"].as_array().map(Vec::len), Some(1));
+    assert_eq!(
+      plist["NSAppTransportSecurity"]
+        .as_dictionary()
+        .and_then(|ats| ats["NSAllowsArbitraryLoads"].as_boolean()),
+      Some(false)
+    );
+  }
+
+  #[test]
+  fn rejects_null_with_its_path() {
+    let json = serde_json::json!({ "NSAppTransportSecurity": { "NSExceptionDomains": [null] } });
+    let error = to_plist(&json).unwrap_err().to_string();
+    assert!(error.contains("`NSAppTransportSecurity.NSExceptionDomains[0]` is null"));
+  }
+}
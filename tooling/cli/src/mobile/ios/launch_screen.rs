@@ -0,0 +1,170 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Generates the launch screen from the `bundle > iOS > launchScreen` configuration.
+
+use super::ErrorKind;
+use crate::{helpers::config::Config as TauriConfig, Result};
+
+use anyhow::Context;
+use serde::Serialize;
+use tauri_utils::config::IosLaunchScreen;
+
+use std::{
+  fs::{copy, create_dir_all, write},
+  path::Path,
+};
+
+const IMAGE_SET: &str = "Assets.xcassets/LaunchImage.imageset";
+/// The name of the image in the asset catalog, referenced by `LaunchScreen.storyboard`.
+const IMAGE_NAME: &str = "LaunchImage";
+/// The launch image is provided at the 3x scale, iOS downscales it for the 2x devices.
+const IMAGE_SCALE: u32 = 3;
+
+/// The data of the `LaunchScreen.storyboard` template.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TemplateData {
+  background_color: Option<Color>,
+  image: Option<Image>,
+}
+
+/// A color with components between 0 and 1, the format of storyboard colors.
+#[derive(Debug, Serialize)]
+struct Color {
+  red: f64,
+  green: f64,
+  blue: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct Image {
+  name: &'static str,
+  /// Size in points.
+  width: u32,
+  height: u32,
+}
+
+/// Parses a `#RRGGBB` hex color.
+fn parse_color(color: &str) -> Option<Color> {
+  let hex = color.strip_prefix('#')?;
+  if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+    return None;
+  }
+  let component = |i: usize| {
+    u8::from_str_radix(&hex[i..i + 2], 16)
+      .ok()
+      .map(|c| c as f64 / 255.0)
+  };
+  Some(Color {
+    red: component(0)?,
+    green: component(2)?,
+    blue: component(4)?,
+  })
+}
+
+/// Checks that the background color is a valid hex color and that the image is an existing PNG file.
+pub fn validate(launch_screen: &IosLaunchScreen, tauri_dir: &Path) -> Result<()> {
+  let mut problems = Vec::new();
+
+  if let Some(color) = &launch_screen.background_color {
+    if parse_color(color).is_none() {
+      problems.push(format!(
+        "backgroundColor `{color}` is not a `#RRGGBB` hex color"
+      ));
+    }
+  }
+  if let Some(image) = &launch_screen.image {
+    let path = tauri_dir.join(image);
+    if !path.exists() {
+      problems.push(format!("image {} does not exist", image.display()));
+    } else if !path.extension().is_some_and(|ext| ext == "png") {
+      problems.push(format!("image {} must be a PNG file", image.display()));
+    }
+  }
+
+  if problems.is_empty() {
+    Ok(())
+  } else {
+    Err(
+      anyhow::anyhow!(
+        "invalid `bundle > iOS > launchScreen` configuration:\n{}",
+        problems
+          .iter()
+          .map(|p| format!("  - {p}"))
+          .collect::<Vec<_>>()
+          .join("\n")
+      )
+      .context(ErrorKind::InvalidConfig),
+    )
+  }
+}
+
+/// Resolves the background color and the image size of the launch screen for the storyboard template.
+pub fn template_data(tauri_config: &TauriConfig, tauri_dir: &Path) -> Result<TemplateData> {
+  let Some(launch_screen) = &tauri_config.bundle.ios.launch_screen else {
+    return Ok(TemplateData::default());
+  };
+  let image = match &launch_screen.image {
+    Some(image) => {
+      let path = tauri_dir.join(image);
+      let (width, height) = image::image_dimensions(&path)
+        .with_context(|| format!("failed to read the launch screen image {}", path.display()))
+        .context(ErrorKind::InvalidConfig)?;
+      Some(Image {
+        name: IMAGE_NAME,
+        width: width / IMAGE_SCALE,
+        height: height / IMAGE_SCALE,
+      })
+    }
+    None => None,
+  };
+  Ok(TemplateData {
+    background_color: launch_screen
+      .background_color
+      .as_deref()
+      .and_then(parse_color),
+    image,
+  })
+}
+
+/// Copies the launch screen image to the asset catalog of the Xcode project, so updates of the image are picked up
+/// without recreating the project.
+pub fn write_image(project_dir: &Path, tauri_dir: &Path, tauri_config: &TauriConfig) -> Result<()> {
+  let Some(image) = tauri_config
+    .bundle
+    .ios
+    .launch_screen
+    .as_ref()
+    .and_then(|launch_screen| launch_screen.image.as_ref())
+  else {
+    return Ok(());
+  };
+
+  let image_set = project_dir.join(IMAGE_SET);
+  create_dir_all(&image_set)?;
+  let file_name = format!("{IMAGE_NAME}.png");
+  copy(tauri_dir.join(image), image_set.join(&file_name)).with_context(|| {
+    format!(
+      "failed to copy the launch screen image {} to the Xcode project",
+      image.display()
+    )
+  })?;
+  let contents = serde_json::json!({
+    "images": [{
+      "idiom": "universal",
+      "filename": file_name,
+      "scale": format!("{IMAGE_SCALE}x"),
+    }],
+    "info": {
+      "author": "xcode",
+      "version": 1,
+    },
+  });
+  write(
+    image_set.join("Contents.json"),
+    serde_json::to_string_pretty(&contents)?,
+  )?;
+  Ok(())
+}
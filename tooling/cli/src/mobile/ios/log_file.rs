@@ -0,0 +1,248 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Copies the output of the CLI and of the commands it runs, like xcodebuild, to a `--log-file`.
+//!
+//! The stdout and stderr file descriptors are replaced so the child processes inherit them,
+//! and their output is forwarded to the console and appended to the file.
+//! A console that is a terminal is replaced with a pseudo-terminal, so the colors and progress output are kept.
+//!
+//! The pending output is also written when the process exits without dropping the [`LogFile`],
+//! e.g. with [`std::process::exit`] or on Ctrl+C.
+
+use crate::Result;
+
+use anyhow::Context;
+
+use std::{
+  fs::{create_dir_all, File},
+  io::{Read, Write},
+  os::fd::{AsRawFd, FromRawFd, IntoRawFd, OwnedFd, RawFd},
+  path::Path,
+  sync::{
+    mpsc::{channel, Receiver},
+    Arc, Mutex,
+  },
+  time::{Duration, Instant},
+};
+
+/// How long to wait for the pending output once the console is restored.
+///
+/// The output of the processes still running afterwards, e.g. detached by xcodebuild, is not waited for.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The redirected output of the process, restored by [`finish`].
+static REDIRECT: Mutex<Option<Redirect>> = Mutex::new(None);
+
+/// Restores the console output when dropped, waiting for the pending output to be written.
+pub struct LogFile {
+  file: Arc<Mutex<File>>,
+}
+
+impl LogFile {
+  /// Appends the error the command failed with, which is printed after the output is restored.
+  pub fn record_error(&self, error: &anyhow::Error) {
+    let _ = writeln!(self.file.lock().unwrap(), "Error {error:#}");
+  }
+}
+
+impl Drop for LogFile {
+  fn drop(&mut self) {
+    finish();
+  }
+}
+
+struct Redirect {
+  restore: Vec<(RawFd, RawFd)>,
+  forwarders: usize,
+  done: Receiver<()>,
+}
+
+impl Redirect {
+  fn restore(self) {
+    let _ = std::io::stdout().flush();
+    let _ = std::io::stderr().flush();
+    for (fd, saved) in self.restore {
+      // closes the write end so the forwarding thread reaches the end of the output
+      unsafe {
+        libc::dup2(saved, fd);
+        libc::close(saved);
+      }
+    }
+    let deadline = Instant::now() + FLUSH_TIMEOUT;
+    for _ in 0..self.forwarders {
+      let timeout = deadline.saturating_duration_since(Instant::now());
+      if self.done.recv_timeout(timeout).is_err() {
+        break;
+      }
+    }
+  }
+}
+
+/// Restores the console output and writes the pending output, does nothing if it is already restored.
+fn finish() {
+  // the lock is only contended if the process exits while the output is being restored
+  let redirect = match REDIRECT.try_lock() {
+    Ok(mut redirect) => redirect.take(),
+    Err(_) => None,
+  };
+  if let Some(redirect) = redirect {
+    redirect.restore();
+  }
+}
+
+extern "C" fn finish_at_exit() {
+  finish();
+}
+
+/// Starts copying the output of the process to the file at `path`, creating its parent directories.
+pub fn tee(path: &Path) -> Result<LogFile> {
+  if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+    create_dir_all(parent)
+      .with_context(|| format!("failed to create directory {}", parent.display()))?;
+  }
+  let file =
+    File::create(path).with_context(|| format!("failed to create log file {}", path.display()))?;
+  let file = Arc::new(Mutex::new(file));
+
+  let (done_tx, done) = channel();
+  let mut redirect = Redirect {
+    restore: Vec::new(),
+    forwarders: 0,
+    done,
+  };
+  for fd in [libc::STDOUT_FILENO, libc::STDERR_FILENO] {
+    let (mut reader, writer) = output_pipe(fd)?;
+    let (saved, console) = unsafe {
+      (
+        libc::fcntl(fd, libc::F_DUPFD_CLOEXEC, 0),
+        libc::fcntl(fd, libc::F_DUPFD_CLOEXEC, 0),
+      )
+    };
+    if saved < 0 || console < 0 || unsafe { libc::dup2(writer.as_raw_fd(), fd) } < 0 {
+      let error = std::io::Error::last_os_error();
+      redirect.restore();
+      return Err(error).context("failed to redirect the output");
+    }
+    drop(writer);
+    redirect.restore.push((fd, saved));
+
+    let mut console = unsafe { File::from_raw_fd(console) };
+    let file = file.clone();
+    let done = done_tx.clone();
+    std::thread::spawn(move || {
+      let mut buf = [0; 8192];
+      let mut stripper = AnsiStripper::default();
+      loop {
+        // a pseudo-terminal fails with EIO instead of reaching the end once its last writer is closed
+        match reader.read(&mut buf) {
+          Ok(0) | Err(_) => break,
+          Ok(n) => {
+            let _ = console.write_all(&buf[..n]);
+            let _ = file.lock().unwrap().write_all(&stripper.strip(&buf[..n]));
+          }
+        }
+      }
+      let _ = done.send(());
+    });
+    redirect.forwarders += 1;
+  }
+  *REDIRECT.lock().unwrap() = Some(redirect);
+
+  unsafe {
+    libc::atexit(finish_at_exit);
+  }
+  // the default Ctrl+C handling terminates the process without running the exit handlers,
+  // this is the handler the dev command installs for the `beforeDevCommand`
+  let _ = ctrlc::set_handler(|| {
+    crate::dev::kill_before_dev_process();
+    std::process::exit(130);
+  });
+
+  Ok(LogFile { file })
+}
+
+/// Creates the reader the output is forwarded from and the file descriptor that replaces the output.
+///
+/// It is a pseudo-terminal when the output is a terminal, a pipe otherwise.
+fn output_pipe(fd: RawFd) -> Result<(File, OwnedFd)> {
+  if unsafe { libc::isatty(fd) } != 1 {
+    let (reader, writer) = os_pipe::pipe()?;
+    return Ok(unsafe {
+      (
+        File::from_raw_fd(reader.into_raw_fd()),
+        OwnedFd::from_raw_fd(writer.into_raw_fd()),
+      )
+    });
+  }
+
+  let (mut master, mut slave) = (-1, -1);
+  let mut size: libc::winsize = unsafe { std::mem::zeroed() };
+  let size = if unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut size) } == 0 {
+    &mut size as *mut libc::winsize
+  } else {
+    std::ptr::null_mut()
+  };
+  if unsafe {
+    libc::openpty(
+      &mut master,
+      &mut slave,
+      std::ptr::null_mut(),
+      std::ptr::null_mut(),
+      size,
+    )
+  } < 0
+  {
+    return Err(std::io::Error::last_os_error()).context("failed to open a pseudo-terminal");
+  }
+  let (master, slave) = unsafe { (File::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+  unsafe {
+    // the children must not inherit the reader, and the output is forwarded as is, e.g. without CRLF translation
+    libc::fcntl(master.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC);
+    let mut termios: libc::termios = std::mem::zeroed();
+    if libc::tcgetattr(slave.as_raw_fd(), &mut termios) == 0 {
+      libc::cfmakeraw(&mut termios);
+      libc::tcsetattr(slave.as_raw_fd(), libc::TCSANOW, &termios);
+    }
+  }
+  Ok((master, slave))
+}
+
+/// Removes the ANSI escape sequences, e.g. colors, from the output written to the log file.
+///
+/// Keeps its state between chunks since a sequence can be split across reads.
+#[derive(Default)]
+struct AnsiStripper {
+  state: AnsiState,
+}
+
+#[derive(Default, Clone, Copy)]
+enum AnsiState {
+  #[default]
+  Text,
+  Escape,
+  Sequence,
+}
+
+impl AnsiStripper {
+  fn strip(&mut self, bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    for &byte in bytes {
+      self.state = match (self.state, byte) {
+        (AnsiState::Text, 0x1b) => AnsiState::Escape,
+        (AnsiState::Text, _) => {
+          out.push(byte);
+          AnsiState::Text
+        }
+        (AnsiState::Escape, b'[') => AnsiState::Sequence,
+        // two byte sequences
+        (AnsiState::Escape, _) => AnsiState::Text,
+        // the final byte of a control sequence is in the `@`..=`~` range
+        (AnsiState::Sequence, 0x40..=0x7e) => AnsiState::Text,
+        (AnsiState::Sequence, _) => AnsiState::Sequence,
+      };
+    }
+    out
+  }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
+use anyhow::Context;
 use cargo_mobile2::{
   apple::{
     config::{
@@ -14,37 +15,78 @@ use cargo_mobile2::{
   },
   config::app::{App, DEFAULT_ASSET_DIR},
   env::Env,
-  opts::NoiseLevel,
+  opts::{NoiseLevel, Profile},
   os,
   util::{prompt, relativize_path},
 };
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use sublime_fuzzy::best_match;
 
 use super::{
   ensure_init, env, get_app,
   init::{command as init_command, configure_cargo},
-  log_finished, read_options, CliOptions, OptionsHandle, Target as MobileTarget,
-  MIN_DEVICE_MATCH_SCORE,
+  log_finished, min_device_match_score, read_options, CliOptions, OptionsHandle,
+  Target as MobileTarget,
 };
 use crate::{
   helpers::{app_paths::tauri_dir, config::Config as TauriConfig},
-  Result,
+  CommandExt, ConfigValue, Result,
 };
+use tauri_utils::config::IosDeviceFamily;
 
 use std::{
-  env::{set_var, var_os},
+  collections::{BTreeMap, HashMap},
+  env::var_os,
+  ffi::OsString,
   fs::create_dir_all,
   path::{Path, PathBuf},
+  process::Command,
+  sync::{
+    atomic::{AtomicBool, Ordering},
+    OnceLock,
+  },
   thread::sleep,
-  time::Duration,
+  time::{Duration, Instant},
 };
 
+mod add_framework;
+mod app_icon;
+mod assets;
 mod build;
+mod build_env;
+mod catalyst;
+mod crashlogs;
 mod dev;
+mod device_tools;
+mod diff_metadata;
+mod dsym;
+mod error;
+mod features_matrix;
+pub(crate) mod frameworks_manifest;
+mod installed_version;
+mod json_plist;
+pub(crate) mod launch_screen;
+mod log_file;
+pub(crate) mod on_demand_resources;
+mod open_container;
+mod print_command;
+pub(crate) mod privacy_manifest;
+mod profiles;
 pub(crate) mod project;
+mod signing_bundle;
+mod signing_fingerprint;
+mod signing_info;
+mod simctl;
+mod simulators;
+mod summary;
+pub(crate) mod target_entitlements;
+mod timings;
+mod validate;
+mod xcframework;
 mod xcode_script;
 
+pub use error::{exit_code, ErrorKind};
+
 pub const APPLE_DEVELOPMENT_TEAM_ENV_VAR_NAME: &str = "APPLE_DEVELOPMENT_TEAM";
 const TARGET_IOS_VERSION: &str = "13.0";
 
@@ -80,6 +122,16 @@ enum Commands {
   Init(InitOptions),
   Dev(dev::Options),
   Build(build::Options),
+  AddFramework(add_framework::Options),
+  SigningInfo(signing_info::Options),
+  Profiles(profiles::Cli),
+  Simulators(simulators::Cli),
+  Validate(validate::Options),
+  Crashlogs(crashlogs::Options),
+  InstalledVersion(installed_version::Options),
+  OpenContainer(open_container::Options),
+  Xcframework(xcframework::Options),
+  DiffMetadata(diff_metadata::Options),
   #[clap(hide(true))]
   XcodeScript(xcode_script::Options),
 }
@@ -95,18 +147,164 @@ pub fn command(cli: Cli, verbosity: u8) -> Result<()> {
     )?,
     Commands::Dev(options) => dev::command(options, noise_level)?,
     Commands::Build(options) => build::command(options, noise_level)?,
+    Commands::AddFramework(options) => add_framework::command(options)?,
+    Commands::SigningInfo(options) => signing_info::command(options)?,
+    Commands::Profiles(cli) => profiles::command(cli)?,
+    Commands::Simulators(cli) => simulators::command(cli)?,
+    Commands::Validate(options) => validate::command(options)?,
+    Commands::Crashlogs(options) => crashlogs::command(options)?,
+    Commands::InstalledVersion(options) => installed_version::command(options)?,
+    Commands::OpenContainer(options) => open_container::command(options)?,
+    Commands::Xcframework(options) => xcframework::command(options, noise_level)?,
+    Commands::DiffMetadata(options) => diff_metadata::command(options)?,
     Commands::XcodeScript(options) => xcode_script::command(options)?,
   }
 
   Ok(())
 }
 
+/// Whether the development team warning was already logged, or suppressed, in this process.
+static DEVELOPMENT_TEAM_WARNING_SHOWN: AtomicBool = AtomicBool::new(false);
+
+/// Disables the warning logged when the development team cannot be resolved,
+/// for users relying on Xcode-managed signing.
+fn suppress_development_team_warning() {
+  DEVELOPMENT_TEAM_WARNING_SHOWN.store(true, Ordering::Relaxed);
+}
+
+/// Logs the development team warning once per process.
+fn warn_development_team(message: std::fmt::Arguments<'_>) {
+  if !DEVELOPMENT_TEAM_WARNING_SHOWN.swap(true, Ordering::Relaxed) {
+    log::warn!("{message}");
+  }
+}
+
+/// Options of [`get_config`] set by the commands building the app.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ConfigOptions {
+  /// Print the source of each resolved value,
+  /// to debug the layering of environment variables, `tauri.conf.json` and defaults.
+  pub explain: bool,
+  /// Resolve the config of the Mac Catalyst variant, see `tauri ios build --catalyst`.
+  pub catalyst: bool,
+}
+
+/// Resolved values of the iOS config with the source that provided them.
+#[derive(Default)]
+struct ConfigExplanation(Vec<(&'static str, String, String)>);
+
+impl ConfigExplanation {
+  fn record(
+    &mut self,
+    key: &'static str,
+    value: impl std::fmt::Display,
+    source: impl Into<String>,
+  ) {
+    self.0.push((key, value.to_string(), source.into()));
+  }
+
+  fn print(&self) {
+    let key_width = self
+      .0
+      .iter()
+      .map(|(key, _, _)| key.len())
+      .max()
+      .unwrap_or_default();
+    log::info!(action = "Config"; "resolved iOS config values:");
+    for (key, value, source) in &self.0 {
+      log::info!("  {key:<key_width$}  {value}  (from {source})");
+    }
+  }
+}
+
+/// Maximum length of the app name, which is used for the Xcode project, target and scheme names.
+const MAX_APP_NAME_LEN: usize = 64;
+/// App names that clash with the frameworks and packages the Xcode project depends on.
+const RESERVED_APP_NAMES: &[&str] = &["Foundation", "Pods", "Swift", "Tauri", "UIKit", "WebKit"];
+
+/// Checks that the app name, the Cargo package name, can be used for the Xcode project.
+fn validate_app_name(name: &str) -> Result<()> {
+  let problem = if name.is_empty() {
+    Some("it cannot be empty".to_string())
+  } else if name.len() > MAX_APP_NAME_LEN {
+    Some(format!(
+      "it cannot be longer than {MAX_APP_NAME_LEN} characters"
+    ))
+  } else if !name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+    Some("it must start with an ASCII letter".to_string())
+  } else if !name
+    .chars()
+    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+  {
+    Some("it can only contain ASCII letters, digits, `-` and `_`".to_string())
+  } else if RESERVED_APP_NAMES
+    .iter()
+    .any(|reserved| reserved.eq_ignore_ascii_case(name))
+  {
+    Some("it is reserved by a framework the Xcode project depends on".to_string())
+  } else {
+    None
+  };
+
+  match problem {
+    Some(problem) => {
+      let mut suggestion = name
+        .chars()
+        .map(|c| {
+          if c.is_ascii_alphanumeric() || c == '_' {
+            c
+          } else {
+            '-'
+          }
+        })
+        .skip_while(|c| !c.is_ascii_alphabetic())
+        .take(MAX_APP_NAME_LEN)
+        .collect::<String>();
+      if suggestion.is_empty() {
+        suggestion = "app".into();
+      } else if RESERVED_APP_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(&suggestion))
+      {
+        suggestion.push_str("-app");
+      }
+      Err(
+        anyhow::anyhow!(
+          "the app name `{name}` cannot be used for the iOS project: {problem}. The app name is read from the `package > name` value of the Cargo.toml file, rename it to e.g. `{suggestion}`"
+        )
+        .context(ErrorKind::InvalidConfig),
+      )
+    }
+    None => Ok(()),
+  }
+}
+
 pub fn get_config(
   app: &App,
   tauri_config: &TauriConfig,
   features: Option<&Vec<String>>,
   cli_options: &CliOptions,
-) -> (AppleConfig, AppleMetadata) {
+  profile: Option<Profile>,
+  options: ConfigOptions,
+) -> Result<(AppleConfig, AppleMetadata)> {
+  validate_app_name(app.name())?;
+  validate_deep_links(tauri_config)?;
+  validate_bundle_identifier(tauri_config)?;
+  if let Some(manifest) = &tauri_config.bundle.ios.privacy_manifest {
+    privacy_manifest::validate(manifest)?;
+  }
+  if let Some(resources) = &tauri_config.bundle.ios.on_demand_resources {
+    on_demand_resources::validate(resources, &tauri_dir())?;
+  }
+  if let Some(launch_screen) = &tauri_config.bundle.ios.launch_screen {
+    launch_screen::validate(launch_screen, &tauri_dir())?;
+  }
+  if let Some(assets) = &tauri_config.bundle.ios.assets {
+    assets::validate(assets)?;
+  }
+  // fails on invalid values before the project is generated
+  metal_build_settings(tauri_config)?;
+
   let mut ios_options = cli_options.clone();
   if let Some(features) = features {
     ios_options
@@ -115,28 +313,101 @@ pub fn get_config(
       .extend_from_slice(features);
   }
 
+  let mut explanation = ConfigExplanation::default();
+  explanation.record(
+    "features",
+    ios_options
+      .features
+      .as_ref()
+      .filter(|features| !features.is_empty())
+      .map(|features| features.join(", "))
+      .unwrap_or_else(|| "none".into()),
+    if features.is_some_and(|features| !features.is_empty()) {
+      "`--features` and the features matrix"
+    } else {
+      "default"
+    },
+  );
+
+  let env_development_team = std::env::var(APPLE_DEVELOPMENT_TEAM_ENV_VAR_NAME).ok();
+  let config_development_team = configured_development_team_with_source(tauri_config, profile);
+  if let (Some(env_team), Some((config_team, _))) =
+    (&env_development_team, &config_development_team)
+  {
+    if env_team != config_team {
+      log::warn!("The `{APPLE_DEVELOPMENT_TEAM_ENV_VAR_NAME}` environment variable ({env_team}) does not match the development team configured in `bundle > iOS` ({config_team}). Using {env_team} from the environment variable.");
+    }
+  }
+
+  let bundle_version = tauri_config
+    .version
+    .as_deref()
+    .map(apple_bundle_version)
+    .transpose()?;
+
+  let development_team = if let Some(team) = env_development_team {
+    explanation.record(
+      "development team",
+      &team,
+      format!("the `{APPLE_DEVELOPMENT_TEAM_ENV_VAR_NAME}` environment variable"),
+    );
+    Some(team)
+  } else if let Some((team, source)) = config_development_team {
+    explanation.record("development team", &team, source);
+    Some(team)
+  } else {
+    let teams = find_development_teams().unwrap_or_default();
+    let team = match teams.len() {
+      0 => {
+        warn_development_team(format_args!("No code signing certificates found. You must add one and set the certificate development team ID on the `bundle > iOS > developmentTeam` config value or the `{APPLE_DEVELOPMENT_TEAM_ENV_VAR_NAME}` environment variable. To list the available certificates, run `tauri info`."));
+        None
+      }
+      1 => Some(teams.first().unwrap().id.clone()),
+      _ => {
+        warn_development_team(format_args!("You must set the code signing certificate development team ID on  the `bundle > iOS > developmentTeam` config value or the `{APPLE_DEVELOPMENT_TEAM_ENV_VAR_NAME}` environment variable. Available certificates: {}", teams.iter().map(|t| format!("{} (ID: {})", t.name, t.id)).collect::<Vec<String>>().join(", ")));
+        None
+      }
+    };
+    match &team {
+      Some(team) => explanation.record(
+        "development team",
+        team,
+        "the only code signing certificate installed",
+      ),
+      None => explanation.record("development team", "not set", "default"),
+    }
+    team
+  };
+
+  if let Some(team) = &development_team {
+    if manual_signing_expected(tauri_config) {
+      warn_team_without_installed_profiles(team);
+    }
+  }
+
+  match &bundle_version {
+    Some(version) => explanation.record("bundle version", version, "`version`"),
+    None => explanation.record("bundle version", "not set", "default"),
+  }
+  let ios_version = if options.catalyst {
+    explanation.record("iOS version", catalyst::IOS_VERSION, "`--catalyst`");
+    catalyst::IOS_VERSION
+  } else {
+    explanation.record("iOS version", TARGET_IOS_VERSION, "default");
+    TARGET_IOS_VERSION
+  };
+  match &tauri_config.bundle.ios.project_dir {
+    Some(dir) => explanation.record("project directory", dir, "`bundle > iOS > projectDir`"),
+    None => explanation.record("project directory", "gen/apple", "default"),
+  }
+
   let raw = RawAppleConfig {
-    development_team: std::env::var(APPLE_DEVELOPMENT_TEAM_ENV_VAR_NAME)
-        .ok()
-        .or_else(|| tauri_config.bundle.ios.development_team.clone())
-        .or_else(|| {
-          let teams = find_development_teams().unwrap_or_default();
-          match teams.len() {
-            0 => {
-              log::warn!("No code signing certificates found. You must add one and set the certificate development team ID on the `bundle > iOS > developmentTeam` config value or the `{APPLE_DEVELOPMENT_TEAM_ENV_VAR_NAME}` environment variable. To list the available certificates, run `tauri info`.");
-              None
-            }
-            1 => Some(teams.first().unwrap().id.clone()),
-            _ => {
-              log::warn!("You must set the code signing certificate development team ID on  the `bundle > iOS > developmentTeam` config value or the `{APPLE_DEVELOPMENT_TEAM_ENV_VAR_NAME}` environment variable. Available certificates: {}", teams.iter().map(|t| format!("{} (ID: {})", t.name, t.id)).collect::<Vec<String>>().join(", "));
-              None
-            }
-          }
-        }),
+    development_team,
     ios_features: ios_options.features.clone(),
-    bundle_version: tauri_config.version.clone(),
-    bundle_version_short: tauri_config.version.clone(),
-    ios_version: Some(TARGET_IOS_VERSION.into()),
+    bundle_version: bundle_version.clone(),
+    bundle_version_short: bundle_version,
+    ios_version: Some(ios_version.into()),
+    project_dir: tauri_config.bundle.ios.project_dir.clone(),
     ..Default::default()
   };
   let config = AppleConfig::from_raw(app.clone(), Some(raw)).unwrap();
@@ -145,31 +416,38 @@ pub fn get_config(
 
   let mut vendor_frameworks = Vec::new();
   let mut frameworks = Vec::new();
-  for framework in tauri_config
+  let excluded_frameworks = excluded_frameworks(tauri_config, profile);
+  let config_frameworks = tauri_config
     .bundle
     .ios
     .frameworks
     .clone()
-    .unwrap_or_default()
-  {
-    let framework_path = PathBuf::from(&framework);
-    let ext = framework_path.extension().unwrap_or_default();
-    if ext.is_empty() {
-      frameworks.push(framework);
-    } else if ext == "framework" {
-      frameworks.push(
-        framework_path
-          .file_stem()
-          .unwrap()
-          .to_string_lossy()
-          .to_string(),
-      );
+    .unwrap_or_default();
+  for framework in declared_frameworks(tauri_config, &tauri_dir)? {
+    let source = if config_frameworks.contains(&framework) {
+      "`bundle > iOS > frameworks`"
     } else {
-      vendor_frameworks.push(
-        relativize_path(tauri_dir.join(framework_path), config.project_dir())
-          .to_string_lossy()
-          .to_string(),
+      "`ios-frameworks.json`"
+    };
+    if excluded_frameworks.contains(&framework) {
+      explanation.record(
+        "framework",
+        &framework,
+        format!("{source}, excluded by `bundle > iOS > excludedFrameworks`"),
       );
+      continue;
+    }
+    explanation.record("framework", &framework, source);
+    match Framework::resolve(&framework, &tauri_dir, &config.project_dir()) {
+      Framework::System(name) => frameworks.push(name),
+      Framework::Vendor(path) => {
+        check_framework_minimum_os_version(
+          &config.project_dir().join(&path),
+          &framework,
+          ios_version,
+        )?;
+        vendor_frameworks.push(path)
+      }
     }
   }
 
@@ -185,209 +463,2381 @@ pub fn get_config(
     macos: Default::default(),
   };
 
-  set_var("TAURI_IOS_PROJECT_PATH", config.project_dir());
-  set_var("TAURI_IOS_APP_NAME", config.app().name());
+  if options.explain {
+    explanation.print();
+  }
 
-  (config, metadata)
+  Ok((config, metadata))
 }
 
-fn connected_device_prompt<'a>(env: &'_ Env, target: Option<&str>) -> Result<Device<'a>> {
-  let device_list = device::list_devices(env)
-    .map_err(|cause| anyhow::anyhow!("Failed to detect connected iOS devices: {cause}"))?;
-  if !device_list.is_empty() {
-    let device = if let Some(t) = target {
-      let (device, score) = device_list
-        .into_iter()
-        .rev()
-        .map(|d| {
-          let score = best_match(t, d.name()).map_or(0, |m| m.score());
-          (d, score)
-        })
-        .max_by_key(|(_, score)| *score)
-        // we already checked the list is not empty
-        .unwrap();
-      if score > MIN_DEVICE_MATCH_SCORE {
-        device
-      } else {
-        anyhow::bail!("Could not find an iOS device matching {t}")
-      }
-    } else {
-      let index = if device_list.len() > 1 {
-        prompt::list(
-          concat!("Detected ", "iOS", " devices"),
-          device_list.iter(),
-          "device",
-          None,
-          "Device",
-        )
-        .map_err(|cause| anyhow::anyhow!("Failed to prompt for iOS device: {cause}"))?
-      } else {
-        0
-      };
-      device_list.into_iter().nth(index).unwrap()
-    };
-    println!(
-      "Detected connected device: {} with target {:?}",
-      device,
-      device.target().triple,
-    );
-    Ok(device)
-  } else {
-    Err(anyhow::anyhow!("No connected iOS devices detected"))
+/// Name of the Xcode project build configuration for the given profile.
+///
+/// The generated project declares the lowercase `debug` and `release` configurations in the `configs` of its `project.yml`,
+/// instead of the `Debug` and `Release` defaults of Xcode.
+pub fn configuration_name(profile: Profile) -> &'static str {
+  match profile {
+    Profile::Debug => "debug",
+    Profile::Release => "release",
   }
 }
 
-fn simulator_prompt(env: &'_ Env, target: Option<&str>) -> Result<device::Simulator> {
-  let simulator_list = device::list_simulators(env).map_err(|cause| {
-    anyhow::anyhow!("Failed to detect connected iOS Simulator devices: {cause}")
-  })?;
-  if !simulator_list.is_empty() {
-    let device = if let Some(t) = target {
-      let (device, score) = simulator_list
-        .into_iter()
-        .rev()
-        .map(|d| {
-          let score = best_match(t, d.name()).map_or(0, |m| m.score());
-          (d, score)
+/// Resolves the development team of the build configuration from the `bundle > iOS > developmentTeams` config,
+/// falling back to `bundle > iOS > developmentTeam`.
+fn configured_development_team(
+  tauri_config: &TauriConfig,
+  profile: Option<Profile>,
+) -> Option<String> {
+  configured_development_team_with_source(tauri_config, profile).map(|(team, _)| team)
+}
+
+/// Same as `configured_development_team`, also returning the config key the team was read from.
+fn configured_development_team_with_source(
+  tauri_config: &TauriConfig,
+  profile: Option<Profile>,
+) -> Option<(String, String)> {
+  let ios = &tauri_config.bundle.ios;
+  profile
+    .and_then(|profile| {
+      let configuration = configuration_name(profile);
+      ios
+        .development_teams
+        .as_ref()?
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(configuration))
+        .map(|(name, team)| {
+          (
+            team.clone(),
+            format!("`bundle > iOS > developmentTeams > {name}`"),
+          )
         })
-        .max_by_key(|(_, score)| *score)
-        // we already checked the list is not empty
-        .unwrap();
-      if score > MIN_DEVICE_MATCH_SCORE {
-        device
-      } else {
-        anyhow::bail!("Could not find an iOS Simulator matching {t}")
-      }
-    } else if simulator_list.len() > 1 {
-      let index = prompt::list(
-        concat!("Detected ", "iOS", " simulators"),
-        simulator_list.iter(),
-        "simulator",
-        None,
-        "Simulator",
-      )
-      .map_err(|cause| anyhow::anyhow!("Failed to prompt for iOS Simulator device: {cause}"))?;
-      simulator_list.into_iter().nth(index).unwrap()
-    } else {
-      simulator_list.into_iter().next().unwrap()
-    };
-    Ok(device)
-  } else {
-    Err(anyhow::anyhow!("No available iOS Simulator detected"))
+    })
+    .or_else(|| {
+      ios
+        .development_team
+        .clone()
+        .map(|team| (team, "`bundle > iOS > developmentTeam`".into()))
+    })
+}
+
+/// Whether the installed provisioning profiles were already checked against the development team in this process.
+static TEAM_PROFILES_CHECKED: AtomicBool = AtomicBool::new(false);
+
+/// Whether the app is expected to be signed manually with an installed provisioning profile,
+/// either forced with `IOS_CODE_SIGN_STYLE` or by selecting a profile by name.
+fn manual_signing_expected(tauri_config: &TauriConfig) -> bool {
+  match std::env::var(CODE_SIGN_STYLE_ENV_VAR_NAME).as_deref() {
+    Ok("manual") => true,
+    Ok(_) => false,
+    Err(_) => {
+      std::env::var_os("IOS_PROVISIONING_PROFILE_NAME").is_some()
+        || tauri_config.bundle.ios.provisioning_profile_name.is_some()
+    }
   }
 }
 
-fn device_prompt<'a>(env: &'_ Env, target: Option<&str>) -> Result<Device<'a>> {
-  if let Ok(device) = connected_device_prompt(env, target) {
-    Ok(device)
-  } else {
-    let simulator = simulator_prompt(env, target)?;
-    log::info!("Starting simulator {}", simulator.name());
-    simulator.start_detached(env)?;
-    Ok(simulator.into())
+/// Warns once per process when no installed provisioning profile belongs to the development team,
+/// since manual signing cannot succeed in that case.
+fn warn_team_without_installed_profiles(team: &str) {
+  if TEAM_PROFILES_CHECKED.swap(true, Ordering::Relaxed) {
+    return;
+  }
+  let profiles = match tauri_macos_sign::ProvisioningProfile::installed() {
+    Ok(profiles) => profiles,
+    Err(e) => {
+      log::debug!("failed to list the installed provisioning profiles: {e:#}");
+      return;
+    }
+  };
+  // unreadable profiles are ignored, they cannot be used to sign the app either
+  let has_team_profile = profiles.iter().any(|profile| {
+    profile
+      .team_ids()
+      .is_ok_and(|teams| teams.iter().any(|t| t == team))
+  });
+  if !has_team_profile {
+    log::warn!("No installed provisioning profile belongs to the development team {team}, manual signing will fail. Download a provisioning profile of this team from your Apple Developer account, or set `{CODE_SIGN_STYLE_ENV_VAR_NAME}` to automatic to let Xcode manage the profiles.");
   }
 }
 
-fn detect_target_ok<'a>(env: &Env) -> Option<&'a Target<'a>> {
-  device_prompt(env, None).map(|device| device.target()).ok()
+/// Development teams of the build configurations that differ from `bundle > iOS > developmentTeam`.
+fn configuration_development_teams(tauri_config: &TauriConfig) -> HashMap<&'static str, String> {
+  [Profile::Debug, Profile::Release]
+    .into_iter()
+    .filter_map(|profile| {
+      let team = configured_development_team(tauri_config, Some(profile))?;
+      (Some(&team) != tauri_config.bundle.ios.development_team.as_ref())
+        .then_some((configuration_name(profile), team))
+    })
+    .collect()
 }
 
-fn open_and_wait(config: &AppleConfig, env: &Env) -> ! {
-  log::info!("Opening Xcode");
-  if let Err(e) = os::open_file_with("Xcode", config.project_dir(), env) {
-    log::error!("{}", e);
-  }
-  loop {
-    sleep(Duration::from_secs(24 * 60 * 60));
+const STRICT_FRAMEWORK_VERSIONS_ENV_VAR_NAME: &str = "TAURI_IOS_STRICT_FRAMEWORK_VERSIONS";
+
+/// Parses an Apple version like `13.0` or `16.4.1` into comparable components.
+fn parse_os_version(version: &str) -> Option<Vec<u64>> {
+  version
+    .trim()
+    .split('.')
+    .map(|c| c.parse().ok())
+    .collect::<Option<Vec<u64>>>()
+    .map(|mut components| {
+      // `13` and `13.0` are the same version
+      while components.len() > 1 && components.last() == Some(&0) {
+        components.pop();
+      }
+      components
+    })
+}
+
+/// The highest `MinimumOSVersion` declared by a vendor framework, or by the iOS slices of an XCFramework.
+fn framework_minimum_os_version(path: &Path) -> Option<String> {
+  let read = |info_plist: PathBuf| {
+    plist::Value::from_file(info_plist)
+      .ok()?
+      .as_dictionary()?
+      .get("MinimumOSVersion")?
+      .as_string()
+      .map(ToString::to_string)
+  };
+  match path.extension().and_then(|ext| ext.to_str()) {
+    Some("framework") => read(path.join("Info.plist")),
+    Some("xcframework") => std::fs::read_dir(path)
+      .ok()?
+      .flatten()
+      .filter(|slice| slice.file_name().to_string_lossy().starts_with("ios-"))
+      .flat_map(|slice| {
+        std::fs::read_dir(slice.path())
+          .into_iter()
+          .flatten()
+          .flatten()
+      })
+      .filter(|entry| {
+        entry
+          .path()
+          .extension()
+          .is_some_and(|ext| ext == "framework")
+      })
+      .filter_map(|framework| read(framework.path().join("Info.plist")))
+      .max_by_key(|version| parse_os_version(version)),
+    _ => None,
   }
 }
 
-fn inject_assets(config: &AppleConfig) -> Result<()> {
-  let asset_dir = config.project_dir().join(DEFAULT_ASSET_DIR);
-  create_dir_all(asset_dir)?;
-  Ok(())
+/// Warns when a vendor framework requires a newer iOS version than the deployment target of the project,
+/// since the app builds but crashes on the older devices. Fails instead when `TAURI_IOS_STRICT_FRAMEWORK_VERSIONS` is set.
+fn check_framework_minimum_os_version(
+  path: &Path,
+  framework: &str,
+  ios_version: &str,
+) -> Result<()> {
+  let Some(minimum) = framework_minimum_os_version(path) else {
+    return Ok(());
+  };
+  if parse_os_version(&minimum) <= parse_os_version(ios_version) {
+    return Ok(());
+  }
+  let message = format!(
+    "the {framework} framework requires iOS {minimum} but the app targets iOS {ios_version}, the app crashes on older devices"
+  );
+  if std::env::var(STRICT_FRAMEWORK_VERSIONS_ENV_VAR_NAME).is_ok_and(|v| v == "true" || v == "1") {
+    Err(anyhow::anyhow!(message).context(ErrorKind::InvalidConfig))
+  } else {
+    log::warn!(
+      "{message}. Set `{STRICT_FRAMEWORK_VERSIONS_ENV_VAR_NAME}` to fail the build instead."
+    );
+    Ok(())
+  }
 }
 
-enum PlistKind {
-  Path(PathBuf),
-  Plist(plist::Value),
+/// An entry of `bundle > iOS > frameworks`.
+enum Framework {
+  /// A system framework name, e.g. `CoreBluetooth`.
+  System(String),
+  /// A framework or library shipped with the app, relative to the Xcode project directory.
+  Vendor(String),
 }
 
-impl From<PathBuf> for PlistKind {
-  fn from(p: PathBuf) -> Self {
-    Self::Path(p)
+impl Framework {
+  fn resolve(framework: &str, tauri_dir: &Path, project_dir: &Path) -> Self {
+    let framework_path = PathBuf::from(framework);
+    let ext = framework_path.extension().unwrap_or_default();
+    if ext.is_empty() {
+      Self::System(framework.to_string())
+    } else if ext == "framework" && !tauri_dir.join(&framework_path).exists() {
+      Self::System(
+        framework_path
+          .file_stem()
+          .unwrap()
+          .to_string_lossy()
+          .to_string(),
+      )
+    } else {
+      Self::Vendor(
+        relativize_path(tauri_dir.join(framework_path), project_dir)
+          .to_string_lossy()
+          .to_string(),
+      )
+    }
   }
 }
-impl From<plist::Value> for PlistKind {
-  fn from(p: plist::Value) -> Self {
-    Self::Plist(p)
+
+/// The `bundle > iOS > frameworks` entries followed by the ones of the frameworks manifest.
+fn declared_frameworks(tauri_config: &TauriConfig, tauri_dir: &Path) -> Result<Vec<String>> {
+  let mut declared_frameworks = tauri_config
+    .bundle
+    .ios
+    .frameworks
+    .clone()
+    .unwrap_or_default();
+  for framework in frameworks_manifest::paths(tauri_dir)? {
+    if !declared_frameworks.contains(&framework) {
+      declared_frameworks.push(framework);
+    }
   }
+  Ok(declared_frameworks)
 }
 
-fn merge_plist(src: Vec<PlistKind>, dest: &Path) -> Result<()> {
-  let mut dest_plist = None;
+/// The frameworks excluded from the build configuration by `bundle > iOS > excludedFrameworks`.
+///
+/// Without a profile, e.g. when generating the Xcode project, the frameworks excluded from any configuration
+/// are returned since they are linked by the per-configuration build settings instead.
+fn excluded_frameworks(tauri_config: &TauriConfig, profile: Option<Profile>) -> Vec<String> {
+  let Some(excluded) = &tauri_config.bundle.ios.excluded_frameworks else {
+    return Vec::new();
+  };
+  excluded
+    .iter()
+    .filter(|(configuration, _)| {
+      profile.map_or(true, |profile| {
+        configuration.eq_ignore_ascii_case(configuration_name(profile))
+      })
+    })
+    .flat_map(|(_, frameworks)| frameworks.iter().cloned())
+    .collect()
+}
 
-  for plist_kind in src {
-    let plist = match plist_kind {
-      PlistKind::Path(p) => plist::Value::from_file(p),
-      PlistKind::Plist(v) => Ok(v),
-    };
-    if let Ok(src_plist) = plist {
-      if dest_plist.is_none() {
-        dest_plist.replace(plist::Value::from_file(dest)?);
-      }
+/// Build settings of the Xcode project that differ per build configuration, keyed by the configuration name.
+///
+/// Frameworks excluded from some configurations cannot be Xcode dependencies,
+/// so they are linked with the `OTHER_LDFLAGS` of the other configurations.
+pub fn configuration_settings(
+  config: &AppleConfig,
+  tauri_config: &TauriConfig,
+) -> Result<HashMap<&'static str, BTreeMap<&'static str, String>>> {
+  let mut settings: HashMap<&'static str, BTreeMap<&'static str, String>> = HashMap::new();
+
+  for (configuration, team) in configuration_development_teams(tauri_config) {
+    settings
+      .entry(configuration)
+      .or_default()
+      .insert("DEVELOPMENT_TEAM", team);
+  }
 
-      let plist = dest_plist.as_mut().expect("plist not loaded");
-      if let Some(plist) = plist.as_dictionary_mut() {
-        if let Some(dict) = src_plist.into_dictionary() {
-          for (key, value) in dict {
-            plist.insert(key, value);
+  let conditional_frameworks = excluded_frameworks(tauri_config, None);
+  if conditional_frameworks.is_empty() {
+    return Ok(settings);
+  }
+  let tauri_dir = tauri_dir();
+  let declared_frameworks = declared_frameworks(tauri_config, &tauri_dir)?;
+  for profile in [Profile::Debug, Profile::Release] {
+    let excluded = excluded_frameworks(tauri_config, Some(profile));
+    let mut linker_flags = Vec::new();
+    let mut search_paths = Vec::new();
+    for framework in declared_frameworks
+      .iter()
+      .filter(|f| conditional_frameworks.contains(f) && !excluded.contains(f))
+    {
+      match Framework::resolve(framework, &tauri_dir, &config.project_dir()) {
+        Framework::System(name) => linker_flags.push(format!("-framework {name}")),
+        Framework::Vendor(path) => {
+          let path = Path::new(&path);
+          match path.extension().and_then(|ext| ext.to_str()) {
+            Some("framework") => {
+              let dir = path.parent().unwrap_or_else(|| Path::new(""));
+              search_paths.push(format!("\"$(PROJECT_DIR)/{}\"", dir.display()));
+              linker_flags.push(format!(
+                "-framework {}",
+                path.file_stem().unwrap().to_string_lossy()
+              ));
+            }
+            Some("xcframework") => {
+              return Err(
+                anyhow::anyhow!(
+                  "{framework} cannot be excluded per configuration in `bundle > iOS > excludedFrameworks`, XCFrameworks must be linked in every configuration"
+                )
+                .context(ErrorKind::InvalidConfig),
+              );
+            }
+            _ => linker_flags.push(format!("\"$(PROJECT_DIR)/{}\"", path.display())),
           }
         }
       }
     }
+
+    let configuration_settings = settings.entry(configuration_name(profile)).or_default();
+    if !linker_flags.is_empty() {
+      configuration_settings.insert(
+        "OTHER_LDFLAGS",
+        format!("$(inherited) {}", linker_flags.join(" ")),
+      );
+    }
+    if !search_paths.is_empty() {
+      configuration_settings.insert(
+        "FRAMEWORK_SEARCH_PATHS",
+        format!("$(inherited) {}", search_paths.join(" ")),
+      );
+    }
   }
+  settings.retain(|_, settings| !settings.is_empty());
+
+  Ok(settings)
+}
+
+/// Converts the app version to the `CFBundleShortVersionString` and `CFBundleVersion` format,
+/// which only allows up to three period-separated integers.
+///
+/// Semver pre-release and build metadata (e.g. `1.2.0-beta.1`) are rejected by Apple, so they are stripped.
+fn apple_bundle_version(version: &str) -> Result<String> {
+  let release = version
+    .split_once(['-', '+'])
+    .map_or(version, |(release, _)| release);
 
-  if let Some(dest_plist) = dest_plist {
-    dest_plist.to_file_xml(dest)?;
+  let components = release.split('.').collect::<Vec<_>>();
+  if components.len() > 3
+    || components
+      .iter()
+      .any(|c| c.is_empty() || !c.chars().all(|c| c.is_ascii_digit()))
+  {
+    return Err(
+      anyhow::anyhow!(
+        "version {version} is not a valid iOS bundle version. It must be made of up to three period-separated integers, e.g. 1.2.0"
+      )
+      .context(ErrorKind::InvalidConfig),
+    );
   }
 
-  Ok(())
+  if release != version {
+    log::warn!(
+      "The version {version} contains a pre-release or build metadata which is not supported on iOS, using {release} as the bundle version"
+    );
+  }
+
+  Ok(release.to_string())
 }
 
-pub fn signing_from_env() -> Result<(
-  Option<tauri_macos_sign::Keychain>,
-  Option<tauri_macos_sign::ProvisioningProfile>,
-)> {
-  let keychain = if let (Some(certificate), Some(certificate_password)) = (
-    var_os("IOS_CERTIFICATE"),
-    var_os("IOS_CERTIFICATE_PASSWORD"),
-  ) {
-    tauri_macos_sign::Keychain::with_certificate(&certificate, &certificate_password).map(Some)?
-  } else {
-    None
-  };
-  let provisioning_profile = if let Some(provisioning_profile) = var_os("IOS_MOBILE_PROVISION") {
-    tauri_macos_sign::ProvisioningProfile::from_base64(&provisioning_profile).map(Some)?
-  } else {
-    None
+/// Environment variables describing the Xcode project, exposed to the Xcode and Rust build processes.
+pub fn project_env_vars(config: &AppleConfig) -> HashMap<String, OsString> {
+  let mut vars = HashMap::new();
+  vars.insert(
+    "TAURI_IOS_PROJECT_PATH".into(),
+    config.project_dir().into_os_string(),
+  );
+  vars.insert("TAURI_IOS_APP_NAME".into(), config.app().name().into());
+  vars
+}
+
+/// Resolves the `ENABLE_BITCODE` build setting from the `bundle > iOS > enableBitcode` configuration.
+pub fn enable_bitcode(tauri_config: &TauriConfig) -> bool {
+  let enabled = tauri_config.bundle.ios.enable_bitcode.unwrap_or(false);
+  if enabled {
+    log::warn!("Bitcode is deprecated since Xcode 14 and the App Store no longer accepts it, only enable `bundle > iOS > enableBitcode` for legacy distribution flows that require it");
+  }
+  enabled
+}
+
+/// Resolves the `TARGETED_DEVICE_FAMILY` build setting from the `bundle > iOS > deviceFamilies` configuration.
+pub fn targeted_device_family(tauri_config: &TauriConfig) -> Result<String> {
+  let families = match &tauri_config.bundle.ios.device_families {
+    Some(families) if families.is_empty() => {
+      return Err(
+        anyhow::anyhow!("`bundle > iOS > deviceFamilies` must include at least one device family")
+          .context(ErrorKind::InvalidConfig),
+      );
+    }
+    Some(families) => families.clone(),
+    None => vec![IosDeviceFamily::Iphone, IosDeviceFamily::Ipad],
   };
 
-  Ok((keychain, provisioning_profile))
+  let mut ids = families
+    .iter()
+    .map(|family| match family {
+      IosDeviceFamily::Iphone => 1,
+      IosDeviceFamily::Ipad => 2,
+    })
+    .collect::<Vec<u8>>();
+  ids.sort_unstable();
+  ids.dedup();
+
+  Ok(
+    ids
+      .iter()
+      .map(ToString::to_string)
+      .collect::<Vec<_>>()
+      .join(","),
+  )
 }
 
+/// Services allowed as the prefix of the `bundle > iOS > associatedDomains` entries.
+const ASSOCIATED_DOMAIN_SERVICES: &[&str] = &[
+  "applinks",
+  "webcredentials",
+  "activitycontinuation",
+  "appclips",
+];
+
+/// Merges the `--bundle-id-suffix` flag into the `--config` value, overriding `bundle > iOS > bundleIdSuffix`.
+fn merge_bundle_id_suffix(config: &mut Option<ConfigValue>, suffix: &str) {
+  let patch = serde_json::json!({ "bundle": { "iOS": { "bundleIdSuffix": suffix } } });
+  match config {
+    Some(config) => json_patch::merge(&mut config.0, &patch),
+    None => *config = Some(ConfigValue(patch)),
+  }
+}
+
+/// The bundle identifier of the iOS app, the `identifier` followed by the `bundle > iOS > bundleIdSuffix` if any.
+pub fn bundle_identifier(tauri_config: &TauriConfig) -> String {
+  format!(
+    "{}{}",
+    tauri_config.identifier,
+    tauri_config
+      .bundle
+      .ios
+      .bundle_id_suffix
+      .as_deref()
+      .unwrap_or_default()
+  )
+}
+
+/// Checks that the suffixed bundle identifier is a valid iOS bundle identifier.
+fn validate_bundle_identifier(tauri_config: &TauriConfig) -> Result<()> {
+  let identifier = bundle_identifier(tauri_config);
+  if identifier == tauri_config.identifier {
+    return Ok(());
+  }
+  let valid = identifier.split('.').all(|segment| {
+    !segment.is_empty()
+      && segment
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-')
+  });
+  if valid {
+    Ok(())
+  } else {
+    Err(
+      anyhow::anyhow!(
+        "the bundle identifier `{identifier}` built with the bundle identifier suffix is invalid: it can only contain dot-separated segments of ASCII letters, digits and `-`"
+      )
+      .context(ErrorKind::InvalidConfig),
+    )
+  }
+}
+
+/// The build setting applying the bundle identifier suffix, since the Xcode project uses the `identifier`.
+fn bundle_identifier_build_setting(tauri_config: &TauriConfig) -> Option<(String, String)> {
+  let identifier = bundle_identifier(tauri_config);
+  (identifier != tauri_config.identifier)
+    .then(|| ("PRODUCT_BUNDLE_IDENTIFIER".to_string(), identifier))
+}
+
+/// Resolves the symbol stripping build settings from the `bundle > iOS > stripSymbols` configuration.
+/// Debug builds, and release builds without the configuration, keep the Xcode defaults.
+fn strip_symbols_build_settings(
+  tauri_config: &TauriConfig,
+  profile: Profile,
+) -> Vec<(String, String)> {
+  let (Some(strip), Profile::Release) = (tauri_config.bundle.ios.strip_symbols, profile) else {
+    return Vec::new();
+  };
+  let value = if strip { "YES" } else { "NO" };
+  vec![
+    ("STRIP_INSTALLED_PRODUCT".into(), value.into()),
+    ("DEPLOYMENT_POSTPROCESSING".into(), value.into()),
+    // the dSYM is extracted before the binary is stripped, so crash reports can still be symbolicated
+    ("DEBUG_INFORMATION_FORMAT".into(), "dwarf-with-dsym".into()),
+  ]
+}
+
+/// Resolves the Metal shader compiler build settings from the `bundle > iOS > metal` configuration,
+/// failing on unknown values. Options that are not set keep the Xcode defaults.
+fn metal_build_settings(tauri_config: &TauriConfig) -> Result<Vec<(String, String)>> {
+  let Some(metal) = &tauri_config.bundle.ios.metal else {
+    return Ok(Vec::new());
+  };
+  let mut settings = Vec::new();
+  if let Some(level) = &metal.optimization_level {
+    let value = match level.as_str() {
+      "default" => "default",
+      "none" => "0",
+      "size" => "s",
+      _ => {
+        return Err(
+          anyhow::anyhow!(
+            "`{level}` in `bundle > iOS > metal > optimizationLevel` is not a valid optimization level, expected `default`, `none` or `size`"
+          )
+          .context(ErrorKind::InvalidConfig),
+        )
+      }
+    };
+    settings.push(("MTL_OPTIMIZATION_LEVEL".into(), value.into()));
+  }
+  if let Some(debug_info) = &metal.debug_info {
+    let value = match debug_info.as_str() {
+      "none" => "NO",
+      "symbols" => "YES",
+      "source" => "INCLUDE_SOURCE",
+      _ => {
+        return Err(
+          anyhow::anyhow!(
+            "`{debug_info}` in `bundle > iOS > metal > debugInfo` is not a valid debug information option, expected `none`, `symbols` or `source`"
+          )
+          .context(ErrorKind::InvalidConfig),
+        )
+      }
+    };
+    settings.push(("MTL_ENABLE_DEBUG_INFO".into(), value.into()));
+  }
+  Ok(settings)
+}
+
+/// Validates the `bundle > iOS > urlSchemes` and `bundle > iOS > associatedDomains` configuration.
+fn validate_deep_links(tauri_config: &TauriConfig) -> Result<()> {
+  let ios = &tauri_config.bundle.ios;
+
+  for scheme in ios.url_schemes.as_deref().unwrap_or_default() {
+    // RFC 3986: ALPHA *( ALPHA / DIGIT / "+" / "-" / "." )
+    let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+      && scheme
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if !valid {
+      return Err(
+        anyhow::anyhow!(
+          "`{scheme}` in `bundle > iOS > urlSchemes` is not a valid URL scheme. It must start with a letter and only contain letters, digits, `+`, `-` and `.`, without the `://` suffix"
+        )
+        .context(ErrorKind::InvalidConfig),
+      );
+    }
+  }
+
+  for entry in ios.associated_domains.as_deref().unwrap_or_default() {
+    let domain = entry.split_once(':').and_then(|(service, domain)| {
+      ASSOCIATED_DOMAIN_SERVICES
+        .contains(&service)
+        .then_some(domain)
+    });
+    let Some(domain) = domain else {
+      return Err(
+        anyhow::anyhow!(
+          "`{entry}` in `bundle > iOS > associatedDomains` must be prefixed with one of the {} services, e.g. `applinks:example.com`",
+          ASSOCIATED_DOMAIN_SERVICES.join(", ")
+        )
+        .context(ErrorKind::InvalidConfig),
+      );
+    };
+    // the domain can be followed by `?mode=developer` or `?mode=managed`
+    let host = domain.split_once('?').map_or(domain, |(host, _)| host);
+    let host = host.strip_prefix("*.").unwrap_or(host);
+    let valid = !host.is_empty()
+      && host
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.'))
+      && !host.starts_with(['.', '-']);
+    if !valid {
+      return Err(
+        anyhow::anyhow!(
+          "`{entry}` in `bundle > iOS > associatedDomains` does not contain a valid domain. Use the domain name only, without the `https://` scheme or a path"
+        )
+        .context(ErrorKind::InvalidConfig),
+      );
+    }
+  }
+
+  Ok(())
+}
+
+/// The `CFBundleURLTypes` Info.plist entry of the `bundle > iOS > urlSchemes` configuration.
+fn url_types_plist(tauri_config: &TauriConfig) -> Option<plist::Value> {
+  let schemes = tauri_config.bundle.ios.url_schemes.as_ref()?;
+  if schemes.is_empty() {
+    return None;
+  }
+  let mut url_type = plist::Dictionary::new();
+  url_type.insert(
+    "CFBundleURLName".into(),
+    bundle_identifier(tauri_config).into(),
+  );
+  url_type.insert(
+    "CFBundleURLSchemes".into(),
+    schemes
+      .iter()
+      .map(|scheme| plist::Value::from(scheme.clone()))
+      .collect::<Vec<_>>()
+      .into(),
+  );
+  let mut plist = plist::Dictionary::new();
+  plist.insert(
+    "CFBundleURLTypes".into(),
+    vec![plist::Value::from(url_type)].into(),
+  );
+  Some(plist.into())
+}
+
+/// Adds the `bundle > iOS > associatedDomains` configuration to the entitlements of the Xcode project.
+fn merge_associated_domains(config: &AppleConfig, tauri_config: &TauriConfig) -> Result<()> {
+  let Some(domains) = &tauri_config.bundle.ios.associated_domains else {
+    return Ok(());
+  };
+  let mut entitlements = plist::Dictionary::new();
+  entitlements.insert(
+    "com.apple.developer.associated-domains".into(),
+    domains
+      .iter()
+      .map(|domain| plist::Value::from(domain.clone()))
+      .collect::<Vec<_>>()
+      .into(),
+  );
+  merge_plist(
+    vec![plist::Value::from(entitlements).into()],
+    &app_entitlements_path(config),
+  )
+}
+
+/// The entitlements file of the app target generated with the Xcode project.
+fn app_entitlements_path(config: &AppleConfig) -> PathBuf {
+  config
+    .project_dir()
+    .join(config.scheme())
+    .join(format!("{}.entitlements", config.scheme()))
+}
+
+/// Reads the iOS version of the connected devices, keyed by UDID.
+fn connected_device_os_versions() -> Result<HashMap<String, semver::Version>> {
+  let output_path =
+    std::env::temp_dir().join(format!("tauri-ios-devices-{}.json", std::process::id()));
+  Command::new("xcrun")
+    .args(["devicectl", "list", "devices", "--json-output"])
+    .arg(&output_path)
+    .output_ok()
+    .context("failed to read the OS version of the connected devices with `xcrun devicectl`, which requires Xcode 15 or newer")
+    .context(ErrorKind::XcodeMissing)?;
+  let output = std::fs::read(&output_path);
+  let _ = std::fs::remove_file(&output_path);
+  let output: serde_json::Value = serde_json::from_slice(&output?)?;
+
+  let mut versions = HashMap::new();
+  for device in output["result"]["devices"].as_array().into_iter().flatten() {
+    let udid = device["hardwareProperties"]["udid"].as_str();
+    let version = device["deviceProperties"]["osVersionNumber"].as_str();
+    if let (Some(udid), Some(version)) = (udid, version) {
+      // iOS versions omit the trailing zero components, e.g. `17.5`
+      let mut components = version.split('.').collect::<Vec<_>>();
+      components.resize(3.max(components.len()), "0");
+      if let Ok(version) = semver::Version::parse(&components.join(".")) {
+        versions.insert(udid.to_string(), version);
+      }
+    }
+  }
+  Ok(versions)
+}
+
+/// How often the connected devices are listed while waiting for a device to be plugged in.
+const DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The outcome of looking for a connected device, so callers can tell an empty list from a failure to list the devices.
+enum ConnectedDevice<'a> {
+  Found(Device<'a>),
+  /// No iOS device is connected.
+  NoneConnected,
+  /// Devices are connected but none matches the requested name or UDID.
+  NoMatch,
+  /// Several devices match the requested name equally well, e.g. two devices of the same model,
+  /// and prompting to pick one is disabled with the `--ci` option.
+  Ambiguous(Vec<Device<'a>>),
+}
+
+/// Selects a connected device by name or UDID, by index or with a prompt.
+///
+/// The devices are sorted by name, then UDID, so `device_index` and the prompt use the same stable,
+/// zero-based order regardless of the order the devices are detected in.
+/// When several devices match the name equally well, e.g. two devices of the same model, the user picks one of them,
+/// unless `ci` disables the prompts.
+///
+/// Errors when the devices cannot be listed or the selection fails, not when no device is connected.
+fn connected_device_prompt<'a>(
+  env: &'_ Env,
+  target: Option<&str>,
+  device_index: Option<usize>,
+  os_version: Option<&semver::VersionReq>,
+  wait_for_device: Option<Duration>,
+  ci: bool,
+) -> Result<ConnectedDevice<'a>> {
+  let list_devices = || {
+    device::list_devices(env)
+      .map_err(|cause| anyhow::anyhow!("Failed to detect connected iOS devices: {cause}"))
+  };
+  let mut device_list = list_devices()?;
+  if let Some(timeout) = wait_for_device {
+    if device_list.is_empty() {
+      log::info!(action = "Waiting"; "for an iOS device to be connected (timeout: {}s)", timeout.as_secs());
+      let start = Instant::now();
+      while device_list.is_empty() && start.elapsed() < timeout {
+        sleep(DEVICE_POLL_INTERVAL);
+        device_list = list_devices()?;
+      }
+    }
+  }
+  if let Some(os_version) = os_version {
+    if !device_list.is_empty() {
+      let versions = connected_device_os_versions()?;
+      device_list.retain(|d| versions.get(d.id()).is_some_and(|v| os_version.matches(v)));
+      if device_list.is_empty() {
+        return Err(
+          anyhow::anyhow!("No connected iOS device runs an iOS version matching {os_version}")
+            .context(ErrorKind::NoDevice),
+        );
+      }
+    }
+  }
+  device_list.sort_by(|a, b| a.name().cmp(b.name()).then_with(|| a.id().cmp(b.id())));
+  if !device_list.is_empty() {
+    let device = if let Some(index) = device_index {
+      let count = device_list.len();
+      device_list.into_iter().nth(index).ok_or_else(|| {
+        anyhow::anyhow!(
+          "Device index {index} is out of range, {count} iOS device{} connected",
+          if count == 1 { " is" } else { "s are" }
+        )
+        .context(ErrorKind::NoDevice)
+      })?
+    } else if let Some(t) = target {
+      let mut scored = device_list
+        .into_iter()
+        .map(|d| {
+          // an exact UDID match always wins over a fuzzy name match
+          let score = if d.id() == t {
+            isize::MAX
+          } else {
+            best_match(t, d.name()).map_or(0, |m| m.score())
+          };
+          (d, score)
+        })
+        .collect::<Vec<_>>();
+      // we already checked the list is not empty
+      let best_score = scored.iter().map(|(_, score)| *score).max().unwrap();
+      if best_score <= min_device_match_score() {
+        return Ok(ConnectedDevice::NoMatch);
+      }
+      scored.retain(|(_, score)| *score == best_score);
+      let mut candidates = scored.into_iter().map(|(d, _)| d).collect::<Vec<_>>();
+      if candidates.len() == 1 {
+        candidates.remove(0)
+      } else if ci {
+        return Ok(ConnectedDevice::Ambiguous(candidates));
+      } else {
+        let index = prompt::list(
+          format!("Several iOS devices match {t}"),
+          candidates.iter().map(|d| format!("{d} ({})", d.id())),
+          "device",
+          None,
+          "Device",
+        )
+        .map_err(|cause| anyhow::anyhow!("Failed to prompt for iOS device: {cause}"))?;
+        candidates.into_iter().nth(index).unwrap()
+      }
+    } else {
+      let index = if device_list.len() > 1 {
+        prompt::list(
+          concat!("Detected ", "iOS", " devices"),
+          device_list.iter(),
+          "device",
+          None,
+          "Device",
+        )
+        .map_err(|cause| anyhow::anyhow!("Failed to prompt for iOS device: {cause}"))?
+      } else {
+        0
+      };
+      device_list.into_iter().nth(index).unwrap()
+    };
+    println!(
+      "Detected connected device: {} with target {:?}",
+      device,
+      device.target().triple,
+    );
+    Ok(ConnectedDevice::Found(device))
+  } else {
+    Ok(ConnectedDevice::NoneConnected)
+  }
+}
+
+fn simulator_prompt(
+  target: Option<&str>,
+  runtime: Option<&str>,
+  refresh_list: bool,
+  create_missing: bool,
+) -> Result<simctl::Simulator> {
+  let mut simulator_list =
+    simctl::list_cached(&xcode_developer_dir()?, refresh_list).map_err(|cause| {
+      anyhow::anyhow!("Failed to detect connected iOS Simulator devices: {cause}")
+    })?;
+
+  if let Some(runtime) = runtime {
+    let all_simulators = simulator_list.clone();
+    simulator_list.retain(|s| {
+      s.runtime_name()
+        .is_some_and(|name| simctl::runtime_matches(&name, runtime))
+    });
+    if let Some(t) = target {
+      if !simulator_list
+        .iter()
+        .any(|s| s.name.eq_ignore_ascii_case(t))
+      {
+        return create_simulator(t, Some(runtime), &all_simulators, create_missing);
+      }
+      simulator_list.retain(|s| s.name.eq_ignore_ascii_case(t));
+    }
+  } else if let Some(t) = target.filter(|_| create_missing) {
+    if !simulator_list
+      .iter()
+      .any(|s| s.name.eq_ignore_ascii_case(t))
+    {
+      return create_simulator(t, None, &simulator_list, true);
+    }
+  }
+
+  if !simulator_list.is_empty() {
+    let device = if let Some(t) = target {
+      let (device, score) = simulator_list
+        .into_iter()
+        .rev()
+        .map(|d| {
+          let score = best_match(t, &d.name).map_or(0, |m| m.score());
+          (d, score)
+        })
+        .max_by_key(|(_, score)| *score)
+        // we already checked the list is not empty
+        .unwrap();
+      if score > min_device_match_score() {
+        device
+      } else {
+        return Err(
+          anyhow::anyhow!("Could not find an iOS Simulator matching {t}")
+            .context(ErrorKind::NoDevice),
+        );
+      }
+    } else if simulator_list.len() > 1 {
+      let index = prompt::list(
+        concat!("Detected ", "iOS", " simulators"),
+        simulator_list.iter(),
+        "simulator",
+        None,
+        "Simulator",
+      )
+      .map_err(|cause| anyhow::anyhow!("Failed to prompt for iOS Simulator device: {cause}"))?;
+      simulator_list.into_iter().nth(index).unwrap()
+    } else {
+      simulator_list.into_iter().next().unwrap()
+    };
+    Ok(device)
+  } else if let Some(runtime) = runtime {
+    Err(
+      anyhow::anyhow!("No available iOS Simulator runs the {runtime} runtime")
+        .context(ErrorKind::NoDevice),
+    )
+  } else {
+    Err(anyhow::anyhow!("No available iOS Simulator detected").context(ErrorKind::NoDevice))
+  }
+}
+
+/// Creates the simulator `name` for the runtime, or the latest installed runtime.
+///
+/// The device type is read from the simulators with the same name on other runtimes,
+/// or matched against the simctl device types when `create_missing` is set.
+/// Only the simulators created with `create_missing` are recorded for `ios simulators clean`.
+fn create_simulator(
+  name: &str,
+  runtime: Option<&str>,
+  simulators: &[simctl::Simulator],
+  create_missing: bool,
+) -> Result<simctl::Simulator> {
+  let mut device_type = simulators
+    .iter()
+    .find(|s| s.name.eq_ignore_ascii_case(name) && !s.device_type_identifier.is_empty())
+    .map(|s| s.device_type_identifier.clone());
+  if device_type.is_none() && create_missing {
+    let device_types = simctl::device_types()?;
+    device_type = device_types
+      .iter()
+      .find(|t| t.name.eq_ignore_ascii_case(name) || t.identifier == name)
+      .map(|t| t.identifier.clone());
+    if device_type.is_none() {
+      return Err(
+        anyhow::anyhow!(
+          "Unknown iOS Simulator device type {name}, available device types: {}",
+          device_types
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+        )
+        .context(ErrorKind::NoDevice),
+      );
+    }
+  }
+  let installed_runtime = match runtime {
+    Some(runtime) => simctl::runtimes()?
+      .into_iter()
+      .find(|r| simctl::runtime_matches(&r.name, runtime)),
+    None => simctl::latest_runtime()?,
+  };
+  let runtime = runtime.unwrap_or("the latest iOS runtime");
+
+  match (device_type, installed_runtime) {
+    (Some(device_type), Some(installed_runtime)) => {
+      log::info!(action = "Creating"; "simulator {name} ({})", installed_runtime.name);
+      let udid = simctl::create(name, &device_type, &installed_runtime, create_missing)?;
+      // refresh the cached list so the next run reuses the new simulator
+      simctl::list_cached(&xcode_developer_dir()?, true)?;
+      simctl::find_udid(&udid)
+    }
+    _ => {
+      let pairings = simulators
+        .iter()
+        .filter(|s| {
+          s.name.eq_ignore_ascii_case(name)
+            || s
+              .runtime_name()
+              .is_some_and(|r| simctl::runtime_matches(&r, runtime))
+        })
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+      let message = if pairings.is_empty() {
+        format!("Could not find or create an iOS Simulator {name} running {runtime}")
+      } else {
+        format!(
+          "Could not find or create an iOS Simulator {name} running {runtime}. Available simulators: {}",
+          pairings.join(", ")
+        )
+      };
+      Err(anyhow::anyhow!(message).context(ErrorKind::NoDevice))
+    }
+  }
+}
+
+/// Finds the booted simulator, like the `booted` shorthand of simctl, failing if none or several are booted.
+fn booted_simulator() -> Result<simctl::Simulator> {
+  // the simulator states of the cached list are outdated
+  let mut booted = simctl::list()?
+    .into_iter()
+    .filter(|simulator| simulator.is_booted())
+    .collect::<Vec<_>>();
+  match booted.len() {
+    0 => Err(anyhow::anyhow!("No booted iOS Simulator detected").context(ErrorKind::NoDevice)),
+    1 => Ok(booted.remove(0)),
+    _ => Err(
+      anyhow::anyhow!(
+        "{} iOS Simulators are booted ({}), select one by name instead of using --booted",
+        booted.len(),
+        booted
+          .iter()
+          .map(ToString::to_string)
+          .collect::<Vec<_>>()
+          .join(", ")
+      )
+      .context(ErrorKind::NoDevice),
+    ),
+  }
+}
+
+/// Options applied to the simulator selected by [`device_prompt`].
+#[derive(Debug, Default, Clone)]
+struct SimulatorOptions {
+  /// The system appearance to set once the simulator is booted.
+  appearance: Option<simctl::Appearance>,
+  /// The system language to set once the simulator is booted, e.g. `fr` or `pt-BR`.
+  language: Option<String>,
+  /// The system region to set once the simulator is booted, e.g. `CA`.
+  region: Option<String>,
+  /// Erase the simulator content and settings before booting it.
+  reset: bool,
+  /// Query the available simulators instead of using the cached list.
+  refresh_list: bool,
+  /// Only select a simulator running this runtime, e.g. `iOS 16.4`, skipping the connected devices.
+  runtime: Option<String>,
+  /// Select the single booted simulator without prompting, skipping the connected devices.
+  booted: bool,
+  /// Create the simulator named by the device argument when it does not exist.
+  create: bool,
+  /// Override the status bar with full battery and signal, showing this time, once the simulator is booted.
+  clean_status_bar_time: Option<String>,
+  /// Select the simulator without booting it, e.g. to read the files it keeps on the host.
+  /// The options applied to a booted simulator are ignored.
+  skip_boot: bool,
+}
+
+/// Selects a connected device, falling back to a simulator.
+///
+/// When `os_version` is set only connected devices running a matching iOS version are considered.
+fn device_prompt<'a>(
+  env: &'_ Env,
+  target: Option<&str>,
+  device_index: Option<usize>,
+  os_version: Option<&semver::VersionReq>,
+  wait_for_device: Option<Duration>,
+  simulator_options: SimulatorOptions,
+  ci: bool,
+) -> Result<Device<'a>> {
+  // a runtime or the booted simulator can only be selected on simulators
+  if simulator_options.runtime.is_none() && !simulator_options.booted {
+    // simulators do not satisfy an OS version requirement, a device index or a request to wait for a physical device
+    let requires_device =
+      device_index.is_some() || os_version.is_some() || wait_for_device.is_some();
+    match connected_device_prompt(env, target, device_index, os_version, wait_for_device, ci) {
+      Ok(ConnectedDevice::Found(device)) => {
+        device_tools::check(false)?;
+        return Ok(device);
+      }
+      Ok(ConnectedDevice::NoneConnected) if requires_device => {
+        return Err(
+          anyhow::anyhow!("No connected iOS devices detected").context(ErrorKind::NoDevice),
+        );
+      }
+      Ok(ConnectedDevice::NoMatch) if requires_device => {
+        return Err(
+          anyhow::anyhow!(
+            "Could not find an iOS device matching {}",
+            target.unwrap_or_default()
+          )
+          .context(ErrorKind::NoDevice),
+        );
+      }
+      // the devices match the name, so falling back to a simulator would not be what the user asked for
+      Ok(ConnectedDevice::Ambiguous(candidates)) => {
+        return Err(
+          anyhow::anyhow!(
+            "Several iOS devices match {}:\n{}\nPass the UDID of the device instead of its name",
+            target.unwrap_or_default(),
+            candidates
+              .iter()
+              .map(|d| format!("  - {d} ({})", d.id()))
+              .collect::<Vec<_>>()
+              .join("\n")
+          )
+          .context(ErrorKind::NoDevice),
+        );
+      }
+      Err(e) if requires_device => return Err(e),
+      Err(e) => log::warn!("{e:#}, looking for a simulator instead"),
+      Ok(ConnectedDevice::NoneConnected | ConnectedDevice::NoMatch) => {}
+    }
+  }
+  device_tools::check(true)?;
+
+  let selected = if simulator_options.booted {
+    booted_simulator()?
+  } else {
+    simulator_prompt(
+      target,
+      simulator_options.runtime.as_deref(),
+      simulator_options.refresh_list,
+      simulator_options.create,
+    )?
+  };
+  let simulator = selected.to_device()?;
+  if simulator_options.skip_boot {
+    return Ok(simulator.into());
+  }
+  // the selected simulator may come from the cached list, read its current state
+  let mut state = simctl::find_udid(&selected.udid).ok();
+
+  if simulator_options.reset {
+    let sim = match state {
+      Some(s) => s,
+      None => simctl::find_udid(&selected.udid)?,
+    };
+    if sim.is_booted() {
+      log::info!("Shutting down simulator {selected}");
+      simctl::shutdown(&sim)?;
+    }
+    simctl::erase(&sim)?;
+    log::warn!("Erased all content and settings of simulator {selected}");
+    state = None;
+  }
+
+  if state.as_ref().is_some_and(|s| s.is_booted()) {
+    log::info!("Reusing running simulator {selected}");
+  } else {
+    warn_low_disk_space();
+    log::info!("Starting simulator {selected}");
+    simulator.start_detached(env)?;
+  }
+
+  if let Some(appearance) = simulator_options.appearance {
+    let booted = match state {
+      Some(s) => s,
+      None => simctl::find_udid(&selected.udid)?,
+    };
+    simctl::wait_booted(&booted)?;
+    log::info!("Setting simulator appearance to {appearance}");
+    simctl::set_appearance(&booted, appearance)?;
+  }
+
+  if simulator_options.language.is_some() || simulator_options.region.is_some() {
+    let booted = simctl::find_udid(&selected.udid)?;
+    simctl::wait_booted(&booted)?;
+    log::info!(
+      "Setting simulator locale to {}",
+      [
+        simulator_options.language.as_deref(),
+        simulator_options.region.as_deref()
+      ]
+      .into_iter()
+      .flatten()
+      .collect::<Vec<_>>()
+      .join(", ")
+    );
+    simctl::set_locale(
+      &booted,
+      simulator_options.language.as_deref(),
+      simulator_options.region.as_deref(),
+    )?;
+  }
+
+  if let Some(time) = &simulator_options.clean_status_bar_time {
+    let booted = simctl::find_udid(&selected.udid)?;
+    simctl::wait_booted(&booted)?;
+    log::info!("Overriding the simulator status bar, showing {time}");
+    simctl::override_status_bar(&booted, time)?;
+  }
+
+  Ok(simulator.into())
+}
+
+fn detect_target_ok<'a>(env: &Env) -> Option<&'a Target<'a>> {
+  // the target detection must not prompt
+  device_prompt(env, None, None, None, None, Default::default(), true)
+    .map(|device| device.target())
+    .ok()
+}
+
+/// Whether the Rust target triple is an iOS simulator target.
+fn is_simulator_triple(triple: &str) -> bool {
+  triple.ends_with("-sim") || triple.starts_with("x86_64")
+}
+
+/// Build settings disabling code signing, passed to xcodebuild as environment variables.
+///
+/// Xcode reads build settings that are not defined in the project from the environment.
+const CODE_SIGNING_DISABLED_ENV_VARS: &[(&str, &str)] = &[
+  ("CODE_SIGNING_ALLOWED", "NO"),
+  ("CODE_SIGNING_REQUIRED", "NO"),
+];
+
+/// Disables code signing on the Xcode build, which is only supported on simulators.
+fn disable_code_signing(env: &mut Env) {
+  for (key, value) in CODE_SIGNING_DISABLED_ENV_VARS {
+    env.insert_env_var((*key).into(), (*value).into());
+  }
+}
+
+/// Parses a `--build-setting` `KEY=VALUE` pair. The key may have conditions, e.g. `OTHER_LDFLAGS[sdk=iphoneos*]`.
+pub fn parse_build_setting(s: &str) -> std::result::Result<(String, String), String> {
+  let (key, value) = match s.split_once('=') {
+    // the conditions contain `=`, so the key ends at the first `=` after a `]`
+    Some((name, _)) if name.contains('[') => s
+      .split_once("]=")
+      .map(|(key, value)| (format!("{}]", key.trim()), value.to_string()))
+      .ok_or_else(|| {
+        format!("invalid build setting `{s}`: the conditions must be closed with `]`")
+      })?,
+    _ => build_env::parse_key_value(s)?,
+  };
+  let (name, conditions) = key.split_once('[').unwrap_or((&key, ""));
+  let valid_name = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+  // conditions can be chained, e.g. `[sdk=iphoneos*][arch=arm64]`
+  let valid_conditions = conditions.is_empty()
+    || conditions.strip_suffix(']').is_some_and(|conditions| {
+      conditions.split("][").all(|condition| {
+        condition
+          .split_once('=')
+          .is_some_and(|(k, v)| !k.is_empty() && !v.is_empty())
+      })
+    });
+  if !valid_name || !valid_conditions {
+    return Err(format!(
+      "invalid build setting `{key}`: expected a name like `SWIFT_VERSION` or `OTHER_LDFLAGS[sdk=iphoneos*]`"
+    ));
+  }
+  if value.contains('\n') {
+    return Err(format!(
+      "invalid build setting `{key}`: the value cannot contain line breaks"
+    ));
+  }
+  Ok((key, value))
+}
+
+/// Path of the xcconfig file holding the `--build-setting` overrides.
+fn build_settings_xcconfig_path(config: &AppleConfig) -> PathBuf {
+  config
+    .project_dir()
+    .join("build")
+    .join("build-settings.xcconfig")
+}
+
+/// Reads the path of the app bundle built by Xcode from the project build settings.
+fn built_app_path(config: &AppleConfig, sdk: &str, profile: Profile) -> Result<PathBuf> {
+  let project_dir = config.project_dir();
+  let project = find_xcode_file(&project_dir, "xcodeproj").ok_or_else(|| {
+    anyhow::anyhow!(
+      "could not find the Xcode project in {}",
+      project_dir.display()
+    )
+    .context(ErrorKind::InvalidConfig)
+  })?;
+  let configuration = configuration_name(profile);
+
+  let mut command = Command::new("xcodebuild");
+  // the `--build-setting` and `--derived-data` overrides change where the app is built
+  let xcconfig = build_settings_xcconfig_path(config);
+  if xcconfig.exists() {
+    command.env("XCODE_XCCONFIG_FILE", xcconfig);
+  }
+  let output = command
+    .arg("-project")
+    .arg(&project)
+    .arg("-scheme")
+    .arg(config.scheme())
+    .args(["-sdk", sdk, "-configuration", configuration])
+    .args(["-showBuildSettings", "-json"])
+    .output_ok()
+    .context("failed to read the Xcode build settings")?;
+  let settings: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+  let build_settings = &settings[0]["buildSettings"];
+  match (
+    build_settings["TARGET_BUILD_DIR"].as_str(),
+    build_settings["WRAPPER_NAME"].as_str(),
+  ) {
+    (Some(dir), Some(wrapper)) => Ok(PathBuf::from(dir).join(wrapper)),
+    _ => Err(anyhow::anyhow!(
+      "could not find the built app in the Xcode build settings"
+    )),
+  }
+}
+
+/// The profiles cargo always defines.
+const BUILTIN_CARGO_PROFILES: &[&str] = &["dev", "release", "test", "bench"];
+
+/// The cargo arguments building the Rust library with the `--cargo-profile`,
+/// which must be a built-in profile or be defined in the Cargo manifest of the app or its workspace.
+fn cargo_profile_args(profile: Option<&str>) -> Result<Vec<String>> {
+  let Some(profile) = profile else {
+    return Ok(Vec::new());
+  };
+  if !BUILTIN_CARGO_PROFILES.contains(&profile) {
+    let tauri_dir = tauri_dir();
+    let mut defined = false;
+    for manifest_path in tauri_dir
+      .ancestors()
+      .map(|dir| dir.join("Cargo.toml"))
+      .filter(|path| path.exists())
+    {
+      let manifest: toml::Value = std::fs::read_to_string(&manifest_path)
+        .ok()
+        .and_then(|manifest| toml::from_str(&manifest).ok())
+        .unwrap_or_else(|| toml::Value::Table(Default::default()));
+      if manifest
+        .get("profile")
+        .and_then(|profiles| profiles.get(profile))
+        .is_some()
+      {
+        defined = true;
+        break;
+      }
+      // profiles are only read from the workspace root
+      if manifest.get("workspace").is_some() {
+        break;
+      }
+    }
+    if !defined {
+      return Err(
+        anyhow::anyhow!(
+          "the cargo profile `{profile}` is not defined, add a `[profile.{profile}]` table to the Cargo.toml of the app or its workspace"
+        )
+        .context(ErrorKind::InvalidConfig),
+      );
+    }
+  }
+  Ok(vec!["--profile".into(), profile.into()])
+}
+
+/// The directories written to the `--derived-data` directory by the build, removed by `--clean`.
+const DERIVED_DATA_BUILD_DIRS: [&str; 2] = ["Build", "ModuleCache.noindex"];
+
+/// The build settings placing the build products and intermediates in the `--derived-data` directory,
+/// with the layout of xcodebuild's `-derivedDataPath` which cannot be passed to the xcodebuild invocations of cargo-mobile2.
+fn derived_data_build_settings(derived_data: &Path) -> Vec<(String, String)> {
+  let [build_dir, module_cache_dir] = DERIVED_DATA_BUILD_DIRS;
+  let build = derived_data.join(build_dir);
+  [
+    ("SYMROOT", build.join("Products")),
+    ("OBJROOT", build.join("Intermediates.noindex")),
+    (
+      "SHARED_PRECOMPS_DIR",
+      build
+        .join("Intermediates.noindex")
+        .join("PrecompiledHeaders"),
+    ),
+    ("MODULE_CACHE_DIR", derived_data.join(module_cache_dir)),
+  ]
+  .into_iter()
+  .map(|(key, path)| (key.to_string(), path.display().to_string()))
+  .collect()
+}
+
+/// Writes the `--build-setting` overrides to an xcconfig file loaded by xcodebuild through `XCODE_XCCONFIG_FILE`.
+///
+/// Unlike the environment variables, the settings of this file override the ones defined in the Xcode project.
+fn apply_build_settings(
+  config: &AppleConfig,
+  env: &mut Env,
+  build_settings: &[(String, String)],
+) -> Result<()> {
+  let path = build_settings_xcconfig_path(config);
+  if build_settings.is_empty() {
+    // a file left by a previous build must not be picked up when reading the build settings
+    let _ = std::fs::remove_file(&path);
+    return Ok(());
+  }
+  create_dir_all(path.parent().unwrap())?;
+  let contents = build_settings
+    .iter()
+    .map(|(key, value)| format!("{key} = {value}\n"))
+    .collect::<String>();
+  std::fs::write(&path, contents).with_context(|| format!("failed to write {}", path.display()))?;
+  env.insert_env_var("XCODE_XCCONFIG_FILE".into(), path.into());
+  Ok(())
+}
+
+/// The environment variables Tauri sets for xcodebuild, printed with `--print-command`.
+fn xcodebuild_env_vars(
+  config: &AppleConfig,
+  build_env: &HashMap<String, OsString>,
+  no_codesign: bool,
+  build_settings: &[(String, String)],
+) -> HashMap<String, OsString> {
+  let mut vars = build_env.clone();
+  vars.extend(project_env_vars(config));
+  if no_codesign {
+    vars.extend(
+      CODE_SIGNING_DISABLED_ENV_VARS
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.into())),
+    );
+  }
+  if !build_settings.is_empty() {
+    vars.insert(
+      "XCODE_XCCONFIG_FILE".into(),
+      build_settings_xcconfig_path(config).into(),
+    );
+  }
+  vars
+}
+
+/// Resolves the active Xcode developer directory, honoring the `DEVELOPER_DIR` environment variable.
+fn xcode_developer_dir() -> Result<PathBuf> {
+  if let Some(dir) = var_os("DEVELOPER_DIR") {
+    let dir = PathBuf::from(dir);
+    if dir.is_dir() {
+      return Ok(dir);
+    }
+    return Err(
+      anyhow::anyhow!(
+        "DEVELOPER_DIR is set to {} which is not a directory",
+        dir.display()
+      )
+      .context(ErrorKind::XcodeMissing),
+    );
+  }
+
+  let output = Command::new("xcode-select")
+    .arg("--print-path")
+    .output()
+    .map_err(|e| anyhow::anyhow!("failed to run xcode-select: {e}"))
+    .context(ErrorKind::XcodeMissing)?;
+  if !output.status.success() {
+    return Err(
+      anyhow::anyhow!("no active developer directory found, please install Xcode")
+        .context(ErrorKind::XcodeMissing),
+    );
+  }
+  Ok(PathBuf::from(
+    String::from_utf8_lossy(&output.stdout).trim().to_string(),
+  ))
+}
+
+const DEFAULT_SIMULATOR_MIN_DISK_SPACE_GB: u64 = 10;
+
+/// Warns when the disk holding the simulator data is low on space,
+/// which makes booting the simulator or installing the app fail with obscure errors.
+fn warn_low_disk_space() {
+  let min_space_gb = match std::env::var("TAURI_IOS_SIMULATOR_MIN_DISK_SPACE") {
+    Ok(value) => value.parse::<u64>().unwrap_or_else(|_| {
+      log::warn!(
+        "Ignoring invalid `TAURI_IOS_SIMULATOR_MIN_DISK_SPACE` value {value}, it must be a number of gigabytes"
+      );
+      DEFAULT_SIMULATOR_MIN_DISK_SPACE_GB
+    }),
+    Err(_) => DEFAULT_SIMULATOR_MIN_DISK_SPACE_GB,
+  };
+  if min_space_gb == 0 {
+    return;
+  }
+
+  // simulator devices are stored in ~/Library/Developer/CoreSimulator
+  let Some(home) = var_os("HOME") else {
+    return;
+  };
+  let Ok(output) = Command::new("df").arg("-Pk").arg(home).output() else {
+    return;
+  };
+  let available_kb = String::from_utf8_lossy(&output.stdout)
+    .lines()
+    .nth(1)
+    .and_then(|line| line.split_whitespace().nth(3))
+    .and_then(|available| available.parse::<u64>().ok());
+  if let Some(available_kb) = available_kb {
+    let available_gb = available_kb / 1024 / 1024;
+    if available_gb < min_space_gb {
+      log::warn!(
+        "Only {available_gb}GB of disk space available, booting the simulator and installing the app may fail. At least {min_space_gb}GB are recommended."
+      );
+    }
+  }
+}
+
+/// Ensures a full Xcode installation (not only the command line tools) is selected.
+fn ensure_xcode() -> Result<PathBuf> {
+  let developer_dir = xcode_developer_dir()?;
+  if developer_dir.ends_with("CommandLineTools") {
+    return Err(
+      anyhow::anyhow!(
+        "the active developer directory {} only contains the command line tools. Install Xcode and run `sudo xcode-select --switch /Applications/Xcode.app`",
+        developer_dir.display()
+      )
+      .context(ErrorKind::XcodeMissing),
+    );
+  }
+  Ok(developer_dir)
+}
+
+/// Parses an Xcode version such as `15`, `15.4` or `16.0.1`.
+fn parse_xcode_version(version: &str) -> Option<semver::Version> {
+  let mut components = version
+    .trim()
+    .split('.')
+    .map(|c| c.parse::<u64>().ok())
+    .collect::<Option<Vec<_>>>()?;
+  if components.is_empty() || components.len() > 3 {
+    return None;
+  }
+  components.resize(3, 0);
+  Some(semver::Version::new(
+    components[0],
+    components[1],
+    components[2],
+  ))
+}
+
+/// Reads the version of the Xcode installation the developer directory belongs to.
+fn xcode_version(developer_dir: &Path) -> Result<semver::Version> {
+  // `Xcode.app/Contents/Developer` -> `Xcode.app/Contents/version.plist`
+  let version_plist = developer_dir
+    .parent()
+    .map(|contents| contents.join("version.plist"))
+    .unwrap_or_default();
+  let version = plist::Value::from_file(&version_plist)
+    .ok()
+    .and_then(|plist| {
+      plist
+        .as_dictionary()?
+        .get("CFBundleShortVersionString")?
+        .as_string()
+        .map(ToString::to_string)
+    })
+    .ok_or_else(|| {
+      anyhow::anyhow!(
+        "failed to read the Xcode version from {}",
+        version_plist.display()
+      )
+    })
+    .context(ErrorKind::XcodeMissing)?;
+  parse_xcode_version(&version)
+    .ok_or_else(|| anyhow::anyhow!("invalid Xcode version {version}"))
+    .context(ErrorKind::XcodeMissing)
+}
+
+/// Checks the active Xcode against the `bundle > iOS > minimumXcodeVersion` configuration.
+fn ensure_minimum_xcode_version(tauri_config: &TauriConfig, developer_dir: &Path) -> Result<()> {
+  let Some(minimum) = &tauri_config.bundle.ios.minimum_xcode_version else {
+    return Ok(());
+  };
+  let minimum_version = parse_xcode_version(minimum)
+    .ok_or_else(|| {
+      anyhow::anyhow!(
+        "`bundle > iOS > minimumXcodeVersion` must be a version such as `15` or `15.4`, found `{minimum}`"
+      )
+    })
+    .context(ErrorKind::InvalidConfig)?;
+  let version = xcode_version(developer_dir)?;
+  if version < minimum_version {
+    return Err(
+      anyhow::anyhow!(
+        "this app requires Xcode {minimum} or newer, but the active Xcode at {} is version {version}. Install a newer Xcode and select it with `sudo xcode-select --switch` or the `DEVELOPER_DIR` environment variable",
+        developer_dir.display()
+      )
+      .context(ErrorKind::XcodeMissing),
+    );
+  }
+  Ok(())
+}
+
+/// The Xcode file to open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum XcodeOpenTarget {
+  /// The `.xcworkspace`, required by CocoaPods and Swift Package Manager integrations.
+  Workspace,
+  /// The `.xcodeproj`.
+  Project,
+}
+
+/// Finds the first file with the given extension in the Xcode project directory.
+fn find_xcode_file(project_dir: &Path, extension: &str) -> Option<PathBuf> {
+  std::fs::read_dir(project_dir)
+    .ok()?
+    .flatten()
+    .map(|entry| entry.path())
+    .find(|path| path.extension().is_some_and(|ext| ext == extension))
+}
+
+/// Removes the build products of the generated Xcode project with `xcodebuild clean`,
+/// or the directories created by the build in the `--derived-data` directory when set.
+///
+/// The `--derived-data` directory itself is kept, since it can be any directory, e.g. the project directory.
+fn clean_xcode_project(config: &AppleConfig, derived_data: Option<&Path>) -> Result<()> {
+  if let Some(derived_data) = derived_data {
+    for dir in DERIVED_DATA_BUILD_DIRS {
+      let path = derived_data.join(dir);
+      if path.is_dir() {
+        std::fs::remove_dir_all(&path)
+          .with_context(|| format!("failed to remove {}", path.display()))?;
+        log::info!(action = "Cleaned"; "{}", path.display());
+      }
+    }
+    return Ok(());
+  }
+
+  let project_dir = config.project_dir();
+  let project = find_xcode_file(&project_dir, "xcodeproj").ok_or_else(|| {
+    anyhow::anyhow!(
+      "could not find the Xcode project in {}",
+      project_dir.display()
+    )
+    .context(ErrorKind::InvalidConfig)
+  })?;
+
+  let start = Instant::now();
+  Command::new("xcodebuild")
+    .arg("clean")
+    .arg("-project")
+    .arg(&project)
+    .arg("-scheme")
+    .arg(config.scheme())
+    .output_ok()
+    .with_context(|| format!("failed to clean {}", project.display()))
+    .context(ErrorKind::BuildFailed)?;
+  log::info!(action = "Cleaned"; "{} in {:.2}s", project.display(), start.elapsed().as_secs_f64());
+
+  Ok(())
+}
+
+/// Opens Xcode, preferring the workspace over the project unless `open_target` is set.
+fn open_and_wait(config: &AppleConfig, env: &Env, open_target: Option<XcodeOpenTarget>) -> ! {
+  let project_dir = config.project_dir();
+  let workspace = || find_xcode_file(&project_dir, "xcworkspace");
+  let project = || find_xcode_file(&project_dir, "xcodeproj");
+  let path = match open_target {
+    Some(XcodeOpenTarget::Workspace) => workspace(),
+    Some(XcodeOpenTarget::Project) => project(),
+    None => workspace().or_else(project),
+  }
+  .unwrap_or_else(|| {
+    if let Some(open_target) = open_target {
+      let kind = match open_target {
+        XcodeOpenTarget::Workspace => "workspace",
+        XcodeOpenTarget::Project => "project",
+      };
+      log::warn!(
+        "Could not find an Xcode {kind} in {}, opening the project directory instead",
+        project_dir.display()
+      );
+    }
+    project_dir.clone()
+  });
+
+  log::info!("Opening {} in Xcode", path.display());
+  if let Err(e) = os::open_file_with("Xcode", &path, env) {
+    log::error!("{}", e);
+  }
+  wait_forever()
+}
+
+/// Blocks the current thread, keeping the dev server alive until the CLI is stopped.
+fn wait_forever() -> ! {
+  loop {
+    sleep(Duration::from_secs(24 * 60 * 60));
+  }
+}
+
+/// Ensures the Xcode project directory, or its closest existing parent, is writable.
+fn ensure_project_dir_writable(project_dir: &Path) -> Result<()> {
+  let existing = project_dir
+    .ancestors()
+    .find(|dir| dir.exists())
+    .unwrap_or(project_dir);
+  let readonly = std::fs::metadata(existing)
+    .map(|metadata| metadata.permissions().readonly())
+    .unwrap_or(false);
+  if readonly {
+    return Err(
+      anyhow::anyhow!(
+        "the iOS project directory {} is not writable",
+        project_dir.display()
+      )
+      .context(ErrorKind::InvalidConfig),
+    );
+  }
+  Ok(())
+}
+
+fn inject_assets(config: &AppleConfig, tauri_config: &TauriConfig) -> Result<()> {
+  ensure_project_dir_writable(&config.project_dir())?;
+  let asset_dir = config.project_dir().join(DEFAULT_ASSET_DIR);
+  create_dir_all(&asset_dir)?;
+  assets::copy_resources(&asset_dir, tauri_config)?;
+  app_icon::populate(
+    &config.project_dir(),
+    &tauri_dir(),
+    &tauri_config.bundle.icon,
+  )?;
+  launch_screen::write_image(&config.project_dir(), &tauri_dir(), tauri_config)?;
+  Ok(())
+}
+
+enum PlistKind {
+  Path(PathBuf),
+  Plist(plist::Value),
+}
+
+impl From<PathBuf> for PlistKind {
+  fn from(p: PathBuf) -> Self {
+    Self::Path(p)
+  }
+}
+impl From<plist::Value> for PlistKind {
+  fn from(p: plist::Value) -> Self {
+    Self::Plist(p)
+  }
+}
+
+impl PlistKind {
+  /// Loads the plist, `None` if its file does not exist or cannot be read.
+  fn load(self) -> Option<plist::Value> {
+    match self {
+      Self::Path(p) => plist::Value::from_file(p).ok(),
+      Self::Plist(v) => Some(v),
+    }
+  }
+}
+
+/// Loads the Info.plist sources of the build configuration, preserving their order.
+///
+/// The `bundle > iOS > infoPlistConfigurationFiles` entry of the configuration is used when it exists,
+/// falling back to `bundle > iOS > infoPlistFiles`.
+fn info_plist_files(
+  tauri_config: &TauriConfig,
+  tauri_dir: &Path,
+  profile: Profile,
+) -> Result<Vec<PlistKind>> {
+  let ios = &tauri_config.bundle.ios;
+  let files = ios
+    .info_plist_configuration_files
+    .as_ref()
+    .and_then(|files| {
+      files
+        .iter()
+        .find(|(configuration, _)| configuration.eq_ignore_ascii_case(configuration_name(profile)))
+        .map(|(_, files)| files.as_slice())
+    })
+    .or(ios.info_plist_files.as_deref())
+    .unwrap_or_default();
+  files
+    .iter()
+    .map(|file| read_plist_source(&tauri_dir.join(file)).map(PlistKind::from))
+    .collect()
+}
+
+/// Reads a plist source of the configuration, a plist file or its JSON or YAML representation, see [`json_plist`].
+fn read_plist_source(path: &Path) -> Result<plist::Value> {
+  let plist = match path.extension().and_then(|ext| ext.to_str()) {
+    Some("plist" | "entitlements") => plist::Value::from_file(path)
+      .with_context(|| format!("failed to read plist file {}", path.display())),
+    Some("json") => std::fs::read_to_string(path)
+      .map_err(anyhow::Error::from)
+      .and_then(|contents| {
+        serde_json::from_str::<serde_json::Value>(&contents).map_err(anyhow::Error::from)
+      })
+      .and_then(|json| json_plist::to_plist(&json))
+      .with_context(|| format!("failed to read JSON file {}", path.display())),
+    Some("yaml" | "yml") => std::fs::read_to_string(path)
+      .map_err(anyhow::Error::from)
+      .and_then(|contents| {
+        serde_yaml::from_str::<serde_json::Value>(&contents).map_err(anyhow::Error::from)
+      })
+      .and_then(|json| json_plist::to_plist(&json))
+      .with_context(|| format!("failed to read YAML file {}", path.display())),
+    _ => Err(anyhow::anyhow!(
+      "unsupported plist source {}, expected a `.plist`, `.entitlements`, `.json` or `.yaml` file",
+      path.display()
+    )),
+  };
+  plist.context(ErrorKind::InvalidConfig)
+}
+
+/// Value of a source plist key removing the key from the merged plist, e.g. `<string>$delete</string>`
+/// or `"UIRequiredDeviceCapabilities": "$delete"` in a JSON source.
+const PLIST_DELETE_MARKER: &str = "$delete";
+
+/// Merges the given plists into `dest`, in order.
+///
+/// A missing `dest` file is treated as an empty dictionary and created on write.
+/// Top-level keys set to [`PLIST_DELETE_MARKER`] are removed instead of replaced.
+fn merge_plist(src: Vec<PlistKind>, dest: &Path) -> Result<()> {
+  let mut dest_plist = None;
+
+  for src_plist in src.into_iter().filter_map(PlistKind::load) {
+    if dest_plist.is_none() {
+      let plist = if dest.exists() {
+        plist::Value::from_file(dest)
+          .with_context(|| format!("failed to read plist file {}", dest.display()))?
+      } else {
+        plist::Dictionary::new().into()
+      };
+      dest_plist.replace(plist);
+    }
+
+    let plist = dest_plist.as_mut().expect("plist not loaded");
+    if let (Some(plist), Some(dict)) = (plist.as_dictionary_mut(), src_plist.into_dictionary()) {
+      merge_plist_keys(plist, dict);
+    }
+  }
+
+  match dest_plist {
+    Some(dest_plist) => write_plist(&dest_plist, dest),
+    None => Ok(()),
+  }
+}
+
+/// Merges the top-level keys of `src` into `dest`, removing the keys set to [`PLIST_DELETE_MARKER`].
+fn merge_plist_keys(dest: &mut plist::Dictionary, src: plist::Dictionary) {
+  for (key, value) in src {
+    if value.as_string() == Some(PLIST_DELETE_MARKER) {
+      dest.remove(&key);
+    } else {
+      dest.insert(key, value);
+    }
+  }
+}
+
+/// Writes a plist file, skipping an unchanged file so Xcode does not rebuild the app.
+fn write_plist(plist: &plist::Value, dest: &Path) -> Result<()> {
+  let mut contents = Vec::new();
+  plist.to_writer_xml(&mut contents)?;
+  if std::fs::read(dest).is_ok_and(|existing| existing == contents) {
+    return Ok(());
+  }
+  if let Some(parent) = dest.parent() {
+    create_dir_all(parent)?;
+  }
+  std::fs::write(dest, contents)?;
+  Ok(())
+}
+
+/// Stores the app Info.plist values replaced by the build sources of the last build,
+/// with [`PLIST_DELETE_MARKER`] for the keys they added.
+const INFO_PLIST_RESTORE_FILE_NAME: &str = ".info-plist-restore.plist";
+
+/// Merges the Info.plist sources into the app Info.plist, followed by the `build_src` plists
+/// that depend on the build, e.g. the [`info_plist_files`] of the build configuration.
+///
+/// The values replaced by the `build_src` plists are restored before merging on the next build,
+/// so the keys of one build configuration do not leak into the builds of another one.
+fn merge_info_plist(
+  config: &AppleConfig,
+  src: Vec<PlistKind>,
+  build_src: Vec<PlistKind>,
+) -> Result<()> {
+  let info_plist_path = config
+    .project_dir()
+    .join(config.scheme())
+    .join("Info.plist");
+  let restore_path = config.project_dir().join(INFO_PLIST_RESTORE_FILE_NAME);
+
+  let mut info_plist = if info_plist_path.exists() {
+    plist::Value::from_file(&info_plist_path)
+      .with_context(|| format!("failed to read plist file {}", info_plist_path.display()))?
+      .into_dictionary()
+      .ok_or_else(|| anyhow::anyhow!("{} is not a dictionary", info_plist_path.display()))?
+  } else {
+    plist::Dictionary::new()
+  };
+  for src_plist in std::iter::once(PlistKind::from(restore_path.clone()))
+    .chain(src)
+    .filter_map(PlistKind::load)
+  {
+    if let Some(dict) = src_plist.into_dictionary() {
+      merge_plist_keys(&mut info_plist, dict);
+    }
+  }
+
+  let mut restore = plist::Dictionary::new();
+  for src_plist in build_src.into_iter().filter_map(PlistKind::load) {
+    if let Some(dict) = src_plist.into_dictionary() {
+      for key in dict.keys() {
+        if !restore.contains_key(key) {
+          let value = info_plist
+            .get(key)
+            .cloned()
+            .unwrap_or_else(|| PLIST_DELETE_MARKER.into());
+          restore.insert(key.clone(), value);
+        }
+      }
+      merge_plist_keys(&mut info_plist, dict);
+    }
+  }
+
+  if restore.is_empty() {
+    if restore_path.exists() {
+      std::fs::remove_file(&restore_path)
+        .with_context(|| format!("failed to remove {}", restore_path.display()))?;
+    }
+  } else {
+    write_plist(&restore.into(), &restore_path)?;
+  }
+  write_plist(&info_plist.into(), &info_plist_path)
+}
+
+pub fn signing_from_env(
+  tauri_config: &TauriConfig,
+  catalyst: bool,
+) -> Result<(
+  Option<tauri_macos_sign::Keychain>,
+  Option<tauri_macos_sign::ProvisioningProfile>,
+)> {
+  let keychain_timeout = keychain_timeout_from_env()?;
+
+  if let Some(bundle) = var_os("IOS_SIGNING_BUNDLE") {
+    let bundle = signing_bundle::SigningBundle::load(&bundle).context(ErrorKind::SigningMissing)?;
+    if let Some(timeout) = keychain_timeout {
+      bundle
+        .keychain
+        .set_lock_timeout(timeout)
+        .context(ErrorKind::SigningMissing)?;
+    }
+    return Ok((Some(bundle.keychain), Some(bundle.provisioning_profile)));
+  }
+
+  let keychain = if let (Some(certificate), Some(certificate_password)) = (
+    var_os("IOS_CERTIFICATE"),
+    var_os("IOS_CERTIFICATE_PASSWORD"),
+  ) {
+    let keychain =
+      tauri_macos_sign::Keychain::with_certificate(&certificate, &certificate_password)
+        .context(ErrorKind::SigningMissing)?;
+    if let Some(timeout) = keychain_timeout {
+      keychain
+        .set_lock_timeout(timeout)
+        .context(ErrorKind::SigningMissing)?;
+    }
+    Some(keychain)
+  } else {
+    None
+  };
+  let provisioning_profile = provisioning_profile_from_env(tauri_config, catalyst)?;
+
+  Ok((keychain, provisioning_profile))
+}
+
+const KEYCHAIN_TIMEOUT_ENV_VAR_NAME: &str = "IOS_KEYCHAIN_TIMEOUT";
+
+/// Reads how long the keychain created for signing stays unlocked from `IOS_KEYCHAIN_TIMEOUT`,
+/// in seconds or `never` to disable the auto-lock for the duration of the build.
+///
+/// The keychain is deleted once the command finishes, so it does not stay unlocked afterwards.
+fn keychain_timeout_from_env() -> Result<Option<Option<Duration>>> {
+  std::env::var(KEYCHAIN_TIMEOUT_ENV_VAR_NAME)
+    .ok()
+    .map(|value| parse_keychain_timeout(&value))
+    .transpose()
+}
+
+/// Parses a `IOS_KEYCHAIN_TIMEOUT` value, `None` meaning that the keychain never locks.
+fn parse_keychain_timeout(value: &str) -> Result<Option<Duration>> {
+  if value.trim().eq_ignore_ascii_case("never") {
+    return Ok(None);
+  }
+  value
+    .trim()
+    .parse::<u64>()
+    .ok()
+    .filter(|secs| *secs > 0)
+    .map(|secs| Some(Duration::from_secs(secs)))
+    .ok_or_else(|| {
+      anyhow::anyhow!(
+        "invalid `{KEYCHAIN_TIMEOUT_ENV_VAR_NAME}` value `{value}`, expected a number of seconds or `never`"
+      )
+      .context(ErrorKind::InvalidConfig)
+    })
+}
+
+/// Path of the profile installed from `IOS_MOBILE_PROVISION`, so it is only written once per process.
+static ENV_PROVISIONING_PROFILE_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// Loads the provisioning profile from the `IOS_MOBILE_PROVISION` environment variable,
+/// falling back to the installed profile matching the `IOS_PROVISIONING_PROFILE_NAME` environment variable
+/// or the `bundle > iOS > provisioningProfileName` config.
+pub fn provisioning_profile_from_env(
+  tauri_config: &TauriConfig,
+  catalyst: bool,
+) -> Result<Option<tauri_macos_sign::ProvisioningProfile>> {
+  if let Some(provisioning_profile) = var_os("IOS_MOBILE_PROVISION") {
+    if let Some(path) = ENV_PROVISIONING_PROFILE_PATH.get() {
+      return Ok(Some(tauri_macos_sign::ProvisioningProfile::from_path(
+        path.clone(),
+      )));
+    }
+    let profile = tauri_macos_sign::ProvisioningProfile::from_base64(&provisioning_profile)
+      .context(ErrorKind::SigningMissing)?;
+    let _ = ENV_PROVISIONING_PROFILE_PATH.set(profile.path().to_path_buf());
+    Ok(Some(profile))
+  } else if let Some(pattern) = std::env::var("IOS_PROVISIONING_PROFILE_NAME")
+    .ok()
+    .or_else(|| tauri_config.bundle.ios.provisioning_profile_name.clone())
+  {
+    find_installed_provisioning_profile(&pattern, &bundle_identifier(tauri_config), catalyst)
+      .map(Some)
+  } else {
+    Ok(None)
+  }
+}
+
+/// The `Platform` of the provisioning profiles that can sign the app being built,
+/// Mac Catalyst apps are signed with macOS profiles.
+fn provisioning_profile_platform(catalyst: bool) -> &'static str {
+  if catalyst {
+    "OSX"
+  } else {
+    "iOS"
+  }
+}
+
+/// Selects the latest expiring installed provisioning profile whose name matches the glob pattern
+/// and that can sign the app with the given bundle identifier on the platform of the build.
+fn find_installed_provisioning_profile(
+  pattern: &str,
+  identifier: &str,
+  catalyst: bool,
+) -> Result<tauri_macos_sign::ProvisioningProfile> {
+  let name_pattern = glob::Pattern::new(pattern)
+    .with_context(|| format!("invalid provisioning profile name pattern {pattern}"))
+    .context(ErrorKind::InvalidConfig)?;
+
+  let mut candidates = installed_provisioning_profiles(identifier, catalyst, |profile| {
+    let name = profile.name()?;
+    Ok(name_pattern.matches(&name).then_some(name))
+  })?;
+  let count = candidates.len();
+  let (profile, name) = candidates.pop().ok_or_else(|| {
+    anyhow::anyhow!(
+      "no valid installed provisioning profile named {pattern} can sign {identifier} on {}",
+      provisioning_profile_platform(catalyst)
+    )
+    .context(ErrorKind::SigningMissing)
+  })?;
+  if count > 1 {
+    log::info!("Using provisioning profile {name}, the latest expiring of the {count} profiles matching {pattern}");
+  } else {
+    log::info!("Using provisioning profile {name}");
+  }
+  Ok(profile)
+}
+
+/// Selects the latest expiring installed provisioning profile of the given distribution method
+/// that can sign the app with the given bundle identifier on the platform of the build, `None` if there is none.
+fn find_installed_provisioning_profile_of_type(
+  profile_type: tauri_macos_sign::ProfileType,
+  identifier: &str,
+  catalyst: bool,
+) -> Result<Option<tauri_macos_sign::ProvisioningProfile>> {
+  let mut candidates = installed_provisioning_profiles(identifier, catalyst, |profile| {
+    Ok((profile.profile_type()? == profile_type).then_some(()))
+  })?;
+  Ok(candidates.pop().map(|(profile, _)| profile))
+}
+
+/// Lists the unexpired installed provisioning profiles that can sign the app with the given bundle identifier
+/// on the platform of the build and that `filter` accepts, sorted by expiration date.
+///
+/// `filter` returns the details of the accepted profiles, e.g. their name.
+fn installed_provisioning_profiles<T>(
+  identifier: &str,
+  catalyst: bool,
+  filter: impl Fn(&tauri_macos_sign::ProvisioningProfile) -> Result<Option<T>>,
+) -> Result<Vec<(tauri_macos_sign::ProvisioningProfile, T)>> {
+  let now = std::time::SystemTime::now();
+  let mut candidates = Vec::new();
+  let mut skipped = 0;
+  for profile in
+    tauri_macos_sign::ProvisioningProfile::installed().context(ErrorKind::SigningMissing)?
+  {
+    // a corrupt profile, e.g. left over by an older Xcode, must not prevent selecting the others
+    let details = filter(&profile).and_then(|details| {
+      Ok((
+        details,
+        profile.matches_bundle_identifier(identifier)?
+          && profile.supports_platform(provisioning_profile_platform(catalyst))?,
+        profile.expires_at()?,
+      ))
+    });
+    match details {
+      Ok((Some(details), true, expires_at)) if expires_at > now => {
+        candidates.push((profile, details, expires_at))
+      }
+      Ok(_) => {}
+      Err(e) => {
+        log::debug!(
+          "skipping unreadable provisioning profile {}: {e:#}",
+          profile.path().display()
+        );
+        skipped += 1;
+      }
+    }
+  }
+  if skipped > 0 {
+    log::debug!("skipped {skipped} unreadable installed provisioning profiles");
+  }
+
+  candidates.sort_by_key(|(_, _, expires_at)| *expires_at);
+  Ok(
+    candidates
+      .into_iter()
+      .map(|(profile, details, _)| (profile, details))
+      .collect(),
+  )
+}
+
+const CODE_SIGN_STYLE_ENV_VAR_NAME: &str = "IOS_CODE_SIGN_STYLE";
+
+/// Resolves the signing configuration of the Xcode project.
+///
+/// The signing style is manual when both a certificate and a provisioning profile are available,
+/// unless it is forced with the `IOS_CODE_SIGN_STYLE` environment variable.
 pub fn init_config(
   keychain: Option<&tauri_macos_sign::Keychain>,
   provisioning_profile: Option<&tauri_macos_sign::ProvisioningProfile>,
 ) -> Result<super::init::IosInitConfig> {
+  let forced_manual = match std::env::var(CODE_SIGN_STYLE_ENV_VAR_NAME) {
+    Ok(style) => match style.as_str() {
+      "manual" => Some(true),
+      "automatic" => Some(false),
+      _ => {
+        return Err(
+          anyhow::anyhow!(
+            "invalid `{CODE_SIGN_STYLE_ENV_VAR_NAME}` value `{style}`, expected `manual` or `automatic`"
+          )
+          .context(ErrorKind::InvalidConfig),
+        )
+      }
+    },
+    Err(_) => None,
+  };
+
+  if forced_manual == Some(true) {
+    let mut missing = Vec::new();
+    if keychain.is_none() {
+      missing.push("a signing certificate (`IOS_CERTIFICATE` and `IOS_CERTIFICATE_PASSWORD`, or `IOS_SIGNING_BUNDLE`)");
+    }
+    if provisioning_profile.is_none() {
+      missing.push("a provisioning profile (`IOS_MOBILE_PROVISION`, `IOS_SIGNING_BUNDLE`, `IOS_PROVISIONING_PROFILE_NAME` or `bundle > iOS > provisioningProfileName`)");
+    }
+    if !missing.is_empty() {
+      return Err(
+        anyhow::anyhow!(
+          "`{CODE_SIGN_STYLE_ENV_VAR_NAME}` is set to manual but {} is missing",
+          missing.join(" and ")
+        )
+        .context(ErrorKind::SigningMissing),
+      );
+    }
+  }
+  let manual =
+    forced_manual.unwrap_or_else(|| keychain.is_some() && provisioning_profile.is_some());
+  if manual {
+    if let (Some(team), Some(profile)) = (keychain.and_then(|k| k.team_id()), provisioning_profile)
+    {
+      if profile
+        .team_ids()
+        .is_ok_and(|teams| !teams.iter().any(|t| t == team))
+      {
+        log::warn!(
+          "The provisioning profile {} does not belong to the development team {team} of the signing certificate, manual signing will fail",
+          profile.name().unwrap_or_else(|_| profile.path().display().to_string())
+        );
+      }
+    }
+  }
+  // Xcode rejects a signing identity or provisioning profile when forced to sign automatically,
+  // the team of the certificate is still used
+  let keep_signing_assets = forced_manual != Some(false);
+
   Ok(super::init::IosInitConfig {
-    code_sign_style: if keychain.is_some() && provisioning_profile.is_some() {
+    code_sign_style: if manual {
       super::init::CodeSignStyle::Manual
     } else {
       super::init::CodeSignStyle::Automatic
     },
-    code_sign_identity: keychain.map(|k| k.signing_identity()),
+    code_sign_identity: keychain
+      .filter(|_| keep_signing_assets)
+      .map(|k| k.signing_identity()),
     team_id: keychain.and_then(|k| k.team_id().map(ToString::to_string)),
-    provisioning_profile_uuid: provisioning_profile.and_then(|p| p.uuid().ok()),
+    provisioning_profile_uuid: provisioning_profile
+      .filter(|_| keep_signing_assets)
+      .and_then(|p| p.uuid().ok()),
   })
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{
+    info_plist_files, merge_plist, merge_plist_keys, parse_build_setting, parse_keychain_timeout,
+    parse_xcode_version, validate_app_name, validate_deep_links, Profile, TauriConfig,
+  };
+
+  use std::time::Duration;
+
+  fn config(ios: serde_json::Value) -> TauriConfig {
+    serde_json::from_value(serde_json::json!({
+      "identifier": "com.tauri.test",
+      "bundle": { "iOS": ios },
+    }))
+    .unwrap()
+  }
+
+  #[test]
+  fn validates_app_name() {
+    assert!(validate_app_name("my-app_2").is_ok());
+    for name in ["", "2app", "my app", "Tauri", "a".repeat(65).as_str()] {
+      assert!(
+        validate_app_name(name).is_err(),
+        "`{name}` should be rejected"
+      );
+    }
+
+    let error = format!("{:#}", validate_app_name("2 my app").unwrap_err());
+    assert!(error.contains("rename it to e.g. `my-app`"), "{error}");
+    let error = format!("{:#}", validate_app_name("pods").unwrap_err());
+    assert!(error.contains("rename it to e.g. `pods-app`"), "{error}");
+  }
+
+  #[test]
+  fn parses_xcode_version() {
+    assert_eq!(
+      parse_xcode_version("15"),
+      Some(semver::Version::new(15, 0, 0))
+    );
+    assert_eq!(
+      parse_xcode_version("15.4\n"),
+      Some(semver::Version::new(15, 4, 0))
+    );
+    assert_eq!(
+      parse_xcode_version("16.0.1"),
+      Some(semver::Version::new(16, 0, 1))
+    );
+    assert_eq!(parse_xcode_version("16.0.1.2"), None);
+    assert_eq!(parse_xcode_version("16 beta"), None);
+    assert_eq!(parse_xcode_version(""), None);
+  }
+
+  #[test]
+  fn parses_build_setting() {
+    assert_eq!(
+      parse_build_setting("SWIFT_VERSION=5.0"),
+      Ok(("SWIFT_VERSION".into(), "5.0".into()))
+    );
+    assert_eq!(
+      parse_build_setting("OTHER_LDFLAGS[sdk=iphoneos*][arch=arm64]=-lz -framework A=B"),
+      Ok((
+        "OTHER_LDFLAGS[sdk=iphoneos*][arch=arm64]".into(),
+        "-lz -framework A=B".into()
+      ))
+    );
+    assert_eq!(
+      parse_build_setting("ENABLE_BITCODE="),
+      Ok(("ENABLE_BITCODE".into(), "".into()))
+    );
+    for setting in [
+      "SWIFT_VERSION",
+      "1SETTING=value",
+      "MY-SETTING=value",
+      "OTHER_LDFLAGS[sdk]=-lz",
+      "OTHER_LDFLAGS[sdk=iphoneos*=-lz",
+      "OTHER_LDFLAGS=-lz\n-lc",
+    ] {
+      assert!(
+        parse_build_setting(setting).is_err(),
+        "`{setting}` should be rejected"
+      );
+    }
+  }
+
+  #[test]
+  fn validates_deep_links() {
+    let valid = config(serde_json::json!({
+      "urlSchemes": ["myapp", "com.example.my-app+dev"],
+      "associatedDomains": [
+        "applinks:example.com",
+        "webcredentials:*.example.com?mode=developer",
+      ],
+    }));
+    assert!(validate_deep_links(&valid).is_ok());
+
+    for ios in [
+      serde_json::json!({ "urlSchemes": ["myapp://"] }),
+      serde_json::json!({ "urlSchemes": ["1app"] }),
+      serde_json::json!({ "associatedDomains": ["example.com"] }),
+      serde_json::json!({ "associatedDomains": ["links:example.com"] }),
+      serde_json::json!({ "associatedDomains": ["applinks:https://example.com"] }),
+      serde_json::json!({ "associatedDomains": ["applinks:.example.com"] }),
+    ] {
+      assert!(validate_deep_links(&config(ios.clone())).is_err(), "{ios}");
+    }
+  }
+
+  #[test]
+  fn merges_plist_keys_with_delete_marker() {
+    let mut dest = plist::Dictionary::new();
+    dest.insert("Kept".into(), "kept".into());
+    dest.insert("Replaced".into(), "old".into());
+    dest.insert("Deleted".into(), true.into());
+
+    let mut src = plist::Dictionary::new();
+    src.insert("Replaced".into(), "new".into());
+    src.insert("Deleted".into(), "$delete".into());
+    src.insert("Missing".into(), "$delete".into());
+    src.insert("Added".into(), 1i64.into());
+    merge_plist_keys(&mut dest, src);
+
+    assert_eq!(dest.get("Kept").and_then(|v| v.as_string()), Some("kept"));
+    assert_eq!(
+      dest.get("Replaced").and_then(|v| v.as_string()),
+      Some("new")
+    );
+    assert_eq!(
+      dest.get("Added").and_then(|v| v.as_signed_integer()),
+      Some(1)
+    );
+    assert!(!dest.contains_key("Deleted"));
+    assert!(!dest.contains_key("Missing"));
+  }
+
+  #[test]
+  fn merges_plist_json_and_yaml_sources_in_order() {
+    let dir = std::env::temp_dir().join(format!("tauri-ios-plist-sources-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let mut base = plist::Dictionary::new();
+    base.insert("Base".into(), "plist".into());
+    base.insert("Overridden".into(), "plist".into());
+    base.insert("Deleted".into(), true.into());
+    plist::Value::from(base)
+      .to_file_xml(dir.join("base.plist"))
+      .unwrap();
+    std::fs::write(
+      dir.join("layer.json"),
+      r#"{ "Overridden": "json", "Json": [1, 2.5] }"#,
+    )
+    .unwrap();
+    std::fs::write(
+      dir.join("layer.yaml"),
+      "Overridden: yaml\nDeleted: $delete\nYaml:\n  Nested: true\n",
+    )
+    .unwrap();
+
+    let sources = info_plist_files(
+      &config(serde_json::json!({
+        "infoPlistFiles": ["base.plist", "layer.json", "layer.yaml"],
+      })),
+      &dir,
+      Profile::Debug,
+    )
+    .unwrap();
+    let dest = dir.join("Info.plist");
+    merge_plist(sources, &dest).unwrap();
+    let merged = plist::Value::from_file(&dest)
+      .unwrap()
+      .into_dictionary()
+      .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+      merged.get("Base").and_then(|v| v.as_string()),
+      Some("plist")
+    );
+    assert_eq!(
+      merged.get("Overridden").and_then(|v| v.as_string()),
+      Some("yaml")
+    );
+    let json = merged.get("Json").and_then(|v| v.as_array()).unwrap();
+    assert_eq!(json[0].as_signed_integer(), Some(1));
+    assert_eq!(json[1].as_real(), Some(2.5));
+    assert_eq!(
+      merged
+        .get("Yaml")
+        .and_then(|v| v.as_dictionary())
+        .and_then(|yaml| yaml.get("Nested"))
+        .and_then(|v| v.as_boolean()),
+      Some(true)
+    );
+    assert!(!merged.contains_key("Deleted"));
+  }
+
+  #[test]
+  fn parses_keychain_timeout() {
+    assert_eq!(
+      parse_keychain_timeout("300").unwrap(),
+      Some(Duration::from_secs(300))
+    );
+    assert_eq!(
+      parse_keychain_timeout(" 60 ").unwrap(),
+      Some(Duration::from_secs(60))
+    );
+    assert_eq!(parse_keychain_timeout("never").unwrap(), None);
+    assert_eq!(parse_keychain_timeout("NEVER").unwrap(), None);
+    for value in ["0", "-5", "5m", ""] {
+      assert!(
+        parse_keychain_timeout(value).is_err(),
+        "`{value}` should be rejected"
+      );
+    }
+  }
+}
@@ -0,0 +1,124 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Wires the `bundle > iOS > onDemandResources` configuration into the Xcode project.
+
+use super::ErrorKind;
+use crate::Result;
+
+use serde::Serialize;
+use tauri_utils::config::{Config as TauriConfig, IosOnDemandResources};
+
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  path::Path,
+};
+
+/// A file or folder of the Xcode project tagged as an On-Demand Resource.
+#[derive(Debug, Serialize)]
+pub struct TaggedSource {
+  /// Path relative to the Tauri directory.
+  pub path: String,
+  pub tags: Vec<String>,
+}
+
+/// Checks that the tags are valid build setting values and that the tagged files exist.
+pub fn validate(resources: &IosOnDemandResources, tauri_dir: &Path) -> Result<()> {
+  let mut problems = Vec::new();
+
+  for (tag, paths) in &resources.tags {
+    if tag.is_empty() || tag.chars().any(char::is_whitespace) {
+      problems.push(format!(
+        "tag `{tag}` must not be empty or contain whitespace"
+      ));
+    }
+    if paths.is_empty() {
+      problems.push(format!("tag `{tag}` does not include any file"));
+    }
+    for path in paths {
+      if !tauri_dir.join(path).exists() {
+        problems.push(format!("{} of tag `{tag}` does not exist", path.display()));
+      }
+    }
+  }
+
+  for (category, tags) in [
+    ("initialInstall", &resources.initial_install),
+    ("prefetch", &resources.prefetch),
+  ] {
+    for tag in tags {
+      if !resources.tags.contains_key(tag) {
+        problems.push(format!("{category} references the unknown tag `{tag}`"));
+      }
+    }
+  }
+  for tag in &resources.initial_install {
+    if resources.prefetch.contains(tag) {
+      problems.push(format!(
+        "tag `{tag}` cannot be both installed with the app and prefetched"
+      ));
+    }
+  }
+
+  if problems.is_empty() {
+    Ok(())
+  } else {
+    Err(
+      anyhow::anyhow!(
+        "invalid `bundle > iOS > onDemandResources` configuration:\n{}",
+        problems
+          .iter()
+          .map(|p| format!("  - {p}"))
+          .collect::<Vec<_>>()
+          .join("\n")
+      )
+      .context(ErrorKind::InvalidConfig),
+    )
+  }
+}
+
+/// The tagged files added to the resources of the app target, with every tag of each file.
+pub fn tagged_sources(tauri_config: &TauriConfig) -> Vec<TaggedSource> {
+  let Some(resources) = &tauri_config.bundle.ios.on_demand_resources else {
+    return Vec::new();
+  };
+  let mut sources: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+  for (tag, paths) in &resources.tags {
+    for path in paths {
+      sources
+        .entry(path.to_string_lossy().into_owned())
+        .or_default()
+        .insert(tag.clone());
+    }
+  }
+  sources
+    .into_iter()
+    .map(|(path, tags)| TaggedSource {
+      path,
+      tags: tags.into_iter().collect(),
+    })
+    .collect()
+}
+
+/// The build settings enabling On-Demand Resources and declaring the download category of the tags.
+pub fn build_settings(tauri_config: &TauriConfig) -> BTreeMap<&'static str, String> {
+  let mut settings = BTreeMap::new();
+  let Some(resources) = &tauri_config.bundle.ios.on_demand_resources else {
+    return settings;
+  };
+  settings.insert("ENABLE_ON_DEMAND_RESOURCES", "YES".into());
+  if !resources.initial_install.is_empty() {
+    settings.insert(
+      "ON_DEMAND_RESOURCES_INITIAL_INSTALL_TAGS",
+      resources.initial_install.join(" "),
+    );
+  }
+  if !resources.prefetch.is_empty() {
+    settings.insert(
+      "ON_DEMAND_RESOURCES_PREFETCH_ORDER",
+      resources.prefetch.join(" "),
+    );
+  }
+  settings
+}
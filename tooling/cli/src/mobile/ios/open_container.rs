@@ -0,0 +1,124 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{booted_simulator, bundle_identifier, ensure_xcode, env, simctl, ErrorKind};
+use crate::{helpers::config::get as get_tauri_config, CommandExt, ConfigValue, Result};
+
+use anyhow::Context;
+use cargo_mobile2::apple::device;
+use clap::{Parser, ValueEnum};
+
+use std::{path::PathBuf, process::Command};
+
+#[derive(Debug, Clone, Parser)]
+#[clap(
+  about = "Open the container of the app installed on a simulator in Finder",
+  long_about = "Open the data container, with the Documents, Library and tmp directories of the app, or the app bundle installed on a simulator in Finder.
+Defaults to the booted simulator. Physical devices are not supported."
+)]
+pub struct Options {
+  /// The simulator name or UDID. Defaults to the booted simulator.
+  #[clap(env = "TAURI_IOS_DEVICE")]
+  pub device: Option<String>,
+  /// JSON string or path to JSON file to merge with tauri.conf.json
+  #[clap(short, long)]
+  pub config: Option<ConfigValue>,
+  /// Open the container of the app with this bundle identifier instead of the configured `identifier`.
+  #[clap(long)]
+  pub bundle_id: Option<String>,
+  /// The container to open.
+  #[clap(long, value_enum, default_value_t = Container::Data)]
+  pub container: Container,
+  /// Print the container path instead of opening it.
+  #[clap(long)]
+  pub print_path: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Container {
+  /// The data container, holding the files written by the app.
+  Data,
+  /// The installed app bundle.
+  App,
+}
+
+impl std::fmt::Display for Container {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Data => write!(f, "data"),
+      Self::App => write!(f, "app"),
+    }
+  }
+}
+
+pub fn command(options: Options) -> Result<()> {
+  ensure_xcode()?;
+  let bundle_id = match options.bundle_id.clone() {
+    Some(bundle_id) => bundle_id,
+    None => {
+      let tauri_config = get_tauri_config(
+        tauri_utils::platform::Target::Ios,
+        options.config.as_ref().map(|c| &c.0),
+      )
+      .context(ErrorKind::InvalidConfig)?;
+      let tauri_config_guard = tauri_config.lock().unwrap();
+      bundle_identifier(tauri_config_guard.as_ref().unwrap())
+    }
+  };
+
+  let simulator = match &options.device {
+    Some(device) => find_simulator(device)?,
+    None => booted_simulator()?,
+  };
+  // simctl cannot query the apps of a shut down simulator
+  simctl::wait_booted(&simulator)?;
+
+  let output = Command::new("xcrun")
+    .args([
+      "simctl",
+      "get_app_container",
+      simulator.udid.as_str(),
+      bundle_id.as_str(),
+    ])
+    .arg(options.container.to_string())
+    .output_ok()
+    .with_context(|| format!("{bundle_id} is not installed on simulator {simulator}"))
+    .context(ErrorKind::NoDevice)?;
+  let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+
+  if options.print_path {
+    println!("{}", path.display());
+    return Ok(());
+  }
+
+  log::info!(action = "Opening"; "{} container of {bundle_id} at {}", options.container, path.display());
+  Command::new("open")
+    .arg(&path)
+    .output_ok()
+    .with_context(|| format!("failed to open {}", path.display()))?;
+  Ok(())
+}
+
+/// Finds the simulator with the given name or UDID, failing with a clear error for physical devices.
+fn find_simulator(device: &str) -> Result<simctl::Simulator> {
+  if let Ok(simulator) = simctl::find_udid(device).or_else(|_| simctl::find(device)) {
+    return Ok(simulator);
+  }
+  let is_physical_device = env()
+    .ok()
+    .and_then(|env| device::list_devices(&env).ok())
+    .is_some_and(|devices| {
+      devices
+        .iter()
+        .any(|d| d.id() == device || d.name().eq_ignore_ascii_case(device))
+    });
+  let error = if is_physical_device {
+    anyhow::anyhow!(
+      "{device} is a physical device, app containers can only be opened on simulators"
+    )
+  } else {
+    anyhow::anyhow!("Could not find an iOS Simulator named {device}")
+  };
+  Err(error.context(ErrorKind::NoDevice))
+}
@@ -0,0 +1,123 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Prints the xcodebuild invocations of `--print-command` instead of running them.
+//!
+//! The commands are run by cargo-mobile2, so they are reconstructed here to match its arguments.
+
+use cargo_mobile2::{
+  apple::{config::Config as AppleConfig, target::Target},
+  opts::Profile,
+};
+
+use super::configuration_name;
+
+use std::{collections::HashMap, ffi::OsString};
+
+/// Environment variable names containing any of these words have their value redacted.
+const SECRET_ENV_VAR_MARKERS: &[&str] = &[
+  "PASSWORD",
+  "SECRET",
+  "TOKEN",
+  "KEY",
+  "CERTIFICATE",
+  "PROVISION",
+];
+
+/// The `xcodebuild build` arguments used for the target.
+pub fn build_args(config: &AppleConfig, target: &Target, profile: Profile) -> Vec<OsString> {
+  let mut args = common_args(config, target, profile);
+  args.extend(["-allowProvisioningUpdates".into(), "build".into()]);
+  args
+}
+
+/// The `xcodebuild archive` arguments used for the target.
+pub fn archive_args(
+  config: &AppleConfig,
+  target: &Target,
+  profile: Profile,
+  build_number: Option<String>,
+) -> Vec<OsString> {
+  let mut args = common_args(config, target, profile);
+  if let Some(build_number) = build_number {
+    args.push(format!("CURRENT_PROJECT_VERSION={build_number}").into());
+  }
+  args.extend([
+    "-allowProvisioningUpdates".into(),
+    "archive".into(),
+    "-archivePath".into(),
+    archive_path(config),
+  ]);
+  args
+}
+
+/// The `xcodebuild -exportArchive` arguments exporting the archive to an IPA.
+pub fn export_args(config: &AppleConfig) -> Vec<OsString> {
+  vec![
+    "-exportArchive".into(),
+    "-archivePath".into(),
+    archive_path(config),
+    "-exportOptionsPlist".into(),
+    config.project_dir().join("ExportOptions.plist").into(),
+    "-exportPath".into(),
+    config.export_dir().into(),
+  ]
+}
+
+fn common_args(config: &AppleConfig, target: &Target, profile: Profile) -> Vec<OsString> {
+  vec![
+    "-scheme".into(),
+    config.scheme().into(),
+    "-workspace".into(),
+    config.workspace_path().into(),
+    "-sdk".into(),
+    target.sdk.into(),
+    "-configuration".into(),
+    configuration_name(profile).into(),
+  ]
+}
+
+fn archive_path(config: &AppleConfig) -> OsString {
+  config
+    .archive_dir()
+    .join(format!("{}.xcarchive", config.scheme()))
+    .into()
+}
+
+/// Prints the xcodebuild command line, prefixed with the environment variables Tauri sets for it.
+pub fn print(env: &HashMap<String, OsString>, args: &[OsString]) {
+  let mut vars = env.iter().collect::<Vec<_>>();
+  vars.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+  let mut line = Vec::new();
+  for (key, value) in vars {
+    let upper = key.to_ascii_uppercase();
+    let value = if SECRET_ENV_VAR_MARKERS
+      .iter()
+      .any(|marker| upper.contains(marker))
+    {
+      "<redacted>".to_string()
+    } else {
+      quote(&value.to_string_lossy())
+    };
+    line.push(format!("{key}={value}"));
+  }
+  line.push("xcodebuild".into());
+  line.extend(args.iter().map(|arg| quote(&arg.to_string_lossy())));
+
+  println!("{}", line.join(" "));
+}
+
+/// Quotes the argument for POSIX shells when needed.
+fn quote(arg: &str) -> String {
+  if !arg.is_empty()
+    && arg
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c))
+  {
+    arg.to_string()
+  } else {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+  }
+}
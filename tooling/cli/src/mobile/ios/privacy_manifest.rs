@@ -0,0 +1,330 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Generates the `PrivacyInfo.xcprivacy` privacy manifest from the `bundle > iOS > privacyManifest` configuration.
+
+use super::{find_xcode_file, ErrorKind};
+use crate::{helpers::config::Config as TauriConfig, Result};
+
+use anyhow::Context;
+use cargo_mobile2::apple::config::Config as AppleConfig;
+use tauri_utils::config::IosPrivacyManifest;
+
+use std::{fs::create_dir_all, path::PathBuf};
+
+const PRIVACY_MANIFEST_FILE_NAME: &str = "PrivacyInfo.xcprivacy";
+
+/// The required reason API categories and their approved reason codes.
+///
+/// See <https://developer.apple.com/documentation/bundleresources/privacy_manifest_files/describing_use_of_required_reason_api>.
+const API_REASONS: &[(&str, &[&str])] = &[
+  (
+    "NSPrivacyAccessedAPICategoryFileTimestamp",
+    &["DDA9.1", "C617.1", "3B52.1", "0A2A.1"],
+  ),
+  (
+    "NSPrivacyAccessedAPICategorySystemBootTime",
+    &["35F9.1", "8FFB.1", "3D61.1"],
+  ),
+  (
+    "NSPrivacyAccessedAPICategoryDiskSpace",
+    &["85F4.1", "E174.1", "7D9E.1", "B728.1"],
+  ),
+  (
+    "NSPrivacyAccessedAPICategoryActiveKeyboards",
+    &["3EC4.1", "54BD.1"],
+  ),
+  (
+    "NSPrivacyAccessedAPICategoryUserDefaults",
+    &["CA92.1", "1C8F.1", "C56D.1", "AC6B.1"],
+  ),
+];
+
+/// The collected data types, without the `NSPrivacyCollectedDataType` prefix.
+const DATA_TYPES: &[&str] = &[
+  "Name",
+  "EmailAddress",
+  "PhoneNumber",
+  "PhysicalAddress",
+  "OtherUserContactInfo",
+  "Health",
+  "Fitness",
+  "PaymentInfo",
+  "CreditInfo",
+  "OtherFinancialInfo",
+  "PreciseLocation",
+  "CoarseLocation",
+  "SensitiveInfo",
+  "Contacts",
+  "EmailsOrTextMessages",
+  "PhotosorVideos",
+  "AudioData",
+  "GameplayContent",
+  "CustomerSupport",
+  "OtherUserContent",
+  "BrowsingHistory",
+  "SearchHistory",
+  "UserID",
+  "DeviceID",
+  "PurchaseHistory",
+  "ProductInteraction",
+  "AdvertisingData",
+  "OtherUsageData",
+  "CrashData",
+  "PerformanceData",
+  "OtherDiagnosticData",
+  "EnvironmentScanning",
+  "Hands",
+  "Head",
+  "OtherDataTypes",
+];
+
+/// The purposes of the collected data, without the `NSPrivacyCollectedDataTypePurpose` prefix.
+const DATA_PURPOSES: &[&str] = &[
+  "ThirdPartyAdvertising",
+  "DeveloperAdvertising",
+  "Analytics",
+  "ProductPersonalization",
+  "AppFunctionality",
+  "Other",
+];
+
+/// Checks the data types, purposes, API categories and reason codes against the values known to Apple.
+pub fn validate(manifest: &IosPrivacyManifest) -> Result<()> {
+  let mut errors = Vec::new();
+
+  for data_type in &manifest.collected_data_types {
+    if !data_type
+      .data_type
+      .strip_prefix("NSPrivacyCollectedDataType")
+      .is_some_and(|name| DATA_TYPES.contains(&name))
+    {
+      errors.push(format!(
+        "unknown collected data type `{}`",
+        data_type.data_type
+      ));
+    }
+    if data_type.purposes.is_empty() {
+      errors.push(format!(
+        "the collected data type `{}` must declare at least one purpose",
+        data_type.data_type
+      ));
+    }
+    for purpose in &data_type.purposes {
+      if !purpose
+        .strip_prefix("NSPrivacyCollectedDataTypePurpose")
+        .is_some_and(|name| DATA_PURPOSES.contains(&name))
+      {
+        errors.push(format!(
+          "unknown purpose `{purpose}` for the collected data type `{}`",
+          data_type.data_type
+        ));
+      }
+    }
+  }
+
+  for api_type in &manifest.accessed_api_types {
+    let Some((_, reasons)) = API_REASONS
+      .iter()
+      .find(|(category, _)| *category == api_type.api_type)
+    else {
+      errors.push(format!(
+        "unknown API category `{}`, expected one of {}",
+        api_type.api_type,
+        API_REASONS
+          .iter()
+          .map(|(category, _)| format!("`{category}`"))
+          .collect::<Vec<_>>()
+          .join(", ")
+      ));
+      continue;
+    };
+    if api_type.reasons.is_empty() {
+      errors.push(format!(
+        "the API category `{}` must declare at least one reason",
+        api_type.api_type
+      ));
+    }
+    for reason in &api_type.reasons {
+      if !reasons.contains(&reason.as_str()) {
+        errors.push(format!(
+          "invalid reason `{reason}` for the API category `{}`, expected one of {}",
+          api_type.api_type,
+          reasons.join(", ")
+        ));
+      }
+    }
+  }
+
+  if errors.is_empty() {
+    Ok(())
+  } else {
+    Err(
+      anyhow::anyhow!(
+        "invalid `bundle > iOS > privacyManifest` configuration:\n{}",
+        errors
+          .iter()
+          .map(|error| format!("- {error}"))
+          .collect::<Vec<_>>()
+          .join("\n")
+      )
+      .context(ErrorKind::InvalidConfig),
+    )
+  }
+}
+
+/// Writes the `bundle > iOS > privacyManifest` configuration to the `PrivacyInfo.xcprivacy` of the Xcode project.
+///
+/// The file is replaced since the configuration is its source of truth. Nothing is written without the configuration.
+pub fn write(config: &AppleConfig, tauri_config: &TauriConfig) -> Result<()> {
+  let Some(manifest) = &tauri_config.bundle.ios.privacy_manifest else {
+    return Ok(());
+  };
+  let path = manifest_path(config);
+  create_dir_all(path.parent().unwrap())?;
+  plist::Value::from(to_plist(manifest))
+    .to_file_xml(&path)
+    .with_context(|| format!("failed to write {}", path.display()))?;
+  Ok(())
+}
+
+/// Writes the privacy manifest and warns when it is missing from the Xcode project,
+/// since Xcode only bundles the files referenced by the project generated on `tauri ios init`.
+pub fn sync(config: &AppleConfig, tauri_config: &TauriConfig) -> Result<()> {
+  if tauri_config.bundle.ios.privacy_manifest.is_none() {
+    return Ok(());
+  }
+  write(config, tauri_config)?;
+
+  let referenced = find_xcode_file(&config.project_dir(), "xcodeproj")
+    .and_then(|project| std::fs::read_to_string(project.join("project.pbxproj")).ok())
+    .is_some_and(|project| project.contains(PRIVACY_MANIFEST_FILE_NAME));
+  if !referenced {
+    log::warn!(
+      "{} is not part of the Xcode project and will not be bundled with the app. Run `tauri ios init` to regenerate the project",
+      manifest_path(config).display()
+    );
+  }
+  Ok(())
+}
+
+fn manifest_path(config: &AppleConfig) -> PathBuf {
+  config
+    .project_dir()
+    .join(config.scheme())
+    .join(PRIVACY_MANIFEST_FILE_NAME)
+}
+
+fn to_plist(manifest: &IosPrivacyManifest) -> plist::Dictionary {
+  let strings = |values: &[String]| {
+    values
+      .iter()
+      .map(|value| plist::Value::from(value.clone()))
+      .collect::<Vec<_>>()
+  };
+
+  let mut plist = plist::Dictionary::new();
+  plist.insert("NSPrivacyTracking".into(), manifest.tracking.into());
+  plist.insert(
+    "NSPrivacyTrackingDomains".into(),
+    strings(&manifest.tracking_domains).into(),
+  );
+  plist.insert(
+    "NSPrivacyCollectedDataTypes".into(),
+    manifest
+      .collected_data_types
+      .iter()
+      .map(|data_type| {
+        let mut dict = plist::Dictionary::new();
+        dict.insert(
+          "NSPrivacyCollectedDataType".into(),
+          data_type.data_type.clone().into(),
+        );
+        dict.insert(
+          "NSPrivacyCollectedDataTypeLinked".into(),
+          data_type.linked.into(),
+        );
+        dict.insert(
+          "NSPrivacyCollectedDataTypeTracking".into(),
+          data_type.tracking.into(),
+        );
+        dict.insert(
+          "NSPrivacyCollectedDataTypePurposes".into(),
+          strings(&data_type.purposes).into(),
+        );
+        plist::Value::from(dict)
+      })
+      .collect::<Vec<_>>()
+      .into(),
+  );
+  plist.insert(
+    "NSPrivacyAccessedAPITypes".into(),
+    manifest
+      .accessed_api_types
+      .iter()
+      .map(|api_type| {
+        let mut dict = plist::Dictionary::new();
+        dict.insert(
+          "NSPrivacyAccessedAPIType".into(),
+          api_type.api_type.clone().into(),
+        );
+        dict.insert(
+          "NSPrivacyAccessedAPITypeReasons".into(),
+          strings(&api_type.reasons).into(),
+        );
+        plist::Value::from(dict)
+      })
+      .collect::<Vec<_>>()
+      .into(),
+  );
+  plist
+}
+
+#[cfg(test)]
+mod tests {
+  use super::validate;
+  use tauri_utils::config::IosPrivacyManifest;
+
+  fn manifest(json: serde_json::Value) -> IosPrivacyManifest {
+    serde_json::from_value(json).unwrap()
+  }
+
+  #[test]
+  fn accepts_known_values() {
+    let manifest = manifest(serde_json::json!({
+      "collectedDataTypes": [{
+        "type": "NSPrivacyCollectedDataTypeCrashData",
+        "purposes": ["NSPrivacyCollectedDataTypePurposeAppFunctionality"],
+      }],
+      "accessedApiTypes": [{
+        "type": "NSPrivacyAccessedAPICategoryUserDefaults",
+        "reasons": ["CA92.1"],
+      }],
+    }));
+    assert!(validate(&manifest).is_ok());
+  }
+
+  #[test]
+  fn reports_every_unknown_value() {
+    let manifest = manifest(serde_json::json!({
+      "collectedDataTypes": [
+        { "type": "NSPrivacyCollectedDataTypeShoeSize", "purposes": ["NSPrivacyCollectedDataTypePurposeAnalytics"] },
+        { "type": "NSPrivacyCollectedDataTypeCrashData", "purposes": [] },
+      ],
+      "accessedApiTypes": [
+        { "type": "NSPrivacyAccessedAPICategoryDiskSpace", "reasons": ["CA92.1"] },
+        { "type": "NSPrivacyAccessedAPICategoryCamera", "reasons": ["CA92.1"] },
+      ],
+    }));
+    let error = format!("{:#}", validate(&manifest).unwrap_err());
+    for expected in [
+      "unknown collected data type `NSPrivacyCollectedDataTypeShoeSize`",
+      "`NSPrivacyCollectedDataTypeCrashData` must declare at least one purpose",
+      "invalid reason `CA92.1` for the API category `NSPrivacyAccessedAPICategoryDiskSpace`",
+      "unknown API category `NSPrivacyAccessedAPICategoryCamera`",
+    ] {
+      assert!(error.contains(expected), "{error}");
+    }
+  }
+}
@@ -0,0 +1,113 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::ErrorKind;
+use crate::Result;
+
+use anyhow::Context;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use tauri_macos_sign::ProvisioningProfile;
+
+#[derive(Debug, Parser)]
+#[clap(
+  about = "Manage the installed provisioning profiles",
+  subcommand_required(true),
+  arg_required_else_help(true)
+)]
+pub struct Cli {
+  #[clap(subcommand)]
+  command: Commands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+  List(ListOptions),
+}
+
+#[derive(Debug, Parser)]
+#[clap(about = "List the installed provisioning profiles")]
+pub struct ListOptions {
+  /// Print the profiles as JSON.
+  #[clap(long)]
+  json: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileInfo {
+  name: String,
+  app_id: String,
+  #[serde(rename = "type")]
+  profile_type: String,
+  platforms: Vec<String>,
+  uuid: String,
+  team_ids: Vec<String>,
+  expiration_date: String,
+  expired: bool,
+}
+
+impl ProfileInfo {
+  fn read(profile: &ProvisioningProfile) -> Result<Self> {
+    Ok(Self {
+      name: profile.name()?,
+      app_id: profile.application_identifier()?,
+      profile_type: profile.profile_type()?.to_string(),
+      platforms: profile.platforms()?,
+      uuid: profile.uuid()?,
+      team_ids: profile.team_ids()?,
+      expiration_date: profile.expiration_date()?,
+      expired: profile.is_expired()?,
+    })
+  }
+}
+
+pub fn command(cli: Cli) -> Result<()> {
+  match cli.command {
+    Commands::List(options) => list(options),
+  }
+}
+
+fn list(options: ListOptions) -> Result<()> {
+  let mut profiles = Vec::new();
+  for profile in ProvisioningProfile::installed()
+    .context("failed to list the installed provisioning profiles")
+    .context(ErrorKind::SigningMissing)?
+  {
+    match ProfileInfo::read(&profile) {
+      Ok(info) => profiles.push(info),
+      Err(e) => log::warn!(
+        "Skipping provisioning profile {}: {e}",
+        profile.path().display()
+      ),
+    }
+  }
+  profiles.sort_by(|a, b| a.name.cmp(&b.name));
+
+  if options.json {
+    println!("{}", serde_json::to_string_pretty(&profiles)?);
+    return Ok(());
+  }
+
+  if profiles.is_empty() {
+    println!("No provisioning profiles installed");
+  }
+  for profile in profiles {
+    println!("{}", profile.name);
+    println!("  App ID: {}", profile.app_id);
+    println!("  Type: {}", profile.profile_type);
+    if !profile.platforms.is_empty() {
+      println!("  Platforms: {}", profile.platforms.join(", "));
+    }
+    println!("  UUID: {}", profile.uuid);
+    println!("  Team: {}", profile.team_ids.join(", "));
+    println!(
+      "  Expiration: {}{}",
+      profile.expiration_date,
+      if profile.expired { " (expired)" } else { "" }
+    );
+  }
+
+  Ok(())
+}
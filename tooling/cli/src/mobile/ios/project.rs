@@ -19,10 +19,10 @@ use include_dir::{include_dir, Dir};
 use std::{
   ffi::OsString,
   fs::{create_dir_all, OpenOptions},
-  path::{Component, PathBuf},
+  path::{Component, Path, PathBuf},
 };
 
-const TEMPLATE_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/templates/mobile/ios");
+pub const TEMPLATE_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/templates/mobile/ios");
 
 // unprefixed app_root seems pretty dangerous!!
 // TODO: figure out what cargo-mobile meant by that
@@ -59,6 +59,7 @@ pub fn gen(
     .with_context(|| "failed to install Apple dependencies")?;
 
   let dest = config.project_dir();
+  super::ensure_project_dir_writable(&dest)?;
   let rel_prefix = util::relativize_path(config.app().root_dir(), &dest);
   let source_dirs = vec![rel_prefix.join("src")];
 
@@ -210,3 +211,15 @@ pub fn gen(
   }
   Ok(())
 }
+
+/// Runs the `bundle > iOS > postInitScript` command in the generated project directory.
+pub fn run_post_init_script(script: &str, project_dir: &Path) -> Result<()> {
+  log::info!(action = "Running"; "post init script `{script}`");
+  duct::cmd("sh", ["-c", script])
+    .dir(project_dir)
+    .stdout_file(os_pipe::dup_stdout().unwrap())
+    .stderr_file(os_pipe::dup_stderr().unwrap())
+    .run()
+    .with_context(|| format!("failed to run post init script `{script}`"))?;
+  Ok(())
+}
@@ -0,0 +1,154 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Reads the certificate and provisioning profile from the `IOS_SIGNING_BUNDLE` archive,
+//! see [`SigningBundle::load`].
+
+use crate::{CommandExt, Result};
+
+use anyhow::Context;
+use base64::Engine;
+
+use std::{
+  env::var_os,
+  ffi::{OsStr, OsString},
+  fs::create_dir_all,
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+/// Names of the file containing the certificate password in a signing bundle.
+const SIGNING_BUNDLE_PASSWORD_FILES: &[&str] = &["password", "password.txt"];
+
+/// The certificate and provisioning profile extracted from the `IOS_SIGNING_BUNDLE` archive.
+pub struct SigningBundle {
+  pub keychain: tauri_macos_sign::Keychain,
+  pub provisioning_profile: tauri_macos_sign::ProvisioningProfile,
+}
+
+impl SigningBundle {
+  /// Unpacks the bundle, given as a path to a tar or zip archive or as its base64 encoded contents.
+  ///
+  /// The archive must contain a single `.p12` certificate and a single `.mobileprovision` profile.
+  /// The certificate password is read from `IOS_CERTIFICATE_PASSWORD`
+  /// or from a `password` or `password.txt` file in the archive.
+  pub fn load(bundle: &OsStr) -> Result<Self> {
+    let work_dir =
+      std::env::temp_dir().join(format!("tauri-ios-signing-bundle-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&work_dir);
+    let contents_dir = work_dir.join("contents");
+    create_dir_all(&contents_dir)?;
+    let bundle = Self::extract(bundle, &work_dir, &contents_dir);
+    let _ = std::fs::remove_dir_all(&work_dir);
+    bundle
+  }
+
+  fn extract(bundle: &OsStr, work_dir: &Path, contents_dir: &Path) -> Result<Self> {
+    let bundle_path = PathBuf::from(bundle);
+    let archive_path = if bundle_path.is_file() {
+      bundle_path
+    } else {
+      let encoded = bundle
+        .to_str()
+        .context("IOS_SIGNING_BUNDLE is neither an existing file nor valid base64")?
+        .split_whitespace()
+        .collect::<String>();
+      let decoded = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .context("IOS_SIGNING_BUNDLE is neither an existing file nor valid base64")?;
+      let archive_path = work_dir.join("bundle");
+      std::fs::write(&archive_path, decoded)?;
+      archive_path
+    };
+
+    // bsdtar handles both tar (optionally compressed) and zip archives
+    Command::new("tar")
+      .arg("-xf")
+      .arg(&archive_path)
+      .arg("-C")
+      .arg(contents_dir)
+      .output_ok()
+      .context("failed to unpack the IOS_SIGNING_BUNDLE archive, expected a tar or zip file")?;
+
+    let mut certificates = Vec::new();
+    let mut profiles = Vec::new();
+    let mut password_file = None;
+    for entry in walkdir::WalkDir::new(contents_dir)
+      .into_iter()
+      .flatten()
+      .filter(|entry| entry.file_type().is_file())
+    {
+      let path = entry.path().to_path_buf();
+      let file_name = entry.file_name().to_string_lossy();
+      if file_name.starts_with("._") {
+        // AppleDouble metadata files created when archiving on macOS
+        continue;
+      }
+      if path.extension().is_some_and(|ext| ext == "p12") {
+        certificates.push(path);
+      } else if path.extension().is_some_and(|ext| ext == "mobileprovision") {
+        profiles.push(path);
+      } else if SIGNING_BUNDLE_PASSWORD_FILES.contains(&file_name.as_ref()) {
+        password_file.replace(path);
+      }
+    }
+
+    let certificate = Self::single(certificates, "`.p12` certificate")?;
+    let profile = Self::single(profiles, "`.mobileprovision` provisioning profile")?;
+    let password = match var_os("IOS_CERTIFICATE_PASSWORD") {
+      Some(password) => password,
+      None => {
+        let path = password_file.with_context(|| {
+          format!(
+            "the IOS_SIGNING_BUNDLE archive does not contain a {} file and IOS_CERTIFICATE_PASSWORD is not set",
+            SIGNING_BUNDLE_PASSWORD_FILES.join(" or ")
+          )
+        })?;
+        std::fs::read_to_string(&path)
+          .with_context(|| format!("failed to read {}", path.display()))?
+          .trim_end_matches(['\r', '\n'])
+          .into()
+      }
+    };
+
+    let encode = |path: &Path| -> Result<OsString> {
+      let contents =
+        std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+      Ok(
+        base64::engine::general_purpose::STANDARD
+          .encode(contents)
+          .into(),
+      )
+    };
+
+    let keychain = tauri_macos_sign::Keychain::with_certificate(&encode(&certificate)?, &password)
+      .context("failed to import the IOS_SIGNING_BUNDLE certificate")?;
+    let provisioning_profile =
+      tauri_macos_sign::ProvisioningProfile::from_base64(&encode(&profile)?)
+        .context("failed to install the IOS_SIGNING_BUNDLE provisioning profile")?;
+    provisioning_profile
+      .uuid()
+      .context("the IOS_SIGNING_BUNDLE provisioning profile is not a valid provisioning profile")?;
+
+    Ok(Self {
+      keychain,
+      provisioning_profile,
+    })
+  }
+
+  fn single(mut files: Vec<PathBuf>, description: &str) -> Result<PathBuf> {
+    match files.len() {
+      0 => anyhow::bail!("the IOS_SIGNING_BUNDLE archive does not contain a {description}"),
+      1 => Ok(files.remove(0)),
+      _ => anyhow::bail!(
+        "the IOS_SIGNING_BUNDLE archive contains more than one {description}: {}",
+        files
+          .iter()
+          .map(|file| file.file_name().unwrap().to_string_lossy())
+          .collect::<Vec<_>>()
+          .join(", ")
+      ),
+    }
+  }
+}
@@ -0,0 +1,127 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Detects when an unchanged app was already archived and signed with the same signing inputs, so `tauri ios build`
+//! can reuse the exported IPA instead of signing it again. See the `--force-sign` flag.
+
+use crate::Result;
+
+use anyhow::Context;
+
+use std::{
+  collections::hash_map::DefaultHasher,
+  fs::{read, read_to_string, write},
+  hash::{Hash, Hasher},
+  path::{Path, PathBuf},
+  time::SystemTime,
+};
+
+/// Stored next to the exported IPA, holding the fingerprint and the IPA file name.
+const FINGERPRINT_FILE_NAME: &str = ".signing-fingerprint";
+
+/// The fingerprint of the signing inputs of an archive and its export.
+pub struct Fingerprint(u64);
+
+impl Fingerprint {
+  /// Hashes the signing inputs, e.g. the signing identity, with the content of the signing files,
+  /// e.g. the entitlements and the export options.
+  pub fn compute(inputs: &[&str], files: &[&Path]) -> Self {
+    // the hasher may change between Rust releases, which only causes a new signing
+    let mut hasher = DefaultHasher::new();
+    inputs.hash(&mut hasher);
+    for file in files {
+      read(file).ok().hash(&mut hasher);
+    }
+    Self(hasher.finish())
+  }
+
+  /// The IPA exported by the last successful build with the same fingerprint, if it still exists
+  /// and the app built by Xcode was not modified since it was exported.
+  pub fn exported_ipa(&self, out_dir: &Path, app: &Path) -> Option<PathBuf> {
+    let stored = read_to_string(out_dir.join(FINGERPRINT_FILE_NAME)).ok()?;
+    let (fingerprint, file_name) = stored.trim().split_once(' ')?;
+    if fingerprint != self.to_string() {
+      return None;
+    }
+    let ipa = out_dir.join(file_name);
+    let exported_at = ipa.metadata().ok()?.modified().ok()?;
+    (ipa.is_file() && !modified_since(app, exported_at)).then_some(ipa)
+  }
+
+  /// Records the fingerprint of the exported IPA.
+  pub fn store(&self, ipa: &Path) -> Result<()> {
+    let (Some(out_dir), Some(file_name)) = (ipa.parent(), ipa.file_name()) else {
+      return Ok(());
+    };
+    let path = out_dir.join(FINGERPRINT_FILE_NAME);
+    write(&path, format!("{self} {}", file_name.to_string_lossy()))
+      .with_context(|| format!("failed to write {}", path.display()))
+  }
+}
+
+/// Whether a file of the app was modified after `time`, or the app does not exist.
+/// Only the modification times are compared, the files are not read.
+fn modified_since(app: &Path, time: SystemTime) -> bool {
+  !app.exists()
+    || walkdir::WalkDir::new(app).into_iter().any(|entry| {
+      entry
+        .ok()
+        .and_then(|entry| entry.metadata().ok())
+        .and_then(|metadata| metadata.modified().ok())
+        .map_or(true, |modified| modified > time)
+    })
+}
+
+impl std::fmt::Display for Fingerprint {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:016x}", self.0)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Fingerprint;
+
+  use std::fs::{create_dir_all, remove_dir_all, write};
+
+  #[test]
+  fn reuses_the_ipa_of_the_same_fingerprint() {
+    let dir = std::env::temp_dir().join(format!(
+      "tauri-signing-fingerprint-test-{}",
+      std::process::id()
+    ));
+    let _ = remove_dir_all(&dir);
+    let (out_dir, app) = (dir.join("out"), dir.join("App.app"));
+    create_dir_all(&out_dir).unwrap();
+    create_dir_all(&app).unwrap();
+    write(app.join("App"), "binary").unwrap();
+    let entitlements = dir.join("App.entitlements");
+    write(&entitlements, "<plist/>").unwrap();
+
+    let ipa = out_dir.join("App.ipa");
+    write(&ipa, "ipa").unwrap();
+    let fingerprint = Fingerprint::compute(&["identity", "profile"], &[&entitlements]);
+    fingerprint.store(&ipa).unwrap();
+
+    assert_eq!(fingerprint.exported_ipa(&out_dir, &app), Some(ipa.clone()));
+    assert_eq!(
+      Fingerprint::compute(&["other identity", "profile"], &[&entitlements])
+        .exported_ipa(&out_dir, &app),
+      None
+    );
+
+    // the content of the signing files is part of the fingerprint
+    write(&entitlements, "<plist><dict/></plist>").unwrap();
+    assert_eq!(
+      Fingerprint::compute(&["identity", "profile"], &[&entitlements]).exported_ipa(&out_dir, &app),
+      None
+    );
+
+    // the app was rebuilt without being exported
+    remove_dir_all(&app).unwrap();
+    assert_eq!(fingerprint.exported_ipa(&out_dir, &app), None);
+
+    let _ = remove_dir_all(&dir);
+  }
+}
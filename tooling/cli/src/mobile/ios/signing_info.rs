@@ -0,0 +1,95 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{get_app, get_config, init_config, signing_from_env, ErrorKind};
+use crate::{
+  helpers::config::get as get_tauri_config,
+  interface::{AppInterface, Interface},
+  mobile::init::CodeSignStyle,
+  ConfigValue, Result,
+};
+
+use anyhow::Context;
+use cargo_mobile2::opts::Profile;
+use clap::Parser;
+
+#[derive(Debug, Clone, Parser)]
+#[clap(
+  about = "Print the iOS signing identity and provisioning profile",
+  long_about = "Print the iOS signing identity and provisioning profile that would be used to build the app.
+The values are resolved from the environment variables and the Tauri configuration the same way `tauri ios build` does.
+Secrets such as the certificate password are never printed."
+)]
+pub struct Options {
+  /// JSON string or path to JSON file to merge with tauri.conf.json
+  #[clap(short, long)]
+  pub config: Option<ConfigValue>,
+}
+
+pub fn command(options: Options) -> Result<()> {
+  let tauri_config = get_tauri_config(
+    tauri_utils::platform::Target::Ios,
+    options.config.as_ref().map(|c| &c.0),
+  )
+  .context(ErrorKind::InvalidConfig)?;
+  let tauri_config_guard = tauri_config.lock().unwrap();
+  let tauri_config_ = tauri_config_guard.as_ref().unwrap();
+  let interface = AppInterface::new(tauri_config_, None)?;
+  let app = get_app(tauri_config_, &interface);
+  // `tauri ios build` uses the release configuration by default
+  let (config, _metadata) = get_config(
+    &app,
+    tauri_config_,
+    None,
+    &Default::default(),
+    Some(Profile::Release),
+    Default::default(),
+  )?;
+
+  let (keychain, provisioning_profile) = signing_from_env(tauri_config_, false)?;
+  let init_config = init_config(keychain.as_ref(), provisioning_profile.as_ref())?;
+
+  let team_id = init_config
+    .team_id
+    .clone()
+    .or_else(|| config.development_team().map(ToString::to_string))
+    .ok_or_else(|| {
+      anyhow::anyhow!(
+        "could not resolve the iOS signing configuration: no development team or signing certificate found"
+      )
+      .context(ErrorKind::SigningMissing)
+    })?;
+
+  println!(
+    "Signing style: {}",
+    match init_config.code_sign_style {
+      CodeSignStyle::Manual => "manual",
+      CodeSignStyle::Automatic => "automatic",
+    }
+  );
+  println!(
+    "Signing identity: {}",
+    init_config
+      .code_sign_identity
+      .as_deref()
+      .unwrap_or("managed by Xcode")
+  );
+  println!("Team ID: {team_id}");
+
+  if let Some(provisioning_profile) = &provisioning_profile {
+    println!("Provisioning profile: {}", provisioning_profile.name()?);
+    println!(
+      "Provisioning profile UUID: {}",
+      provisioning_profile.uuid()?
+    );
+    println!(
+      "Provisioning profile expiration: {}",
+      provisioning_profile.expiration_date()?
+    );
+  } else {
+    println!("Provisioning profile: managed by Xcode");
+  }
+
+  Ok(())
+}
@@ -0,0 +1,706 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Helpers around `xcrun simctl` for the simulator operations cargo-mobile2 does not expose.
+
+use crate::{CommandExt, Result};
+
+use anyhow::Context;
+use cargo_mobile2::apple::device;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use std::{
+  collections::HashMap,
+  fmt,
+  path::{Path, PathBuf},
+  process::Command,
+  time::{Duration, SystemTime},
+};
+
+/// How long the cached simulator list is reused for.
+const LIST_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Appearance {
+  Light,
+  Dark,
+}
+
+impl fmt::Display for Appearance {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Light => write!(f, "light"),
+      Self::Dark => write!(f, "dark"),
+    }
+  }
+}
+
+/// The privacy services whose permission can be reset with `xcrun simctl privacy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PrivacyService {
+  All,
+  Calendar,
+  ContactsLimited,
+  Contacts,
+  Location,
+  LocationAlways,
+  PhotosAdd,
+  Photos,
+  MediaLibrary,
+  Microphone,
+  Motion,
+  Reminders,
+  Siri,
+}
+
+impl fmt::Display for PrivacyService {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.to_possible_value().unwrap().get_name())
+  }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Simulator {
+  pub udid: String,
+  pub name: String,
+  pub state: String,
+  #[serde(default)]
+  pub device_type_identifier: String,
+  /// The runtime identifier, e.g. `com.apple.CoreSimulator.SimRuntime.iOS-16-4`.
+  /// simctl groups the simulators by runtime, so it is filled by [`list`].
+  #[serde(default)]
+  pub runtime: String,
+}
+
+impl fmt::Display for Simulator {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self.runtime_name() {
+      Some(runtime) => write!(f, "{} ({runtime})", self.name),
+      None => write!(f, "{}", self.name),
+    }
+  }
+}
+
+impl Simulator {
+  pub fn is_booted(&self) -> bool {
+    self.state == "Booted"
+  }
+
+  /// The human readable runtime name, e.g. `iOS 16.4`.
+  pub fn runtime_name(&self) -> Option<String> {
+    let (platform, version) = self.runtime.rsplit('.').next()?.split_once('-')?;
+    Some(format!("{platform} {}", version.replace('-', ".")))
+  }
+
+  /// Converts to the cargo-mobile2 simulator, which can only be created from the simctl JSON output.
+  pub fn to_device(&self) -> Result<device::Simulator> {
+    serde_json::from_value(serde_json::json!({
+      "name": self.name,
+      "udid": self.udid,
+    }))
+    .with_context(|| format!("failed to read simulator {}", self.name))
+  }
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListCache {
+  developer_dir: PathBuf,
+  created_at: SystemTime,
+  simulators: Vec<Simulator>,
+}
+
+#[derive(Deserialize)]
+struct DeviceList {
+  devices: HashMap<String, Vec<Simulator>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Runtime {
+  /// The runtime identifier, e.g. `com.apple.CoreSimulator.SimRuntime.iOS-16-4`.
+  pub identifier: String,
+  /// The human readable runtime name, e.g. `iOS 16.4`.
+  pub name: String,
+  /// The runtime version, e.g. `16.4`.
+  #[serde(default)]
+  pub version: String,
+}
+
+impl Runtime {
+  fn version_components(&self) -> Vec<u64> {
+    self
+      .version
+      .split('.')
+      .map(|component| component.parse().unwrap_or(0))
+      .collect()
+  }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceType {
+  /// The device type identifier, e.g. `com.apple.CoreSimulator.SimDeviceType.iPhone-15`.
+  pub identifier: String,
+  /// The human readable device type name, e.g. `iPhone 15`.
+  pub name: String,
+}
+
+#[derive(Deserialize)]
+struct DeviceTypeList {
+  devicetypes: Vec<DeviceType>,
+}
+
+#[derive(Deserialize)]
+struct RuntimeList {
+  runtimes: Vec<Runtime>,
+}
+
+/// Whether the runtime name, e.g. `iOS 16.4`, matches the requested runtime, e.g. `iOS 16.4` or `16.4`.
+pub fn runtime_matches(runtime_name: &str, requested: &str) -> bool {
+  fn version(runtime: &str) -> &str {
+    let runtime = runtime.trim();
+    match runtime.get(..3) {
+      Some(platform) if platform.eq_ignore_ascii_case("ios") => runtime[3..].trim(),
+      _ => runtime,
+    }
+  }
+  version(runtime_name) == version(requested)
+}
+
+fn simctl<I, S>(args: I) -> Result<Vec<u8>>
+where
+  I: IntoIterator<Item = S>,
+  S: AsRef<std::ffi::OsStr>,
+{
+  Command::new("xcrun")
+    .arg("simctl")
+    .args(args)
+    .output_ok()
+    .map(|output| output.stdout)
+}
+
+/// Lists the available iOS simulators.
+pub fn list() -> Result<Vec<Simulator>> {
+  let stdout = simctl(["list", "devices", "available", "--json"])?;
+  let list: DeviceList =
+    serde_json::from_slice(&stdout).context("failed to parse simctl device list")?;
+
+  Ok(
+    list
+      .devices
+      .into_iter()
+      .filter(|(runtime, _)| runtime.contains("iOS"))
+      .flat_map(|(runtime, devices)| {
+        devices.into_iter().map(move |device| Simulator {
+          runtime: runtime.clone(),
+          ..device
+        })
+      })
+      .collect(),
+  )
+}
+
+fn cache_dir() -> PathBuf {
+  std::env::var_os("HOME")
+    .map(|home| {
+      PathBuf::from(home)
+        .join("Library")
+        .join("Caches")
+        .join("tauri")
+    })
+    .unwrap_or_else(std::env::temp_dir)
+}
+
+fn list_cache_path() -> PathBuf {
+  cache_dir().join("ios-simulators.json")
+}
+
+fn created_simulators_path() -> PathBuf {
+  cache_dir().join("ios-created-simulators.json")
+}
+
+/// Lists the available iOS simulators, reusing the list cached by a previous run
+/// unless `refresh` is set, the cache expired or the active Xcode (`developer_dir`) changed.
+///
+/// The simulator states of a cached list are outdated, use [`find`] to read the current state.
+pub fn list_cached(developer_dir: &Path, refresh: bool) -> Result<Vec<Simulator>> {
+  let cache_path = list_cache_path();
+
+  if !refresh {
+    let cache = std::fs::read(&cache_path)
+      .ok()
+      .and_then(|cache| serde_json::from_slice::<ListCache>(&cache).ok());
+    if let Some(cache) = cache {
+      let fresh = cache
+        .created_at
+        .elapsed()
+        .is_ok_and(|elapsed| elapsed < LIST_CACHE_TTL);
+      if fresh && cache.developer_dir == developer_dir {
+        return Ok(cache.simulators);
+      }
+    }
+  }
+
+  let mut simulators = list()?;
+  simulators.sort_by(|a, b| a.name.cmp(&b.name));
+
+  let cache = ListCache {
+    developer_dir: developer_dir.to_path_buf(),
+    created_at: SystemTime::now(),
+    simulators,
+  };
+  // the cache is only an optimization, ignore write failures
+  let written = cache_path
+    .parent()
+    .map_or(Ok(()), std::fs::create_dir_all)
+    .and_then(|_| std::fs::write(&cache_path, serde_json::to_vec(&cache)?));
+  if let Err(e) = written {
+    log::debug!("failed to write simulator list cache: {e}");
+  }
+
+  Ok(cache.simulators)
+}
+
+/// Finds the simulator with the given name, preferring one that is already booted.
+pub fn find(name: &str) -> Result<Simulator> {
+  let mut matches = list()?
+    .into_iter()
+    .filter(|s| s.name == name)
+    .collect::<Vec<_>>();
+  matches.sort_by_key(|s| !s.is_booted());
+  matches
+    .into_iter()
+    .next()
+    .ok_or_else(|| anyhow::anyhow!("could not find simulator {name}"))
+}
+
+/// Finds the simulator with the given UDID.
+pub fn find_udid(udid: &str) -> Result<Simulator> {
+  list()?
+    .into_iter()
+    .find(|s| s.udid == udid)
+    .ok_or_else(|| anyhow::anyhow!("could not find simulator {udid}"))
+}
+
+/// Lists the available iOS simulator runtimes.
+pub fn runtimes() -> Result<Vec<Runtime>> {
+  let stdout = simctl(["list", "runtimes", "available", "--json"])?;
+  let list: RuntimeList =
+    serde_json::from_slice(&stdout).context("failed to parse simctl runtime list")?;
+  Ok(
+    list
+      .runtimes
+      .into_iter()
+      .filter(|runtime| runtime.identifier.contains("iOS"))
+      .collect(),
+  )
+}
+
+/// The installed iOS runtime with the highest version.
+pub fn latest_runtime() -> Result<Option<Runtime>> {
+  Ok(
+    runtimes()?
+      .into_iter()
+      .max_by_key(|runtime| runtime.version_components()),
+  )
+}
+
+/// Lists the iPhone and iPad simulator device types.
+pub fn device_types() -> Result<Vec<DeviceType>> {
+  let stdout = simctl(["list", "devicetypes", "--json"])?;
+  let list: DeviceTypeList =
+    serde_json::from_slice(&stdout).context("failed to parse simctl device type list")?;
+  Ok(
+    list
+      .devicetypes
+      .into_iter()
+      .filter(|device_type| {
+        device_type.identifier.contains("iPhone") || device_type.identifier.contains("iPad")
+      })
+      .collect(),
+  )
+}
+
+/// Creates a simulator of the given device type and runtime, returning its UDID.
+///
+/// The simulator is recorded when `record` is set, so it can be deleted with [`delete_created`].
+pub fn create(
+  name: &str,
+  device_type_identifier: &str,
+  runtime: &Runtime,
+  record: bool,
+) -> Result<String> {
+  let stdout = simctl([
+    "create",
+    name,
+    device_type_identifier,
+    runtime.identifier.as_str(),
+  ])
+  .with_context(|| format!("failed to create simulator {name} ({})", runtime.name))?;
+  let udid = String::from_utf8_lossy(&stdout).trim().to_string();
+  if !record {
+    return Ok(udid);
+  }
+
+  let mut created = created_simulators();
+  created.push(udid.clone());
+  let path = created_simulators_path();
+  let written = path
+    .parent()
+    .map_or(Ok(()), std::fs::create_dir_all)
+    .and_then(|_| std::fs::write(&path, serde_json::to_vec(&created)?));
+  if let Err(e) = written {
+    log::warn!("failed to record created simulator {name}: {e}");
+  }
+
+  Ok(udid)
+}
+
+/// The UDIDs of the simulators created by [`create`] that were not deleted yet.
+fn created_simulators() -> Vec<String> {
+  std::fs::read(created_simulators_path())
+    .ok()
+    .and_then(|created| serde_json::from_slice(&created).ok())
+    .unwrap_or_default()
+}
+
+/// Deletes the simulators created by [`create`], returning the deleted ones.
+pub fn delete_created() -> Result<Vec<Simulator>> {
+  let available = list()?;
+  let mut deleted = Vec::new();
+  for udid in created_simulators() {
+    // the simulator may already have been deleted by the user
+    let Some(simulator) = available.iter().find(|s| s.udid == udid) else {
+      continue;
+    };
+    if simulator.is_booted() {
+      shutdown(simulator)?;
+    }
+    simctl(["delete", udid.as_str()])
+      .with_context(|| format!("failed to delete simulator {}", simulator.name))?;
+    deleted.push(simulator.clone());
+  }
+  let _ = std::fs::remove_file(created_simulators_path());
+  Ok(deleted)
+}
+
+/// Waits until the simulator finishes booting, booting it if needed.
+pub fn wait_booted(simulator: &Simulator) -> Result<()> {
+  simctl(["bootstatus", simulator.udid.as_str(), "-b"])
+    .with_context(|| format!("failed to boot simulator {}", simulator.name))?;
+  Ok(())
+}
+
+/// Shuts down a booted simulator.
+pub fn shutdown(simulator: &Simulator) -> Result<()> {
+  simctl(["shutdown", simulator.udid.as_str()])
+    .with_context(|| format!("failed to shut down simulator {}", simulator.name))?;
+  Ok(())
+}
+
+/// Erases all content and settings of a simulator. The simulator must be shut down.
+pub fn erase(simulator: &Simulator) -> Result<()> {
+  simctl(["erase", simulator.udid.as_str()])
+    .with_context(|| format!("failed to erase simulator {}", simulator.name))?;
+  Ok(())
+}
+
+/// Sets the system appearance (light or dark mode) of a booted simulator.
+pub fn set_appearance(simulator: &Simulator, appearance: Appearance) -> Result<()> {
+  simctl([
+    "ui",
+    simulator.udid.as_str(),
+    "appearance",
+    appearance.to_string().as_str(),
+  ])
+  .with_context(|| {
+    format!(
+      "failed to set the appearance of simulator {}",
+      simulator.name
+    )
+  })?;
+  Ok(())
+}
+
+/// Resets the permission of the app for a privacy service on a booted simulator,
+/// so the app prompts for it again on its next request.
+pub fn reset_privacy(
+  simulator: &Simulator,
+  service: PrivacyService,
+  bundle_id: &str,
+) -> Result<()> {
+  simctl([
+    "privacy",
+    simulator.udid.as_str(),
+    "reset",
+    service.to_string().as_str(),
+    bundle_id,
+  ])
+  .with_context(|| {
+    format!(
+      "failed to reset the {service} permission of {bundle_id} on simulator {}",
+      simulator.name
+    )
+  })?;
+  Ok(())
+}
+
+/// Launches the installed app on a booted simulator, returning its process identifier.
+pub fn launch(simulator: &Simulator, bundle_id: &str, launch_args: &[String]) -> Result<u32> {
+  launch_with(simulator, &[], bundle_id, launch_args)
+}
+
+/// Launches the installed app on a booted simulator suspended until a debugger attaches, returning its process identifier.
+pub fn launch_waiting_for_debugger(
+  simulator: &Simulator,
+  bundle_id: &str,
+  launch_args: &[String],
+) -> Result<u32> {
+  launch_with(simulator, &["--wait-for-debugger"], bundle_id, launch_args)
+}
+
+fn launch_with(
+  simulator: &Simulator,
+  flags: &[&str],
+  bundle_id: &str,
+  launch_args: &[String],
+) -> Result<u32> {
+  let stdout = simctl(
+    ["launch"]
+      .into_iter()
+      .chain(flags.iter().copied())
+      .chain([
+        "--terminate-running-process",
+        simulator.udid.as_str(),
+        bundle_id,
+      ])
+      .chain(launch_args.iter().map(String::as_str)),
+  )
+  .with_context(|| {
+    format!(
+      "failed to launch {bundle_id} on simulator {}",
+      simulator.name
+    )
+  })?;
+  // prints `<bundle id>: <pid>`
+  let stdout = String::from_utf8_lossy(&stdout);
+  stdout
+    .trim()
+    .rsplit(": ")
+    .next()
+    .and_then(|pid| pid.parse().ok())
+    .with_context(|| {
+      format!("failed to read the process identifier of {bundle_id} from `{stdout}`")
+    })
+}
+
+/// Terminates the app running on a booted simulator.
+pub fn terminate(simulator: &Simulator, bundle_id: &str) -> Result<()> {
+  simctl(["terminate", simulator.udid.as_str(), bundle_id]).with_context(|| {
+    format!(
+      "failed to terminate {bundle_id} on simulator {}",
+      simulator.name
+    )
+  })?;
+  Ok(())
+}
+
+/// The time shown in the status bar of Apple marketing screenshots.
+pub const CLEAN_STATUS_BAR_TIME: &str = "9:41";
+
+/// Overrides the status bar of a booted simulator with full battery and signal and the given time, for clean screenshots.
+///
+/// The overrides are kept until the simulator is erased or `xcrun simctl status_bar <udid> clear` is run.
+pub fn override_status_bar(simulator: &Simulator, time: &str) -> Result<()> {
+  simctl([
+    "status_bar",
+    simulator.udid.as_str(),
+    "override",
+    "--time",
+    time,
+    "--dataNetwork",
+    "wifi",
+    "--wifiMode",
+    "active",
+    "--wifiBars",
+    "3",
+    "--cellularMode",
+    "active",
+    "--cellularBars",
+    "4",
+    "--operatorName",
+    "",
+    "--batteryState",
+    "charged",
+    "--batteryLevel",
+    "100",
+  ])
+  .with_context(|| {
+    format!(
+      "failed to override the status bar of simulator {}",
+      simulator.name
+    )
+  })?;
+  Ok(())
+}
+
+/// Parses a language tag such as `fr` or `pt-BR` for [`set_locale`].
+pub fn parse_language(language: &str) -> std::result::Result<String, String> {
+  let mut subtags = language.split(['-', '_']);
+  let valid = subtags.next().is_some_and(|primary| {
+    (2..=3).contains(&primary.len()) && primary.chars().all(|c| c.is_ascii_alphabetic())
+  }) && subtags.all(|subtag| {
+    (2..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+  });
+  if valid {
+    Ok(language.replace('_', "-"))
+  } else {
+    Err(format!(
+      "`{language}` is not a valid language, expected a language code like `fr` or `pt-BR`"
+    ))
+  }
+}
+
+/// Parses a region code such as `CA` or `419` for [`set_locale`].
+pub fn parse_region(region: &str) -> std::result::Result<String, String> {
+  let valid = (region.len() == 2 && region.chars().all(|c| c.is_ascii_alphabetic()))
+    || (region.len() == 3 && region.chars().all(|c| c.is_ascii_digit()));
+  if valid {
+    Ok(region.to_ascii_uppercase())
+  } else {
+    Err(format!(
+      "`{region}` is not a valid region, expected a region code like `CA` or `419`"
+    ))
+  }
+}
+
+/// Sets the system language and region of a booted simulator, read by the apps launched afterwards.
+///
+/// The locale keeps the current language or region when only one of them is given.
+pub fn set_locale(
+  simulator: &Simulator,
+  language: Option<&str>,
+  region: Option<&str>,
+) -> Result<()> {
+  let defaults = |args: &[&str]| {
+    simctl(
+      ["spawn", simulator.udid.as_str(), "defaults"]
+        .iter()
+        .chain(args),
+    )
+  };
+
+  if let Some(language) = language {
+    defaults(&[
+      "write",
+      "Apple Global Domain",
+      "AppleLanguages",
+      "-array",
+      language,
+    ])
+    .with_context(|| format!("failed to set the language of simulator {}", simulator.name))?;
+  }
+
+  let current = defaults(&["read", "Apple Global Domain", "AppleLocale"])
+    .map(|stdout| String::from_utf8_lossy(&stdout).trim().to_string())
+    .unwrap_or_default();
+  let locale = locale(language, region, &current);
+  defaults(&[
+    "write",
+    "Apple Global Domain",
+    "AppleLocale",
+    "-string",
+    &locale,
+  ])
+  .with_context(|| format!("failed to set the region of simulator {}", simulator.name))?;
+
+  Ok(())
+}
+
+/// Computes the `AppleLocale` value from the language tag, the region and the current locale, e.g. `fr-CA` gives `fr_CA`.
+///
+/// Only a trailing two letter or three digit subtag of the language tag is a region,
+/// so the script of `zh-Hans` is not mistaken for one.
+fn locale(language: Option<&str>, region: Option<&str>, current: &str) -> String {
+  let (current_language, current_region) = current
+    .split_once('_')
+    .map(|(language, region)| (language, Some(region)))
+    .unwrap_or((current, None));
+
+  let locale_language = language
+    .and_then(|language| language.split('-').next())
+    .filter(|language| !language.is_empty())
+    .or(Some(current_language).filter(|language| !language.is_empty()))
+    .unwrap_or("en");
+  let locale_region = region
+    .map(|region| region.to_ascii_uppercase())
+    .or_else(|| {
+      language
+        .and_then(|language| language.rsplit_once('-'))
+        .and_then(|(_, region)| parse_region(region).ok())
+    })
+    .or_else(|| current_region.map(ToString::to_string));
+  match locale_region {
+    Some(region) => format!("{locale_language}_{region}"),
+    None => locale_language.to_string(),
+  }
+}
+
+/// Saves a screenshot of the booted simulator screen to the given path.
+pub fn screenshot(simulator: &Simulator, path: &Path) -> Result<()> {
+  Command::new("xcrun")
+    .args(["simctl", "io", simulator.udid.as_str(), "screenshot"])
+    .arg(path)
+    .output_ok()
+    .with_context(|| {
+      format!(
+        "failed to capture screenshot of simulator {}",
+        simulator.name
+      )
+    })?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{locale, parse_region, runtime_matches};
+
+  #[test]
+  fn matches_runtime_with_or_without_platform() {
+    assert!(runtime_matches("iOS 16.4", "iOS 16.4"));
+    assert!(runtime_matches("iOS 16.4", "16.4"));
+    assert!(runtime_matches("iOS 16.4", "ios16.4"));
+    assert!(runtime_matches(" iOS 16.4 ", "16.4"));
+    assert!(!runtime_matches("iOS 16.4", "iOS 17.0"));
+    assert!(!runtime_matches("iOS 16.4", "16"));
+  }
+
+  #[test]
+  fn parses_region_codes() {
+    assert_eq!(parse_region("ca"), Ok("CA".into()));
+    assert_eq!(parse_region("419"), Ok("419".into()));
+    assert!(parse_region("Hans").is_err());
+    assert!(parse_region("C1").is_err());
+  }
+
+  #[test]
+  fn computes_locale_from_language_tag() {
+    assert_eq!(locale(Some("fr-CA"), None, "en_US"), "fr_CA");
+    assert_eq!(locale(Some("es-419"), None, ""), "es_419");
+    assert_eq!(locale(Some("zh-Hans"), None, "en_US"), "zh_US");
+    assert_eq!(locale(Some("zh-Hans-CN"), None, ""), "zh_CN");
+    assert_eq!(locale(Some("de"), None, ""), "de");
+  }
+
+  #[test]
+  fn keeps_current_language_or_region() {
+    assert_eq!(locale(None, Some("gb"), "en_US"), "en_GB");
+    assert_eq!(locale(Some("ja"), None, "en_US"), "ja_US");
+    assert_eq!(locale(None, Some("DE"), ""), "en_DE");
+    assert_eq!(locale(Some("pt-BR"), Some("PT"), "en_US"), "pt_PT");
+  }
+}
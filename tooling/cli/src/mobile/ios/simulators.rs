@@ -0,0 +1,45 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{simctl, xcode_developer_dir};
+use crate::Result;
+
+use clap::{Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+#[clap(
+  about = "Manage the iOS simulators created by the CLI",
+  subcommand_required(true),
+  arg_required_else_help(true)
+)]
+pub struct Cli {
+  #[clap(subcommand)]
+  command: Commands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+  /// Delete the simulators created by `tauri ios dev --create-simulator`
+  Clean,
+}
+
+pub fn command(cli: Cli) -> Result<()> {
+  match cli.command {
+    Commands::Clean => clean(),
+  }
+}
+
+fn clean() -> Result<()> {
+  let deleted = simctl::delete_created()?;
+  if deleted.is_empty() {
+    log::info!("No created simulators to delete");
+    return Ok(());
+  }
+  for simulator in &deleted {
+    log::info!(action = "Deleted"; "simulator {simulator}");
+  }
+  // the deleted simulators must not be offered from the cached list
+  simctl::list_cached(&xcode_developer_dir()?, true)?;
+  Ok(())
+}
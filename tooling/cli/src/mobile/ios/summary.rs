@@ -0,0 +1,73 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! The single line build summary printed with `--summary`, an anchor for CI scripts.
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SummaryFormat {
+  Json,
+  /// Space separated `key=value` pairs, values containing spaces are quoted.
+  KeyValue,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+  pub artifacts: Vec<PathBuf>,
+  pub bundle_id: String,
+  pub version: String,
+  /// The signing identity, `automatic` when Xcode manages signing or `none` for unsigned builds.
+  pub signing_identity: String,
+}
+
+impl Summary {
+  pub fn print(&self, format: SummaryFormat) {
+    match format {
+      SummaryFormat::Json => println!("{}", serde_json::to_string(self).unwrap()),
+      SummaryFormat::KeyValue => {
+        let artifacts = self
+          .artifacts
+          .iter()
+          .map(|path| path.display().to_string())
+          .collect::<Vec<_>>()
+          .join(",");
+        println!(
+          "artifacts={} bundle_id={} version={} signing_identity={}",
+          quote(&artifacts),
+          quote(&self.bundle_id),
+          quote(&self.version),
+          quote(&self.signing_identity)
+        );
+      }
+    }
+  }
+}
+
+fn quote(value: &str) -> String {
+  if value.is_empty() || value.contains([' ', '"']) {
+    format!("\"{}\"", value.replace('"', "\\\""))
+  } else {
+    value.to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::quote;
+
+  #[test]
+  fn quotes_values_with_spaces() {
+    assert_eq!(quote("com.tauri.app"), "com.tauri.app");
+    assert_eq!(
+      quote("Apple Distribution: Tauri (ABC123)"),
+      "\"Apple Distribution: Tauri (ABC123)\""
+    );
+    assert_eq!(quote(""), "\"\"");
+  }
+}
@@ -0,0 +1,219 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Applies the `bundle > iOS > targetEntitlements` configuration to the entitlements files of the Xcode project targets
+//! when the project is generated.
+//!
+//! The keys written to each file are recorded, so the keys removed from the configuration are removed from the files
+//! on the next generation while the keys added in Xcode, e.g. with the Signing & Capabilities editor, are kept.
+
+use super::{app_entitlements_path, find_xcode_file, read_plist_source, write_plist, ErrorKind};
+use crate::{
+  helpers::{app_paths::tauri_dir, config::Config as TauriConfig},
+  CommandExt, Result,
+};
+
+use anyhow::Context;
+use cargo_mobile2::apple::config::Config as AppleConfig;
+use plist::{Dictionary, Value};
+
+use std::{
+  collections::{BTreeMap, HashMap},
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+/// Records the entitlements file and the keys written to it for each target, see [`apply`].
+const RECORD_FILE_NAME: &str = ".target-entitlements.plist";
+
+/// The entitlements file of a target and the keys written to it.
+struct Applied {
+  path: PathBuf,
+  keys: Vec<String>,
+}
+
+/// Merges the `bundle > iOS > targetEntitlements` files into the entitlements file of each target,
+/// removing the keys written by the previous generation that are no longer configured.
+///
+/// The extension targets must exist in the Xcode project, e.g. added by the `bundle > iOS > postInitScript`.
+pub fn apply(config: &AppleConfig, tauri_config: &TauriConfig) -> Result<()> {
+  let record_path = config.project_dir().join(RECORD_FILE_NAME);
+  let previous = read_record(&record_path);
+  let configured = tauri_config
+    .bundle
+    .ios
+    .target_entitlements
+    .clone()
+    .unwrap_or_default();
+  if configured.is_empty() && previous.is_empty() {
+    return Ok(());
+  }
+
+  let tauri_dir = tauri_dir();
+  let mut target_paths = None;
+  let mut applied = BTreeMap::new();
+  for (target, file) in &configured {
+    let path = if target == config.scheme() {
+      app_entitlements_path(config)
+    } else {
+      if target_paths.is_none() {
+        target_paths = Some(entitlements_build_settings(config)?);
+      }
+      resolve_extension_path(target_paths.as_ref().unwrap(), target)?
+    };
+    let Some(entitlements) = read_plist_source(&tauri_dir.join(file))?.into_dictionary() else {
+      return Err(
+        anyhow::anyhow!("{} is not a dictionary", file.display()).context(ErrorKind::InvalidConfig),
+      );
+    };
+
+    let mut contents = read_entitlements(&path)?;
+    if let Some(previous) = previous
+      .get(target)
+      .filter(|previous| previous.path == path)
+    {
+      for key in &previous.keys {
+        if !entitlements.contains_key(key) {
+          contents.remove(key);
+        }
+      }
+    }
+    let keys = entitlements.keys().cloned().collect();
+    for (key, value) in entitlements {
+      contents.insert(key, value);
+    }
+    write_plist(&contents.into(), &path)?;
+    applied.insert(target.clone(), Applied { path, keys });
+  }
+
+  // the targets removed from the configuration, or whose entitlements file changed
+  for (target, previous) in &previous {
+    if applied
+      .get(target)
+      .is_some_and(|applied| applied.path == previous.path)
+      || !previous.path.exists()
+    {
+      continue;
+    }
+    let mut contents = read_entitlements(&previous.path)?;
+    for key in &previous.keys {
+      contents.remove(key);
+    }
+    write_plist(&contents.into(), &previous.path)?;
+  }
+
+  write_record(&record_path, &applied)
+}
+
+/// Reads the `CODE_SIGN_ENTITLEMENTS` build setting of every target of the Xcode project, keyed by the target name.
+/// The targets without entitlements file map to `None`.
+fn entitlements_build_settings(config: &AppleConfig) -> Result<HashMap<String, Option<PathBuf>>> {
+  let project_dir = config.project_dir();
+  let project = find_xcode_file(&project_dir, "xcodeproj").ok_or_else(|| {
+    anyhow::anyhow!(
+      "could not find the Xcode project in {}",
+      project_dir.display()
+    )
+    .context(ErrorKind::InvalidConfig)
+  })?;
+  let output = Command::new("xcodebuild")
+    .arg("-project")
+    .arg(&project)
+    .args(["-showBuildSettings", "-alltargets", "-json"])
+    .output_ok()
+    .context("failed to read the build settings of the Xcode project targets")?;
+  let targets: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)?;
+  Ok(
+    targets
+      .into_iter()
+      .filter_map(|target| {
+        let name = target.get("target")?.as_str()?.to_string();
+        let path = target["buildSettings"]
+          .get("CODE_SIGN_ENTITLEMENTS")
+          .and_then(|path| path.as_str())
+          .filter(|path| !path.is_empty())
+          .map(|path| project_dir.join(path));
+        Some((name, path))
+      })
+      .collect(),
+  )
+}
+
+fn resolve_extension_path(
+  target_paths: &HashMap<String, Option<PathBuf>>,
+  target: &str,
+) -> Result<PathBuf> {
+  let path = target_paths.get(target).ok_or_else(|| {
+    let mut targets = target_paths.keys().map(String::as_str).collect::<Vec<_>>();
+    targets.sort_unstable();
+    anyhow::anyhow!(
+      "unknown target `{target}` in `bundle > iOS > targetEntitlements`, the Xcode project targets are: {}",
+      targets.join(", ")
+    )
+    .context(ErrorKind::InvalidConfig)
+  })?;
+  path.clone().ok_or_else(|| {
+    anyhow::anyhow!(
+      "the `{target}` target has no entitlements file, set its Code Signing Entitlements build setting in Xcode"
+    )
+    .context(ErrorKind::InvalidConfig)
+  })
+}
+
+/// Reads an entitlements file, a missing file being empty.
+fn read_entitlements(path: &Path) -> Result<Dictionary> {
+  if !path.exists() {
+    return Ok(Dictionary::new());
+  }
+  Value::from_file(path)
+    .with_context(|| format!("failed to read plist file {}", path.display()))?
+    .into_dictionary()
+    .ok_or_else(|| anyhow::anyhow!("{} is not a dictionary", path.display()))
+}
+
+/// Reads the record of the previous generation, which is empty if it does not exist or cannot be read.
+fn read_record(path: &Path) -> HashMap<String, Applied> {
+  let Some(record) = Value::from_file(path).ok().and_then(Value::into_dictionary) else {
+    return HashMap::new();
+  };
+  record
+    .into_iter()
+    .filter_map(|(target, applied)| {
+      let applied = applied.into_dictionary()?;
+      let path = PathBuf::from(applied.get("path")?.as_string()?);
+      let keys = applied
+        .get("keys")?
+        .as_array()?
+        .iter()
+        .filter_map(|key| key.as_string().map(ToString::to_string))
+        .collect();
+      Some((target, Applied { path, keys }))
+    })
+    .collect()
+}
+
+fn write_record(path: &Path, applied: &BTreeMap<String, Applied>) -> Result<()> {
+  if applied.is_empty() {
+    if path.exists() {
+      std::fs::remove_file(path).with_context(|| format!("failed to remove {}", path.display()))?;
+    }
+    return Ok(());
+  }
+  let mut record = Dictionary::new();
+  for (target, applied) in applied {
+    let mut entry = Dictionary::new();
+    entry.insert("path".into(), applied.path.display().to_string().into());
+    entry.insert(
+      "keys".into(),
+      applied
+        .keys
+        .iter()
+        .map(|key| Value::from(key.clone()))
+        .collect::<Vec<_>>()
+        .into(),
+    );
+    record.insert(target.clone(), entry.into());
+  }
+  write_plist(&record.into(), path)
+}
@@ -0,0 +1,82 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+//! Wall-clock timings of the build phases, printed with `--timings`.
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TimingsFormat {
+  Json,
+  Plain,
+}
+
+#[derive(Serialize)]
+struct Phase {
+  name: &'static str,
+  seconds: f64,
+}
+
+pub struct Timings {
+  start: Instant,
+  last: Instant,
+  phases: Vec<(&'static str, Duration)>,
+}
+
+impl Timings {
+  pub fn new() -> Self {
+    let now = Instant::now();
+    Self {
+      start: now,
+      last: now,
+      phases: Vec::new(),
+    }
+  }
+
+  /// Records the time elapsed since the previous phase ended as the given phase.
+  ///
+  /// Phases recorded multiple times, e.g. once per target, are summed up.
+  pub fn lap(&mut self, phase: &'static str) {
+    let now = Instant::now();
+    let elapsed = now - self.last;
+    self.last = now;
+    match self.phases.iter_mut().find(|(name, _)| *name == phase) {
+      Some((_, duration)) => *duration += elapsed,
+      None => self.phases.push((phase, elapsed)),
+    }
+  }
+
+  pub fn print(&self, format: TimingsFormat) {
+    let total = self.start.elapsed();
+    match format {
+      TimingsFormat::Plain => {
+        println!("Build timings:");
+        for (name, duration) in &self.phases {
+          println!("  {name:<16} {:>8.2}s", duration.as_secs_f64());
+        }
+        println!("  {:<16} {:>8.2}s", "total", total.as_secs_f64());
+      }
+      TimingsFormat::Json => {
+        let phases = self
+          .phases
+          .iter()
+          .map(|(name, duration)| Phase {
+            name,
+            seconds: duration.as_secs_f64(),
+          })
+          .collect::<Vec<_>>();
+        println!(
+          "{}",
+          serde_json::json!({
+            "phases": phases,
+            "total": total.as_secs_f64(),
+          })
+        );
+      }
+    }
+  }
+}
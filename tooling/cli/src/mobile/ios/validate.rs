@@ -0,0 +1,255 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{apple_bundle_version, ErrorKind};
+use crate::{helpers::config::get as get_tauri_config, CommandExt, ConfigValue, Result};
+
+use anyhow::Context;
+use clap::Parser;
+use tauri_macos_sign::ProvisioningProfile;
+
+use std::{
+  fs::{read_dir, remove_dir_all},
+  ops::Deref,
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+/// Info.plist keys required by the App Store.
+const REQUIRED_INFO_PLIST_KEYS: &[&str] = &[
+  "CFBundleIdentifier",
+  "CFBundleExecutable",
+  "CFBundleShortVersionString",
+  "CFBundleVersion",
+  "MinimumOSVersion",
+];
+
+#[derive(Debug, Clone, Parser)]
+#[clap(
+  about = "Validate an IPA before distributing it",
+  long_about = "Validate an IPA before distributing it.
+Checks the bundle identifier and version against the Tauri configuration, the required Info.plist keys,
+and that the embedded provisioning profile matches the app and its code signature. App Store Connect is not involved."
+)]
+pub struct Options {
+  /// Path to the IPA to validate.
+  pub path: PathBuf,
+  /// JSON string or path to JSON file to merge with tauri.conf.json
+  #[clap(short, long)]
+  pub config: Option<ConfigValue>,
+}
+
+pub fn command(options: Options) -> Result<()> {
+  let tauri_config = get_tauri_config(
+    tauri_utils::platform::Target::Ios,
+    options.config.as_ref().map(|c| &c.0),
+  )
+  .context(ErrorKind::InvalidConfig)?;
+  let (identifier, version) = {
+    let tauri_config_guard = tauri_config.lock().unwrap();
+    let tauri_config_ = tauri_config_guard.as_ref().unwrap();
+    (
+      tauri_config_.identifier.clone(),
+      tauri_config_.version.clone(),
+    )
+  };
+  let version = version.as_deref().map(apple_bundle_version).transpose()?;
+
+  let extract_dir = extract(&options.path)?;
+  let problems =
+    validate(&extract_dir, &identifier, version.as_deref()).context(ErrorKind::ValidationFailed)?;
+
+  if problems.is_empty() {
+    log::info!(action = "Validated"; "{}", options.path.display());
+    Ok(())
+  } else {
+    for problem in &problems {
+      log::error!("{problem}");
+    }
+    Err(
+      anyhow::anyhow!(
+        "{} failed validation with {} problem(s)",
+        options.path.display(),
+        problems.len()
+      )
+      .context(ErrorKind::ValidationFailed),
+    )
+  }
+}
+
+/// The temporary directory an IPA is extracted to, removed when dropped.
+pub struct ExtractedIpa(PathBuf);
+
+impl Deref for ExtractedIpa {
+  type Target = Path;
+
+  fn deref(&self) -> &Path {
+    &self.0
+  }
+}
+
+impl Drop for ExtractedIpa {
+  fn drop(&mut self) {
+    let _ = remove_dir_all(&self.0);
+  }
+}
+
+/// Extracts the IPA to a temporary directory, removed when the returned value is dropped or the extraction fails.
+pub fn extract(ipa: &Path) -> Result<ExtractedIpa> {
+  let extract_dir = ExtractedIpa(std::env::temp_dir().join(format!(
+    "tauri-ios-validate-{}-{}",
+    std::process::id(),
+    ipa.file_stem().unwrap_or_default().to_string_lossy()
+  )));
+  Command::new("ditto")
+    .arg("-x")
+    .arg("-k")
+    .arg(ipa)
+    .arg(&*extract_dir)
+    .output_ok()
+    .with_context(|| format!("failed to extract {}", ipa.display()))
+    .context(ErrorKind::ValidationFailed)?;
+  Ok(extract_dir)
+}
+
+/// Finds the app bundle in the Payload directory of the extracted IPA.
+pub fn find_app(extract_dir: &Path) -> Result<PathBuf> {
+  read_dir(extract_dir.join("Payload"))
+    .ok()
+    .and_then(|entries| {
+      entries
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| path.extension().is_some_and(|ext| ext == "app"))
+    })
+    .ok_or_else(|| {
+      anyhow::anyhow!("the IPA does not contain an app bundle in its Payload directory")
+    })
+}
+
+/// Checks that the provisioning profile embedded in the IPA is the one the app was meant to be signed with,
+/// since Xcode may substitute another matching profile during the export.
+pub fn verify_embedded_profile(ipa: &Path, expected_uuid: &str) -> Result<()> {
+  let extract_dir = extract(ipa)?;
+  let embedded = find_app(&extract_dir).and_then(|app| {
+    let profile_path = app.join("embedded.mobileprovision");
+    if !profile_path.exists() {
+      anyhow::bail!("the app does not embed a provisioning profile");
+    }
+    let profile = ProvisioningProfile::from_path(profile_path);
+    Ok((profile.uuid()?, profile.name()?))
+  });
+  drop(extract_dir);
+  let (uuid, name) = embedded
+    .with_context(|| {
+      format!(
+        "failed to read the provisioning profile of {}",
+        ipa.display()
+      )
+    })
+    .context(ErrorKind::ValidationFailed)?;
+
+  if uuid.eq_ignore_ascii_case(expected_uuid) {
+    Ok(())
+  } else {
+    Err(
+      anyhow::anyhow!(
+        "{} embeds the provisioning profile {name} ({uuid}) instead of the configured profile {expected_uuid}. Xcode substituted another profile, check that the configured profile is installed and matches the signing certificate",
+        ipa.display()
+      )
+      .context(ErrorKind::ValidationFailed),
+    )
+  }
+}
+
+/// Validates the extracted IPA, returning the problems found.
+fn validate(extract_dir: &Path, identifier: &str, version: Option<&str>) -> Result<Vec<String>> {
+  let app = find_app(extract_dir)?;
+
+  let mut problems = Vec::new();
+
+  let info_plist_path = app.join("Info.plist");
+  let info_plist = plist::Value::from_file(&info_plist_path)
+    .with_context(|| format!("failed to read {}", info_plist_path.display()))?
+    .into_dictionary()
+    .ok_or_else(|| anyhow::anyhow!("{} is not a dictionary", info_plist_path.display()))?;
+  let info_string = |key: &str| info_plist.get(key).and_then(|v| v.as_string());
+
+  for key in REQUIRED_INFO_PLIST_KEYS {
+    if !info_plist.contains_key(*key) {
+      problems.push(format!("Info.plist is missing the required {key} key"));
+    }
+  }
+
+  let bundle_identifier = info_string("CFBundleIdentifier");
+  if let Some(bundle_identifier) = bundle_identifier {
+    if bundle_identifier != identifier {
+      problems.push(format!(
+        "bundle identifier {bundle_identifier} does not match the configured identifier {identifier}"
+      ));
+    }
+  }
+
+  if let Some(version) = version {
+    if let Some(bundle_version) = info_string("CFBundleShortVersionString") {
+      if bundle_version != version {
+        problems.push(format!(
+          "bundle version {bundle_version} does not match the configured version {version}"
+        ));
+      }
+    }
+  }
+
+  let profile_path = app.join("embedded.mobileprovision");
+  if !profile_path.exists() {
+    problems.push("the app does not embed a provisioning profile".into());
+    return Ok(problems);
+  }
+  let profile = ProvisioningProfile::from_path(profile_path);
+
+  if profile.is_expired()? {
+    problems.push(format!(
+      "the embedded provisioning profile {} expired",
+      profile.name()?
+    ));
+  }
+
+  if let Some(bundle_identifier) = bundle_identifier {
+    if !profile.matches_bundle_identifier(bundle_identifier)? {
+      problems.push(format!(
+        "the embedded provisioning profile is for {}, not {bundle_identifier}",
+        profile.application_identifier()?
+      ));
+    }
+  }
+
+  let team_ids = profile.team_ids()?;
+  match signing_team_id(&app)? {
+    Some(team_id) if team_ids.contains(&team_id) => {}
+    Some(team_id) => problems.push(format!(
+      "the app is signed by team {team_id} but the embedded provisioning profile belongs to {}",
+      team_ids.join(", ")
+    )),
+    None => problems.push("the app is not code signed".into()),
+  }
+
+  Ok(problems)
+}
+
+/// Reads the team identifier from the code signature of the app.
+fn signing_team_id(app: &Path) -> Result<Option<String>> {
+  let output = Command::new("codesign")
+    .args(["--display", "--verbose=2"])
+    .arg(app)
+    .output()
+    .context("failed to run codesign")?;
+  // codesign writes the signature details to stderr
+  Ok(
+    String::from_utf8_lossy(&output.stderr)
+      .lines()
+      .find_map(|line| line.strip_prefix("TeamIdentifier="))
+      .filter(|team_id| *team_id != "not set")
+      .map(ToString::to_string),
+  )
+}
@@ -0,0 +1,231 @@
+// Copyright 2019-2024 Tauri Programme within The Commons Conservancy
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-License-Identifier: MIT
+
+use super::{env, get_app, get_config, is_simulator_triple, ErrorKind};
+use crate::{
+  helpers::{app_paths::tauri_dir, config::get as get_tauri_config},
+  interface::{AppInterface, AppSettings, Interface, Options as InterfaceOptions},
+  CommandExt, ConfigValue, Result,
+};
+
+use anyhow::Context;
+use cargo_mobile2::{
+  apple::target::Target,
+  opts::{NoiseLevel, Profile},
+  target::TargetTrait,
+};
+use clap::{ArgAction, Parser};
+
+use std::{
+  collections::HashMap,
+  env::set_current_dir,
+  ffi::OsStr,
+  fs::{create_dir_all, remove_dir_all},
+  path::{Path, PathBuf},
+  process::Command,
+};
+
+#[derive(Debug, Clone, Parser)]
+#[clap(
+  about = "Build the Rust library of the app as an XCFramework",
+  long_about = "Build the Rust library of the app for the device and simulator targets and package it as an XCFramework, to embed it in other native apps.
+The simulator slices are merged into a single universal library."
+)]
+pub struct Options {
+  /// Path of the XCFramework to create, e.g. `dist/MyApp.xcframework`.
+  #[clap(short, long)]
+  pub output: PathBuf,
+  /// Builds with the debug flag
+  #[clap(short, long)]
+  pub debug: bool,
+  /// Which targets to build. Defaults to all targets.
+  #[clap(
+    short,
+    long = "target",
+    action = ArgAction::Append,
+    num_args(0..),
+    value_parser(clap::builder::PossibleValuesParser::new(Target::name_list()))
+  )]
+  pub targets: Vec<String>,
+  /// List of cargo features to activate
+  #[clap(short, long, action = ArgAction::Append, num_args(0..))]
+  pub features: Option<Vec<String>>,
+  /// JSON string or path to JSON file to merge with tauri.conf.json
+  #[clap(short, long)]
+  pub config: Option<ConfigValue>,
+  /// Directory of C headers to include in each library of the XCFramework.
+  #[clap(long)]
+  pub headers: Option<PathBuf>,
+}
+
+pub fn command(options: Options, noise_level: NoiseLevel) -> Result<()> {
+  super::ensure_xcode()?;
+  let profile = if options.debug {
+    Profile::Debug
+  } else {
+    Profile::Release
+  };
+
+  let targets = if options.targets.is_empty() {
+    Target::all().values().collect::<Vec<_>>()
+  } else {
+    options
+      .targets
+      .iter()
+      .map(|target| Target::all().get(target.as_str()).unwrap())
+      .collect()
+  };
+
+  let tauri_config = get_tauri_config(
+    tauri_utils::platform::Target::Ios,
+    options.config.as_ref().map(|c| &c.0),
+  )
+  .context(ErrorKind::InvalidConfig)?;
+  let (config, metadata) = {
+    let tauri_config_guard = tauri_config.lock().unwrap();
+    let tauri_config_ = tauri_config_guard.as_ref().unwrap();
+    let interface = AppInterface::new(tauri_config_, None)?;
+    let app = get_app(tauri_config_, &interface);
+    get_config(
+      &app,
+      tauri_config_,
+      options.features.as_ref(),
+      &Default::default(),
+      Some(profile),
+      Default::default(),
+    )?
+  };
+
+  set_current_dir(tauri_dir()).context("failed to change current working directory")?;
+  let env = env()?;
+  let lib_name = format!("lib{}.a", config.app().lib_name());
+
+  let mut device_libs = Vec::new();
+  let mut simulator_libs = Vec::new();
+  for target in targets {
+    let sdk = if is_simulator_triple(target.triple) {
+      "iphonesimulator"
+    } else {
+      "iphoneos"
+    };
+    let sdk_root = sdk_path(sdk)?;
+    let isysroot = format!("-isysroot {}", sdk_root.display());
+    let env_triple = target.triple.replace('-', "_");
+    let cflags = format!("CFLAGS_{env_triple}");
+    let cxxflags = format!("CXXFLAGS_{env_triple}");
+    let mut target_env = HashMap::<&str, &OsStr>::new();
+    target_env.insert("RUST_BACKTRACE", "1".as_ref());
+    target_env.insert(cflags.as_str(), isysroot.as_ref());
+    target_env.insert(cxxflags.as_str(), isysroot.as_ref());
+
+    log::info!(action = "Building"; "Rust library for {}", target.triple);
+    target
+      .compile_lib(
+        &config,
+        &metadata,
+        noise_level,
+        true,
+        profile,
+        &env,
+        target_env,
+      )
+      .context(ErrorKind::BuildFailed)?;
+
+    let interface = AppInterface::new(
+      tauri_config.lock().unwrap().as_ref().unwrap(),
+      Some(target.triple.into()),
+    )?;
+    let bin_path = interface
+      .app_settings()
+      .app_binary_path(&InterfaceOptions {
+        debug: options.debug,
+        target: Some(target.triple.into()),
+        ..Default::default()
+      })?;
+    let lib_path = bin_path.parent().unwrap().join(&lib_name);
+    if !lib_path.exists() {
+      return Err(anyhow::anyhow!("Library not found at {}. Make sure your Cargo.toml file has a [lib] block with `crate-type = [\"staticlib\", \"cdylib\", \"lib\"]`", lib_path.display()).context(ErrorKind::BuildFailed));
+    }
+
+    if sdk == "iphonesimulator" {
+      simulator_libs.push(lib_path);
+    } else {
+      device_libs.push(lib_path);
+    }
+  }
+
+  // an XCFramework has a single library per platform, so the simulator architectures are merged
+  let work_dir = std::env::temp_dir().join(format!("tauri-ios-xcframework-{}", std::process::id()));
+  let _ = remove_dir_all(&work_dir);
+  let mut libraries = device_libs;
+  if simulator_libs.len() > 1 {
+    let universal_dir = work_dir.join("simulator");
+    create_dir_all(&universal_dir)?;
+    let universal = universal_dir.join(&lib_name);
+    Command::new("lipo")
+      .arg("-create")
+      .args(&simulator_libs)
+      .arg("-output")
+      .arg(&universal)
+      .output_ok()
+      .context("failed to merge the simulator libraries with lipo")
+      .context(ErrorKind::BuildFailed)?;
+    libraries.push(universal);
+  } else {
+    libraries.extend(simulator_libs);
+  }
+
+  let result = create_xcframework(&libraries, options.headers.as_deref(), &options.output);
+  let _ = remove_dir_all(&work_dir);
+  result?;
+
+  log::info!(action = "Created"; "XCFramework at {}", options.output.display());
+  Ok(())
+}
+
+/// Reads the path of the installed SDK, e.g. `iphoneos`.
+fn sdk_path(sdk: &str) -> Result<PathBuf> {
+  let output = Command::new("xcrun")
+    .args(["--sdk", sdk, "--show-sdk-path"])
+    .output_ok()
+    .with_context(|| format!("failed to find the {sdk} SDK"))
+    .context(ErrorKind::XcodeMissing)?;
+  Ok(PathBuf::from(
+    String::from_utf8_lossy(&output.stdout).trim(),
+  ))
+}
+
+fn create_xcframework(libraries: &[PathBuf], headers: Option<&Path>, output: &Path) -> Result<()> {
+  if let Some(headers) = headers {
+    if !headers.is_dir() {
+      return Err(
+        anyhow::anyhow!("headers directory {} does not exist", headers.display())
+          .context(ErrorKind::InvalidConfig),
+      );
+    }
+  }
+  // xcodebuild refuses to overwrite an existing XCFramework
+  if output.exists() {
+    remove_dir_all(output).with_context(|| format!("failed to remove {}", output.display()))?;
+  }
+  if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+    create_dir_all(parent)?;
+  }
+
+  let mut command = Command::new("xcodebuild");
+  command.arg("-create-xcframework");
+  for library in libraries {
+    command.arg("-library").arg(library);
+    if let Some(headers) = headers {
+      command.arg("-headers").arg(headers);
+    }
+  }
+  command
+    .arg("-output")
+    .arg(output)
+    .output_ok()
+    .context("failed to create the XCFramework")
+    .context(ErrorKind::BuildFailed)?;
+  Ok(())
+}
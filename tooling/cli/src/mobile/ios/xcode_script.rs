@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use super::{ensure_init, env, get_app, get_config, read_options, MobileTarget};
+use super::{
+  catalyst, ensure_init, env, get_app, get_config, project_env_vars, read_options,
+  suppress_development_team_warning, MobileTarget,
+};
 use crate::{
   helpers::config::get as get_tauri_config,
   interface::{AppInterface, AppSettings, Interface, Options as InterfaceOptions},
@@ -11,6 +14,7 @@ use crate::{
 
 use cargo_mobile2::{apple::target::Target, opts::Profile};
 use clap::Parser;
+use tauri_utils::config::parse::folder_has_configuration_file;
 
 use std::{
   collections::HashMap,
@@ -61,13 +65,34 @@ pub fn command(options: Options) -> Result<()> {
     }
   }
 
-  // `xcode-script` is ran from the `gen/apple` folder when not using NPM.
+  // `xcode-script` is ran from the Xcode project folder (`gen/apple` by default) when not using NPM.
   if var_os("npm_lifecycle_event").is_none() && var_os("PNPM_PACKAGE_NAME").is_none() {
-    set_current_dir(current_dir()?.parent().unwrap().parent().unwrap()).unwrap();
+    let cwd = current_dir()?;
+    let tauri_dir = cwd
+      .ancestors()
+      .find(|dir| {
+        folder_has_configuration_file(tauri_utils::platform::Target::Ios, dir)
+          || folder_has_configuration_file(
+            tauri_utils::platform::Target::Ios,
+            &dir.join("src-tauri"),
+          )
+      })
+      .ok_or_else(|| {
+        anyhow::anyhow!(
+          "could not find the Tauri directory from the Xcode project folder {}",
+          cwd.display()
+        )
+      })?;
+    set_current_dir(tauri_dir)?;
   }
 
+  // the development team warning was already logged by the command that started the Xcode build
+  suppress_development_team_warning();
+
   let profile = profile_from_configuration(&options.configuration);
-  let macos = macos_from_platform(&options.platform);
+  // Xcode reports the macOS platform for Mac Catalyst builds too
+  let catalyst = catalyst::is_xcode_building();
+  let macos = !catalyst && macos_from_platform(&options.platform);
 
   let tauri_config = get_tauri_config(tauri_utils::platform::Target::Ios, None)?;
 
@@ -80,7 +105,9 @@ pub fn command(options: Options) -> Result<()> {
       tauri_config_,
       None,
       &cli_options,
-    );
+      Some(profile),
+      Default::default(),
+    )?;
     (config, metadata, cli_options)
   };
   ensure_init(
@@ -90,7 +117,9 @@ pub fn command(options: Options) -> Result<()> {
     MobileTarget::Ios,
   )?;
 
-  let env = env()?.explicit_env_vars(cli_options.vars);
+  let mut vars = cli_options.vars;
+  vars.extend(project_env_vars(&config));
+  let env = env()?.explicit_env_vars(vars);
 
   if !options.sdk_root.is_dir() {
     return Err(anyhow::anyhow!(
@@ -145,10 +174,26 @@ pub fn command(options: Options) -> Result<()> {
   let macos_target = Target::macos();
 
   let isysroot = format!("-isysroot {}", options.sdk_root.display());
+  // cargo rejects `--release` combined with the `--profile` of `--cargo-profile`
+  let compile_profile = if cli_options
+    .args
+    .iter()
+    .any(|arg| arg.starts_with("--profile"))
+  {
+    Profile::Debug
+  } else {
+    profile
+  };
 
   for arch in options.arches {
     // Set target-specific flags
     let (env_triple, rust_triple) = match arch.as_str() {
+      _ if catalyst => catalyst::rust_triple(&arch).ok_or_else(|| {
+        anyhow::anyhow!(
+          "Arch specified by Xcode was invalid. {} isn't a known Mac Catalyst arch",
+          arch
+        )
+      })?,
       "arm64" => ("aarch64_apple_ios", "aarch64-apple-ios"),
       "arm64-sim" => ("aarch64_apple_ios_sim", "aarch64-apple-ios-sim"),
       "x86_64" => ("x86_64_apple_ios", "x86_64-apple-ios"),
@@ -178,8 +223,16 @@ pub fn command(options: Options) -> Result<()> {
     target_env.insert(cxxflags.as_ref(), isysroot.as_ref());
     target_env.insert(objc_include_path.as_ref(), include_dir.as_ref());
 
+    let catalyst_target;
     let target = if macos {
       &macos_target
+    } else if catalyst {
+      // the iOS target of the same arch, compiled for the Mac Catalyst triple
+      catalyst_target = Target {
+        triple: rust_triple,
+        ..Target::for_arch(&arch).unwrap().clone()
+      };
+      &catalyst_target
     } else {
       Target::for_arch(&arch).ok_or_else(|| {
         anyhow::anyhow!(
@@ -193,7 +246,7 @@ pub fn command(options: Options) -> Result<()> {
       &metadata,
       cli_options.noise_level,
       true,
-      profile,
+      compile_profile,
       &env,
       target_env,
     )?;
@@ -203,6 +256,7 @@ pub fn command(options: Options) -> Result<()> {
       .app_binary_path(&InterfaceOptions {
         debug: matches!(profile, Profile::Debug),
         target: Some(rust_triple.into()),
+        args: cli_options.args.clone(),
         ..Default::default()
       })?;
     let out_dir = bin_path.parent().unwrap();
@@ -215,7 +269,14 @@ pub fn command(options: Options) -> Result<()> {
     validate_lib(&lib_path)?;
 
     let project_dir = config.project_dir();
-    let externals_lib_dir = project_dir.join(format!("Externals/{arch}/{}", profile.as_str()));
+    // the Mac Catalyst libraries must not replace the iOS device library of the same arch
+    let externals_arch = if catalyst {
+      format!("{arch}-maccatalyst")
+    } else {
+      arch.clone()
+    };
+    let externals_lib_dir =
+      project_dir.join(format!("Externals/{externals_arch}/{}", profile.as_str()));
     std::fs::create_dir_all(&externals_lib_dir)?;
     std::fs::copy(
       lib_path,
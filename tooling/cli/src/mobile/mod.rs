@@ -51,7 +51,28 @@ mod init;
 #[cfg(target_os = "macos")]
 pub mod ios;
 
-const MIN_DEVICE_MATCH_SCORE: isize = 0;
+const DEFAULT_MIN_DEVICE_MATCH_SCORE: isize = 0;
+const MAX_DEVICE_MATCH_SCORE: isize = 1000;
+
+/// The minimum fuzzy match score a device name must reach to be selected by the `device` argument.
+///
+/// The score grows with the number of matched characters, consecutive matches and word starts,
+/// so the default of `0` accepts any partial match. It can be tuned with the
+/// `TAURI_CLI_DEVICE_MATCH_SCORE` environment variable, clamped between 0 and 1000.
+fn min_device_match_score() -> isize {
+  match std::env::var("TAURI_CLI_DEVICE_MATCH_SCORE") {
+    Ok(score) => match score.parse::<isize>() {
+      Ok(score) => score.clamp(0, MAX_DEVICE_MATCH_SCORE),
+      Err(_) => {
+        log::warn!(
+          "Ignoring invalid `TAURI_CLI_DEVICE_MATCH_SCORE` value {score}, it must be an integer"
+        );
+        DEFAULT_MIN_DEVICE_MATCH_SCORE
+      }
+    },
+    Err(_) => DEFAULT_MIN_DEVICE_MATCH_SCORE,
+  }
+}
 
 #[derive(Clone)]
 pub struct DevChild {
@@ -330,6 +351,25 @@ fn ensure_init(
         project_outdated_reasons
           .push("you have modified your [lib.name] or [package.name] in the Cargo.toml file");
       }
+
+      if tauri_config_.bundle.ios.device_families.is_some()
+        && !project_yml.contains(&format!(
+          "TARGETED_DEVICE_FAMILY: \"{}\"",
+          ios::targeted_device_family(tauri_config_)?
+        ))
+      {
+        project_outdated_reasons.push(
+          "you have modified your \"bundle > iOS > deviceFamilies\" in the Tauri configuration",
+        );
+      }
+
+      if let Some(enable_bitcode) = tauri_config_.bundle.ios.enable_bitcode {
+        if !project_yml.contains(&format!("ENABLE_BITCODE: {enable_bitcode}")) {
+          project_outdated_reasons.push(
+            "you have modified your \"bundle > iOS > enableBitcode\" in the Tauri configuration",
+          );
+        }
+      }
     }
   }
 
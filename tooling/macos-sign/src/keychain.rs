@@ -6,6 +6,7 @@ use std::{
   ffi::OsString,
   path::{Path, PathBuf},
   process::Command,
+  time::Duration,
 };
 
 use crate::assert_command;
@@ -161,6 +162,30 @@ impl Keychain {
     })
   }
 
+  /// Sets how long the keychain created by [`Self::with_certificate`] stays unlocked, defaults to one hour.
+  /// `None` disables the auto-lock, so the keychain stays unlocked until it is deleted.
+  ///
+  /// The settings of the default keychain are left untouched.
+  pub fn set_lock_timeout(&self, timeout: Option<Duration>) -> Result<()> {
+    let Some(path) = &self.path else {
+      return Ok(());
+    };
+    let mut command = Command::new("security");
+    command.arg("set-keychain-settings");
+    // without options the keychain never locks automatically
+    if let Some(timeout) = timeout {
+      command
+        .arg("-t")
+        .arg(timeout.as_secs().to_string())
+        .arg("-u");
+    }
+    assert_command(
+      command.arg(path).status(),
+      "failed to set keychain lock timeout",
+    )?;
+    Ok(())
+  }
+
   pub fn signing_identity(&self) -> String {
     match &self.signing_identity {
       SigningIdentity::Team(t) => t.certificate_name(),
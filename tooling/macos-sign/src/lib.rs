@@ -15,7 +15,7 @@ mod keychain;
 mod provisioning_profile;
 
 pub use keychain::{Keychain, Team};
-pub use provisioning_profile::ProvisioningProfile;
+pub use provisioning_profile::{ProfileType, ProvisioningProfile};
 
 pub enum ApiKey {
   Path(PathBuf),
@@ -2,13 +2,41 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-License-Identifier: MIT
 
-use std::{ffi::OsStr, path::PathBuf, process::Command};
+use std::{
+  cell::OnceCell,
+  ffi::OsStr,
+  path::{Path, PathBuf},
+  process::Command,
+  time::SystemTime,
+};
 
 use anyhow::{Context, Result};
 use rand::distributions::{Alphanumeric, DistString};
 
+/// The distribution method a provisioning profile is meant for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileType {
+  Development,
+  AdHoc,
+  AppStore,
+  Enterprise,
+}
+
+impl std::fmt::Display for ProfileType {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Development => write!(f, "development"),
+      Self::AdHoc => write!(f, "ad-hoc"),
+      Self::AppStore => write!(f, "app-store"),
+      Self::Enterprise => write!(f, "enterprise"),
+    }
+  }
+}
+
 pub struct ProvisioningProfile {
   path: PathBuf,
+  /// The decoded plist dictionary, cached so `security cms` runs once per profile.
+  decoded: OnceCell<plist::Dictionary>,
 }
 
 impl ProvisioningProfile {
@@ -28,10 +56,58 @@ impl ProvisioningProfile {
 
     Ok(Self {
       path: provisioning_profile_path,
+      decoded: OnceCell::new(),
     })
   }
 
-  pub fn uuid(&self) -> Result<String> {
+  /// Loads an existing provisioning profile file, e.g. the `embedded.mobileprovision` of an app bundle.
+  pub fn from_path(path: impl Into<PathBuf>) -> Self {
+    Self {
+      path: path.into(),
+      decoded: OnceCell::new(),
+    }
+  }
+
+  /// Lists the provisioning profiles installed for the current user, including the ones managed by Xcode.
+  pub fn installed() -> Result<Vec<Self>> {
+    let home_dir = dirs_next::home_dir().context("could not find the home directory")?;
+    let folders = [
+      home_dir
+        .join("Library")
+        .join("MobileDevice")
+        .join("Provisioning Profiles"),
+      home_dir
+        .join("Library")
+        .join("Developer")
+        .join("Xcode")
+        .join("UserData")
+        .join("Provisioning Profiles"),
+    ];
+
+    let mut profiles = Vec::new();
+    for folder in folders.iter().filter(|folder| folder.is_dir()) {
+      // an unreadable entry must not prevent listing the other profiles
+      for entry in std::fs::read_dir(folder)?.flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "mobileprovision") {
+          profiles.push(Self::from_path(path));
+        }
+      }
+    }
+    Ok(profiles)
+  }
+
+  /// The path of the provisioning profile file.
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  /// Decodes the provisioning profile CMS message into its plist dictionary, on first use.
+  fn decode(&self) -> Result<&plist::Dictionary> {
+    if let Some(decoded) = self.decoded.get() {
+      return Ok(decoded);
+    }
+
     let output = Command::new("security")
       .args(["cms", "-D", "-i"])
       .arg(&self.path)
@@ -41,12 +117,160 @@ impl ProvisioningProfile {
       return Err(anyhow::anyhow!("failed to decode provisioning profile"));
     }
 
-    let plist = plist::from_bytes::<plist::Dictionary>(&output.stdout)
+    let decoded = plist::from_bytes::<plist::Dictionary>(&output.stdout)
       .context("failed to decode provisioning profile as plist")?;
+    Ok(self.decoded.get_or_init(|| decoded))
+  }
 
-    plist
+  pub fn uuid(&self) -> Result<String> {
+    self
+      .decode()?
       .get("UUID")
       .and_then(|v| v.as_string().map(ToString::to_string))
       .ok_or_else(|| anyhow::anyhow!("could not find provisioning profile UUID"))
   }
+
+  /// The name of the provisioning profile.
+  pub fn name(&self) -> Result<String> {
+    self
+      .decode()?
+      .get("Name")
+      .and_then(|v| v.as_string().map(ToString::to_string))
+      .ok_or_else(|| anyhow::anyhow!("could not find provisioning profile name"))
+  }
+
+  /// The expiration date of the provisioning profile, in the RFC 3339 format.
+  pub fn expiration_date(&self) -> Result<String> {
+    self
+      .decode()?
+      .get("ExpirationDate")
+      .and_then(|v| v.as_date().map(|d| d.to_xml_format()))
+      .ok_or_else(|| anyhow::anyhow!("could not find provisioning profile expiration date"))
+  }
+
+  /// Whether the provisioning profile expired.
+  pub fn is_expired(&self) -> Result<bool> {
+    Ok(self.expires_at()? < SystemTime::now())
+  }
+
+  /// The time the provisioning profile expires at.
+  pub fn expires_at(&self) -> Result<SystemTime> {
+    self
+      .decode()?
+      .get("ExpirationDate")
+      .and_then(|v| v.as_date())
+      .map(SystemTime::from)
+      .ok_or_else(|| anyhow::anyhow!("could not find provisioning profile expiration date"))
+  }
+
+  /// The identifiers of the teams the provisioning profile belongs to.
+  pub fn team_ids(&self) -> Result<Vec<String>> {
+    self
+      .decode()?
+      .get("TeamIdentifier")
+      .and_then(|v| v.as_array())
+      .map(|teams| {
+        teams
+          .iter()
+          .filter_map(|t| t.as_string().map(ToString::to_string))
+          .collect()
+      })
+      .ok_or_else(|| anyhow::anyhow!("could not find provisioning profile team identifier"))
+  }
+
+  /// The `application-identifier` entitlement, `<team ID>.<bundle identifier>`,
+  /// where the bundle identifier can end with a `*` wildcard.
+  pub fn application_identifier(&self) -> Result<String> {
+    self
+      .decode()?
+      .get("Entitlements")
+      .and_then(|v| v.as_dictionary())
+      .and_then(|entitlements| entitlements.get("application-identifier"))
+      .and_then(|v| v.as_string().map(ToString::to_string))
+      .ok_or_else(|| anyhow::anyhow!("could not find provisioning profile application identifier"))
+  }
+
+  /// Whether the provisioning profile can sign the app with the given bundle identifier,
+  /// taking wildcard application identifiers into account.
+  pub fn matches_bundle_identifier(&self, bundle_identifier: &str) -> Result<bool> {
+    let application_identifier = self.application_identifier()?;
+    // the application identifier is prefixed with the team ID
+    let Some((_team_id, identifier)) = application_identifier.split_once('.') else {
+      return Ok(false);
+    };
+    Ok(match identifier.strip_suffix('*') {
+      Some(prefix) => bundle_identifier.starts_with(prefix),
+      None => bundle_identifier == identifier,
+    })
+  }
+
+  /// The distribution method of the provisioning profile.
+  pub fn profile_type(&self) -> Result<ProfileType> {
+    let profile = self.decode()?;
+    let get_task_allow = profile
+      .get("Entitlements")
+      .and_then(|v| v.as_dictionary())
+      .and_then(|entitlements| entitlements.get("get-task-allow"))
+      .and_then(|v| v.as_boolean())
+      .unwrap_or(false);
+    let provisions_all_devices = profile
+      .get("ProvisionsAllDevices")
+      .and_then(|v| v.as_boolean())
+      .unwrap_or(false);
+
+    Ok(if provisions_all_devices {
+      ProfileType::Enterprise
+    } else if profile.contains_key("ProvisionedDevices") {
+      if get_task_allow {
+        ProfileType::Development
+      } else {
+        ProfileType::AdHoc
+      }
+    } else {
+      ProfileType::AppStore
+    })
+  }
+
+  /// The platforms the provisioning profile is valid for, e.g. `iOS`, `xrOS` or `OSX`.
+  pub fn platforms(&self) -> Result<Vec<String>> {
+    Ok(
+      self
+        .decode()?
+        .get("Platform")
+        .and_then(|v| v.as_array())
+        .map(|platforms| {
+          platforms
+            .iter()
+            .filter_map(|p| p.as_string().map(ToString::to_string))
+            .collect()
+        })
+        .unwrap_or_default(),
+    )
+  }
+
+  /// Whether the provisioning profile is valid for the given platform, e.g. `iOS`.
+  /// Profiles that do not list their platforms are considered valid for every platform.
+  pub fn supports_platform(&self, platform: &str) -> Result<bool> {
+    let platforms = self.platforms()?;
+    Ok(platforms.is_empty() || platforms.iter().any(|p| p == platform))
+  }
+
+  /// The UDIDs of the devices this profile can be installed on.
+  ///
+  /// Returns `None` when the profile is not restricted to a list of devices,
+  /// which is the case for App Store and enterprise distribution profiles.
+  pub fn provisioned_devices(&self) -> Result<Option<Vec<String>>> {
+    Ok(
+      self
+        .decode()?
+        .get("ProvisionedDevices")
+        .and_then(|v| v.as_array())
+        .map(|devices| {
+          devices
+            .iter()
+            .filter_map(|d| d.as_string().map(ToString::to_string))
+            .collect()
+        }),
+    )
+  }
 }